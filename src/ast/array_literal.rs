@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use crate::ast::{ASTEnum, AstId};
+use crate::compiler::visit::{Parsed, Phase};
+use crate::{impl_ast_node, new_ast_id};
+use crate::lexer::token::PositionRange;
+
+#[derive(Serialize)]
+pub struct ArrayLiteral<P: Phase = Parsed> {
+    pub elements: Vec<ASTEnum<P>>,
+    position: PositionRange,
+    id: AstId,
+}
+
+impl ArrayLiteral {
+    pub fn new(elements: Vec<ASTEnum>, position: PositionRange) -> Self {
+        Self {
+            elements,
+            position,
+            id: new_ast_id!(),
+        }
+    }
+}
+
+impl_ast_node!(ArrayLiteral, visit_array_literal);
@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+use crate::lexer::token::Located;
+
+// `#[...]` written in front of a function or global
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub enum Attribute {
+    // `#[entry]`, the function the startup code calls into, emitted as `main`
+    Entry,
+    // `#[section("name")]`, places the item in the named section of the object file
+    Section(String),
+}
+
+impl Attribute {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Entry => "entry",
+            Attribute::Section(_) => "section",
+        }
+    }
+}
+
+pub fn is_entry(attributes: &[Located<Attribute>]) -> bool {
+    attributes.iter().any(|attribute| **attribute == Attribute::Entry)
+}
+
+pub fn section(attributes: &[Located<Attribute>]) -> Option<&str> {
+    attributes.iter().find_map(|attribute| match &**attribute {
+        Attribute::Section(name) => Some(name.as_str()),
+        _ => None,
+    })
+}
@@ -10,6 +10,7 @@ pub enum ExitType {
     Return,
     Result,
     Break,
+    Continue,
 }
 
 #[derive(Serialize)]
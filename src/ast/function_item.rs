@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-use crate::ast::{ASTNode, DeclarationExpr};
+use crate::ast::{ASTNode, Attribute, DeclarationExpr, Visibility};
 use crate::ast::{block_expr::BlockExpr, parsed_type::ParsedType, AstId};
 use crate::compiler::visit::{Parsed, Phase};
 use crate::{impl_ast_node, new_ast_id};
@@ -8,6 +8,8 @@ use crate::lexer::token::{Located, PositionRange};
 
 #[derive(Serialize)]
 pub struct FunctionItem<P: Phase = Parsed> {
+    pub attributes: Vec<Located<Attribute>>,
+    pub visibility: Visibility,
     pub name: Located<String>,
     pub parameters: Vec<DeclarationExpr<P>>,
     pub return_type: ParsedType,
@@ -17,8 +19,10 @@ pub struct FunctionItem<P: Phase = Parsed> {
 }
 
 impl FunctionItem {
-    pub fn new(name: Located<String>, parameters: Vec<DeclarationExpr>, return_type: ParsedType, body: Option<BlockExpr>, position: PositionRange) -> Self {
+    pub fn new(attributes: Vec<Located<Attribute>>, visibility: Visibility, name: Located<String>, parameters: Vec<DeclarationExpr>, return_type: ParsedType, body: Option<BlockExpr>, position: PositionRange) -> Self {
         Self {
+            attributes,
+            visibility,
             name,
             parameters,
             return_type,
@@ -0,0 +1,43 @@
+use serde::Serialize;
+
+use crate::ast::{ASTEnum, AstId, Attribute, ParsedType, Visibility};
+use crate::compiler::visit::{Parsed, Phase};
+use crate::{impl_ast_node, new_ast_id};
+use crate::lexer::token::{Located, PositionRange};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalKind {
+    // Evaluated at compile time and never written
+    Const,
+    // A mutable global with a static initializer
+    Static,
+}
+
+#[derive(Serialize)]
+pub struct GlobalItem<P: Phase = Parsed> {
+    pub attributes: Vec<Located<Attribute>>,
+    pub visibility: Visibility,
+    pub kind: GlobalKind,
+    pub name: Located<String>,
+    pub declaration_type: ParsedType,
+    pub expr: ASTEnum<P>,
+    position: PositionRange,
+    id: AstId,
+}
+
+impl GlobalItem {
+    pub fn new(attributes: Vec<Located<Attribute>>, visibility: Visibility, kind: GlobalKind, name: Located<String>, declaration_type: ParsedType, expr: ASTEnum, position: PositionRange) -> Self {
+        Self {
+            attributes,
+            visibility,
+            kind,
+            name,
+            declaration_type,
+            expr,
+            position,
+            id: new_ast_id!(),
+        }
+    }
+}
+
+impl_ast_node!(GlobalItem, visit_global);
@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+use crate::ast::{AstId, DeclarationExpr};
+use crate::ast::{block_expr::BlockExpr, parsed_type::ParsedType};
+use crate::compiler::visit::{Parsed, Phase};
+use crate::{impl_ast_node, new_ast_id};
+use crate::lexer::token::PositionRange;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CaptureMode {
+    Value, Reference,
+}
+
+#[derive(Serialize)]
+pub struct LambdaExpr<P: Phase = Parsed> {
+    pub parameters: Vec<DeclarationExpr<P>>,
+    pub return_type: ParsedType,
+    pub body: BlockExpr<P>,
+    pub capture_mode: CaptureMode,
+    position: PositionRange,
+    id: AstId,
+}
+
+impl LambdaExpr {
+    pub fn new(parameters: Vec<DeclarationExpr>, return_type: ParsedType, body: BlockExpr, capture_mode: CaptureMode, position: PositionRange) -> Self {
+        Self {
+            parameters,
+            return_type,
+            body,
+            capture_mode,
+            position,
+            id: new_ast_id!(),
+        }
+    }
+}
+
+impl_ast_node!(LambdaExpr, visit_lambda);
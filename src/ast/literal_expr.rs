@@ -3,12 +3,14 @@ use serde::Serialize;
 use crate::ast::{ASTNode, AstId};
 use crate::compiler::visit::{Parsed, Phase};
 use crate::{impl_ast_node, new_ast_id};
-use crate::lexer::token::PositionRange;
+use crate::lexer::token::{NumericType, PositionRange};
 
 #[derive(Serialize)]
 pub enum Literal {    
     Int (i64),
     Double (f64),
+    SizedInt (u64, NumericType),
+    SizedFloat (f64, NumericType),
     Bool (bool),
     Char (char),
     String (String),
@@ -1,4 +1,5 @@
 mod assignment_expr;
+mod attribute;
 mod binary_expr;
 mod block_expr;
 mod cast;
@@ -8,22 +9,28 @@ mod declaration_expr;
 mod delete_expr;
 mod exit_expr;
 mod function_item;
+mod global_item;
 mod if_expr;
 mod impl_item;
+mod lambda_expr;
 mod literal_expr;
 mod loop_expr;
 mod struct_access;
 mod array_access;
+mod array_literal;
 mod function_access;
 mod new_array_expr;
 mod parsed_type;
 mod path;
 mod scope;
 mod struct_item;
+mod struct_literal;
 mod unary_expr;
 mod var_expr;
+mod visibility;
 
 pub use assignment_expr::AssignmentExpr;
+pub use attribute::{Attribute, is_entry, section};
 pub use binary_expr::{BinaryExpr, BinaryOperator};
 pub use block_expr::BlockExpr;
 pub use cast::CastExpr;
@@ -33,11 +40,14 @@ pub use declaration_expr::DeclarationExpr;
 pub use delete_expr::DeleteExpr;
 pub use exit_expr::{ExitExpr, ExitType};
 pub use function_item::FunctionItem;
+pub use global_item::{GlobalItem, GlobalKind};
 pub use if_expr::IfExpr;
 pub use impl_item::ImplItem;
+pub use lambda_expr::{CaptureMode, LambdaExpr};
 pub use literal_expr::{Literal, LiteralExpr};
 pub use loop_expr::LoopExpr;
 pub use array_access::ArrayAccess;
+pub use array_literal::ArrayLiteral;
 pub use function_access::FunctionAccess;
 pub use struct_access::StructAccess;
 pub use new_array_expr::NewArrayExpr;
@@ -45,8 +55,10 @@ pub use parsed_type::{ParsedType, ParsedTypeEnum};
 pub use path::Path;
 pub use scope::{Require, Scope};
 pub use struct_item::StructItem;
+pub use struct_literal::StructLiteral;
 pub use unary_expr::{UnaryExpr, UnaryOperator};
 pub use var_expr::VarExpr;
+pub use visibility::Visibility;
 
 
 use serde::Serialize;
@@ -72,6 +84,7 @@ pub trait ASTNode<P: Phase>: Positioned + Send + Sync + Serialize {
 #[derive(Serialize)]
 pub enum ASTEnum<P: Phase = Parsed> {
     ArrayAccess(Box<ArrayAccess<P>>),
+    ArrayLiteral(Box<ArrayLiteral<P>>),
     Assignment(Box<AssignmentExpr<P>>),
     Binary(Box<BinaryExpr<P>>),
     Block(Box<BlockExpr<P>>),
@@ -83,14 +96,17 @@ pub enum ASTEnum<P: Phase = Parsed> {
     Exit(Box<ExitExpr<P>>),
     Function(Box<FunctionItem<P>>),
     FunctionAccess(Box<FunctionAccess<P>>),
+    Global(Box<GlobalItem<P>>),
     If(Box<IfExpr<P>>),
     Impl(Box<ImplItem<P>>),
+    Lambda(Box<LambdaExpr<P>>),
     Literal(Box<LiteralExpr<P>>),
     Loop(Box<LoopExpr<P>>),
     NewArray(Box<NewArrayExpr<P>>),
     Scope(Box<Scope<P>>),
     Struct(Box<StructItem<P>>),
     StructAccess(Box<StructAccess<P>>),
+    StructLiteral(Box<StructLiteral<P>>),
     Unary(Box<UnaryExpr<P>>),
     Var(Box<VarExpr<P>>)
 }
@@ -101,6 +117,12 @@ impl<P: Phase> From<Box<ArrayAccess<P>>> for ASTEnum<P> {
     }
 }
 
+impl<P: Phase> From<Box<ArrayLiteral<P>>> for ASTEnum<P> {
+    fn from(node: Box<ArrayLiteral<P>>) -> Self {
+        ASTEnum::ArrayLiteral(node)
+    }
+}
+
 impl<P: Phase> From<Box<AssignmentExpr<P>>> for ASTEnum<P> {
     fn from(node: Box<AssignmentExpr<P>>) -> Self {
         ASTEnum::Assignment(node)
@@ -167,6 +189,12 @@ impl<P: Phase> From<Box<FunctionAccess<P>>> for ASTEnum<P> {
     }
 }
 
+impl<P: Phase> From<Box<GlobalItem<P>>> for ASTEnum<P> {
+    fn from(node: Box<GlobalItem<P>>) -> Self {
+        ASTEnum::Global(node)
+    }
+}
+
 impl<P: Phase> From<Box<IfExpr<P>>> for ASTEnum<P> {
     fn from(node: Box<IfExpr<P>>) -> Self {
         ASTEnum::If(node)
@@ -179,6 +207,12 @@ impl<P: Phase> From<Box<ImplItem<P>>> for ASTEnum<P> {
     }
 }
 
+impl<P: Phase> From<Box<LambdaExpr<P>>> for ASTEnum<P> {
+    fn from(node: Box<LambdaExpr<P>>) -> Self {
+        ASTEnum::Lambda(node)
+    }
+}
+
 impl<P: Phase> From<Box<LiteralExpr<P>>> for ASTEnum<P> {
     fn from(node: Box<LiteralExpr<P>>) -> Self {
         ASTEnum::Literal(node)
@@ -215,6 +249,12 @@ impl<P: Phase> From<Box<StructAccess<P>>> for ASTEnum<P> {
     }
 }
 
+impl<P: Phase> From<Box<StructLiteral<P>>> for ASTEnum<P> {
+    fn from(node: Box<StructLiteral<P>>) -> Self {
+        ASTEnum::StructLiteral(node)
+    }
+}
+
 impl<P: Phase> From<Box<UnaryExpr<P>>> for ASTEnum<P> {
     fn from(node: Box<UnaryExpr<P>>) -> Self {
         ASTEnum::Unary(node)
@@ -231,6 +271,7 @@ impl<P: Phase> Positioned for ASTEnum<P> {
     fn get_position(&self) -> &crate::lexer::token::PositionRange {
         match self {
             Self::ArrayAccess(node) => node.get_position(),
+            Self::ArrayLiteral(node) => node.get_position(),
             Self::Assignment(node) => node.get_position(),
             Self::Binary(node) => node.get_position(),
             Self::Block(node) => node.get_position(),
@@ -242,14 +283,17 @@ impl<P: Phase> Positioned for ASTEnum<P> {
             Self::Exit(node) => node.get_position(),
             Self::Function(node) => node.get_position(),
             Self::FunctionAccess(node) => node.get_position(),
+            Self::Global(node) => node.get_position(),
             Self::If(node) => node.get_position(),
             Self::Impl(node) => node.get_position(),
+            Self::Lambda(node) => node.get_position(),
             Self::Literal(node) => node.get_position(),
             Self::Loop(node) => node.get_position(),
             Self::NewArray(node) => node.get_position(),
             Self::Scope(node) => node.get_position(),
             Self::Struct(node) => node.get_position(),
             Self::StructAccess(node) => node.get_position(),
+            Self::StructLiteral(node) => node.get_position(),
             Self::Unary(node) => node.get_position(),
             Self::Var(node) => node.get_position(),
         }
@@ -260,6 +304,7 @@ impl<P: Phase> ASTNode<P> for ASTEnum<P> {
     fn get_id(&self) -> AstId {
         match self {
             Self::ArrayAccess(node) => node.get_id(),
+            Self::ArrayLiteral(node) => node.get_id(),
             Self::Assignment(node) => node.get_id(),
             Self::Binary(node) => node.get_id(),
             Self::Block(node) => node.get_id(),
@@ -271,20 +316,35 @@ impl<P: Phase> ASTNode<P> for ASTEnum<P> {
             Self::Exit(node) => node.get_id(),
             Self::Function(node) => node.get_id(),
             Self::FunctionAccess(node) => node.get_id(),
+            Self::Global(node) => node.get_id(),
             Self::If(node) => node.get_id(),
             Self::Impl(node) => node.get_id(),
+            Self::Lambda(node) => node.get_id(),
             Self::Literal(node) => node.get_id(),
             Self::Loop(node) => node.get_id(),
             Self::NewArray(node) => node.get_id(),
             Self::Scope(node) => node.get_id(),
             Self::Struct(node) => node.get_id(),
             Self::StructAccess(node) => node.get_id(),
+            Self::StructLiteral(node) => node.get_id(),
             Self::Unary(node) => node.get_id(),
             Self::Var(node) => node.get_id(),
         }
     }
 }
 
+impl<P: Phase> ASTEnum<P> {
+    // Whether the expression makes a new value, rather than naming one that lives in a variable or in memory
+    pub fn is_temporary(&self) -> bool {
+        match self {
+            Self::Var(_) | Self::ArrayAccess(_) | Self::StructAccess(_) | Self::Assignment(_) => false,
+            Self::Unary(unary) => !matches!(unary.operator, UnaryOperator::Deref),
+            Self::Cast(cast) => cast.expr.is_temporary(),
+            _ => true,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! new_ast_id {
     () => {
@@ -1,15 +1,21 @@
 use serde::Serialize;
 
+use crate::ast::Path;
 use crate::impl_positioned;
-use crate::lexer::token::PositionRange;
+use crate::lexer::token::{NumericType, PositionRange};
 
 #[derive(Serialize, Clone, PartialEq, Eq, Debug)]
 pub enum ParsedTypeEnum {
-    Integer, Double, Boolean, Char, Void,
+    Integer, Double, Boolean, Char, Str, Void,
+    Numeric(NumericType),
     TypeName(String),
     Pointer(Box<ParsedType>),
     Reference(Box<ParsedType>),
     Array(Box<ParsedType>),
+    FixedArray(Box<ParsedType>, usize),
+    // A fixed-size array whose length is a named constant, e.g. `u8[BUFFER_SIZE]`
+    ConstArray(Box<ParsedType>, Path),
+    Function(Vec<ParsedType>, Box<ParsedType>),
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -33,6 +39,44 @@ impl ParsedType {
             position,
         }
     }
+
+    // The constants naming array lengths anywhere in this type
+    pub fn array_lengths(&self) -> Vec<&Path> {
+        match &self.parsed_type {
+            ParsedTypeEnum::Pointer(inner) | ParsedTypeEnum::Reference(inner) | ParsedTypeEnum::Array(inner) | ParsedTypeEnum::FixedArray(inner, _) => {
+                inner.array_lengths()
+            },
+            ParsedTypeEnum::ConstArray(inner, length) => {
+                let mut lengths = inner.array_lengths();
+                lengths.push(length);
+                lengths
+            },
+            ParsedTypeEnum::Function(param_types, return_type) => {
+                param_types.iter()
+                    .chain(std::iter::once(return_type.as_ref()))
+                    .flat_map(|parsed_type| parsed_type.array_lengths())
+                    .collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    // The struct names used anywhere in this type
+    pub fn type_names(&self) -> Vec<&String> {
+        match &self.parsed_type {
+            ParsedTypeEnum::Pointer(inner) | ParsedTypeEnum::Reference(inner) | ParsedTypeEnum::Array(inner) | ParsedTypeEnum::FixedArray(inner, _) | ParsedTypeEnum::ConstArray(inner, _) => {
+                inner.type_names()
+            },
+            ParsedTypeEnum::Function(param_types, return_type) => {
+                param_types.iter()
+                    .chain(std::iter::once(return_type.as_ref()))
+                    .flat_map(|parsed_type| parsed_type.type_names())
+                    .collect()
+            },
+            ParsedTypeEnum::TypeName(type_name) => vec![type_name],
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl_positioned!(ParsedType);
@@ -4,7 +4,7 @@ use serde::Serialize;
 
 use crate::lexer::token::{Located, PositionRange};
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Debug)]
 pub struct Path {
     // Store segments and their locations separately for easier access
     pub segments: Vec<String>,
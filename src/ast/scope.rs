@@ -6,6 +6,8 @@ use crate::{ast::{ASTEnum, ASTNode, AstId, Path}, compiler::visit::{Parsed, Phas
 pub struct Require {
     pub path: Located<Path>,
     pub alias: Option<Located<String>>, 
+    // `from path require *;` imports every public item of the scope at `path`
+    pub glob: bool,
 }
 
 impl Require {
@@ -13,6 +15,15 @@ impl Require {
         Self {
             path,
             alias,
+            glob: false,
+        }
+    }
+
+    pub fn new_glob(path: Located<Path>) -> Self {
+        Self {
+            path,
+            alias: None,
+            glob: true,
         }
     }
 }
@@ -1,30 +1,40 @@
 use serde::Serialize;
 
-use crate::ast::{constructor_item::ConstructorItem, parsed_type::ParsedType, AstId};
+use crate::ast::{constructor_item::ConstructorItem, parsed_type::ParsedType, ASTEnum, AstId, Visibility};
 use crate::compiler::visit::{Parsed, Phase};
 use crate::{impl_ast_node, new_ast_id};
 use crate::lexer::token::{Located, PositionRange};
 
+// Members with an initializer take it as their default value in struct literals
+pub type StructMember<P = Parsed> = (Visibility, ParsedType, Located<String>, Option<ASTEnum<P>>);
+
 #[derive(Serialize)]
 pub struct StructItem<P: Phase = Parsed> {
+    pub visibility: Visibility,
     pub name: Located<String>,
-    pub members: Vec<(ParsedType, Located<String>)>,
+    pub members: Vec<StructMember<P>>,
     pub constructors: Vec<ConstructorItem<P>>,
+    // `~ { ... }`, kept as a constructor without parameters that runs when a value is dropped
+    pub destructor: Option<ConstructorItem<P>>,
     position: PositionRange,
     id: AstId,
 }
 
 impl StructItem {
     pub fn new(
+        visibility: Visibility,
         name: Located<String>,
-        members: Vec<(ParsedType, Located<String>)>,
+        members: Vec<StructMember>,
         constructors: Vec<ConstructorItem>,
+        destructor: Option<ConstructorItem>,
         position: PositionRange,
     ) -> Self {
         Self {
+            visibility,
             name,
             members,
             constructors,
+            destructor,
             position,
             id: new_ast_id!(),
         }
@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use crate::ast::{ASTEnum, AstId};
+use crate::compiler::visit::{Parsed, Phase};
+use crate::{impl_ast_node, new_ast_id};
+use crate::lexer::token::{Located, PositionRange};
+
+#[derive(Serialize)]
+pub struct StructLiteral<P: Phase = Parsed> {
+    pub type_name: Located<String>,
+    // Fields in the order they were written, members left out take their default value
+    pub fields: Vec<(Located<String>, ASTEnum<P>)>,
+    position: PositionRange,
+    id: AstId,
+}
+
+impl StructLiteral {
+    pub fn new(type_name: Located<String>, fields: Vec<(Located<String>, ASTEnum)>, position: PositionRange) -> Self {
+        Self {
+            type_name,
+            fields,
+            position,
+            id: new_ast_id!(),
+        }
+    }
+}
+
+impl_ast_node!(StructLiteral, visit_struct_literal);
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+// Whether an item can be reached from outside the scope that declares it
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Visibility {
+    // Only visible to the declaring scope and the scopes nested inside it
+    Private,
+    // Marked `pub`, visible to every scope
+    Public,
+}
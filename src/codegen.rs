@@ -1,995 +1,3279 @@
-use core::str;
-use std::{collections::{HashMap, VecDeque}, f32::consts::E, ffi::OsStr, hash::Hash, path::Path, process::Command, sync::MutexGuard};
+use std::{collections::HashMap, env, fs::{self, DirBuilder}, path::{Path, PathBuf}, process::{self, Command}, time::{SystemTime, UNIX_EPOCH}};
 
-use inkwell::{AddressSpace, basic_block::BasicBlock, builder::Builder, context::Context, llvm_sys::prelude::LLVMValueRef, module::Module, types::BasicTypeEnum, values::{AnyValueEnum, AsValueRef, BasicValue, BasicValueEnum, FunctionValue, IntValue, PointerValue}};
+use inkwell::{AddressSpace, FloatPredicate, IntPredicate, OptimizationLevel, basic_block::BasicBlock, builder::Builder, context::Context, intrinsics::Intrinsic, llvm_sys::prelude::LLVMValueRef, module::{Linkage, Module}, passes::PassBuilderOptions, targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple}, types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum}, values::{AsValueRef, BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue, GlobalValue, IntValue, PointerValue, StructValue}};
 
-use crate::{ast::*, logger::{Log, LogTarget}, resolver::{FunctionType, GlobalSymbolTable, ResolvedType, SymbolTable, TypeArena}};
+use crate::{ast::*, compiler::{Allocator, Compiler, Emit, FunctionType, NumericCast, OptLevel, ResolvedType, SymbolTable, TypeId, const_eval::ConstValue, visit::{ASTVisitor, AcceptsASTVisitor, LocalResolved, PhaseWitness, VisitResult}}, lexer::token::{NumericType, PositionRange, Positioned}, logger::{Log, LogTarget}};
 
-pub struct CodeGen<'ctx> {
+impl PhaseWitness<LocalResolved> for CodeGenWitness {}
+
+pub type CodeGenResult = VisitResult<LocalResolved, CodeGenWitness>;
+
+pub struct CodeGenWitness {
+    _private: (),
+}
+
+// A loop or block expression being generated, along with the values of the exits taken out of it so far
+struct ExitFrame<'ctx, 'ast> {
+    label: Option<&'ast String>,
+    exit_block: BasicBlock<'ctx>,
+    // Where `continue` goes, only loops have one
+    continue_block: Option<BasicBlock<'ctx>>,
+    values: Vec<(BasicBlock<'ctx>, BasicValueEnum<'ctx>)>,
+    // Drop scopes that were open on entry, exiting drops the ones opened since
+    drop_depth: usize,
+    // Temporaries that were alive on entry, exiting cleans up the ones made since
+    temporary_depth: usize,
+}
+
+// What happens to a value when its owner is done with it
+#[derive(Clone, Copy, PartialEq)]
+enum Cleanup<'ctx> {
+    // A struct with a destructor runs it, on the address of the value
+    Destroy(FunctionValue<'ctx>),
+    // A function value gives up its reference to the closure environment
+    Release,
+}
+
+// A local variable that owns its value, cleaned up when the block declaring it is left
+#[derive(Clone, Copy)]
+struct OwnedLocal<'ctx> {
+    slot: PointerValue<'ctx>,
+    cleanup: Cleanup<'ctx>,
+    // Set while a struct local holds a value, which it doesn't before its first assignment or after a move
+    drop_flag: Option<PointerValue<'ctx>>,
+}
+
+// Lowers the checked scopes of the whole program into a single LLVM module. Every item is declared
+// before any body is generated, so that the bodies of one scope can use the items of any other.
+pub struct CodeGen<'ctx, 'ast> {
     log_target: &'ctx dyn LogTarget,
     context: &'ctx Context,
     module: Module<'ctx>,
     builder: Builder<'ctx>,
+    compiler: &'ctx Compiler<'ast>,
+    // Symbol table and source file of the scope being generated
+    symbol_table: Option<&'ctx SymbolTable<'ast>>,
+    source_file: String,
     value: LLVMValueRef,
     local_vars: HashMap<AstId, PointerValue<'ctx>>,
-    break_block: HashMap<String, BasicBlock<'ctx>>,
-    result_block: Option<BasicBlock<'ctx>>,
-    global_table: &'ctx GlobalSymbolTable,
-    symbol_table: &'ctx SymbolTable,
-    break_values: HashMap<String, Vec<(BasicBlock<'ctx>, LLVMValueRef)>>,
-    functions: HashMap<String, FunctionValue<'ctx>>,
-    foreign_functions: HashMap<Vec<String>, FunctionValue<'ctx>>,
-    struct_types: HashMap<String, inkwell::types::StructType<'ctx>>,
-    struct_field_maps: HashMap<String, HashMap<String, u32>>,
-    loop_labels: VecDeque<String>,
+    functions: HashMap<AstId, FunctionValue<'ctx>>,
+    globals: HashMap<AstId, (GlobalValue<'ctx>, TypeId)>,
+    struct_types: HashMap<TypeId, inkwell::types::StructType<'ctx>>,
+    struct_field_maps: HashMap<TypeId, HashMap<String, u32>>,
+    // Declaration of every struct with the symbol table of its scope, which default member values are generated in
+    struct_items: HashMap<TypeId, (&'ast StructItem<LocalResolved>, &'ctx SymbolTable<'ast>)>,
+    // Constructors are generated as `void Foo.ctor.N(Foo *self, ...)`, keyed by the constructor's id
+    constructors: HashMap<AstId, (FunctionValue<'ctx>, Vec<TypeId>)>,
+    destructors: HashMap<TypeId, FunctionValue<'ctx>>,
+    // Locals that own their value, keyed by their declaration
+    owned_locals: HashMap<AstId, OwnedLocal<'ctx>>,
+    // Owned locals of every block being generated, innermost last
+    drop_scopes: Vec<Vec<AstId>>,
+    // Values made by the full expressions being generated that nothing took ownership of, cleaned up at their end.
+    // The address of a struct to destroy, or the environment of a function value.
+    temporaries: Vec<(PointerValue<'ctx>, Cleanup<'ctx>)>,
+    // Enclosing loops and block expressions, innermost last. Set aside while a function body is generated.
+    loops: Vec<ExitFrame<'ctx, 'ast>>,
+    blocks: Vec<ExitFrame<'ctx, 'ast>>,
+    // Set right before visiting an expression whose address is wanted instead of its value
     lvalue_mode: bool,
 }
 
-impl Log for CodeGen<'_> {
+// Bytes in front of every allocation made while the memory sanitizer is enabled, keeps payloads 16 byte aligned
+const SANITIZER_HEADER_SIZE: u64 = 32;
+
+// Fields of a closure environment in front of the captures: its reference count and its drop function
+const CLOSURE_ENV_HEADER: u32 = 2;
+
+impl Log for CodeGen<'_, '_> {
     fn get_source(&self) -> String {
         "CodeGen".to_string()
     }
 }
 
-impl<'ctx> CodeGen<'ctx> {
-    unsafe fn get_basic_value(&self, resolved_type: &ResolvedType, value: LLVMValueRef) -> BasicValueEnum<'_> {
-        match resolved_type {
-            ResolvedType::Boolean | ResolvedType::Integer | ResolvedType::Char => {
-                unsafe { IntValue::new(value).into() }
-            },
-            ResolvedType::Pointer(_) => {
-                unsafe { IntValue::new(value).into() }
-            },
-            _ => unimplemented!("Type not implemented"),
-        }
+impl<'ctx, 'ast> CodeGen<'ctx, 'ast> {
+    fn witness(&mut self, node: &impl ASTNode<LocalResolved>) -> CodeGenResult {
+        VisitResult::new(node.get_id(), CodeGenWitness { _private: () })
     }
 
-    fn get_int_type(&self, resolved_type: &ResolvedType) -> inkwell::types::IntType<'_> {
-        match resolved_type {
-            ResolvedType::Boolean => self.context.bool_type(),
-            ResolvedType::Integer => self.context.i32_type(),
-            ResolvedType::Char => self.context.i8_type(),
-            _ => unimplemented!("Type not implemented"),
-        }
+    fn resolved_type(&self, type_id: TypeId) -> ResolvedType {
+        self.compiler.type_arena.get(type_id).clone()
     }
 
-    fn get_type(&self, resolved_type: &ResolvedType) -> BasicTypeEnum<'ctx> {
-        match resolved_type {
-            ResolvedType::Boolean => self.context.bool_type().into(),
-            ResolvedType::Integer => self.context.i32_type().into(),
-            ResolvedType::Char => self.context.i8_type().into(),
-            ResolvedType::Struct(struct_type) => {
-                self.struct_types.get(&struct_type.name).unwrap().clone().into()
-            }
-            ResolvedType::Pointer(_) => self.context.ptr_type(AddressSpace::default()).into(),
-            _ => unimplemented!("Type not implemented"),
-        }
+    fn symbol_table(&self) -> &'ctx SymbolTable<'ast> {
+        self.symbol_table.expect("No scope is being generated")
     }
 
-    fn make_function(&self, fn_type: &FunctionType) -> inkwell::types::FunctionType<'ctx> {
-        let mut param_types = Vec::new();
-
-        for &parameter in &fn_type.param_types {
-            let param_type = self.global_table.type_arena.get(parameter);
-            match *param_type {
-                ResolvedType::Integer => param_types.push(self.context.i32_type().into()),
-                ResolvedType::Char => param_types.push(self.context.i8_type().into()),
-                ResolvedType::Pointer(_) => param_types.push(self.context.ptr_type(AddressSpace::default()).into()),
-                _ => unimplemented!("Parameter type not implemented"),
-            }
-        }
+    fn ast_type(&self, node: &impl ASTNode<LocalResolved>) -> TypeId {
+        *self.symbol_table().ast_types.get(&node.get_id()).unwrap()
+    }
 
-        let ret_type_id = fn_type.return_type;
-        let ret_type = self.global_table.type_arena.get(fn_type.return_type);
-        
-        match *ret_type {
-            ResolvedType::Integer => self.context.i32_type().fn_type(&param_types, false),
-            ResolvedType::Void => self.context.void_type().fn_type(&param_types, false),
-            ResolvedType::Char => self.context.i8_type().fn_type(&param_types, false),
-            ResolvedType::Pointer(_) => self.context.ptr_type(AddressSpace::default()).fn_type(&param_types, false),
-            _ => unimplemented!("Return type not implemented"),
+    fn function_type(&self, type_id: TypeId) -> FunctionType {
+        match self.resolved_type(type_id) {
+            ResolvedType::Function(function_type) => function_type,
+            _ => panic!("Expected function type"),
         }
     }
-}
-
-impl<'ctx> CodeGen<'ctx> {
-    pub fn new(log_target: &'ctx dyn LogTarget, context: &'ctx Context, global_table: &'ctx GlobalSymbolTable, symbol_table: &'ctx SymbolTable) -> Self {
-        let module = context.create_module("main_module");
-        let builder = context.create_builder();
-        
-        let mut functions = HashMap::new();
 
-        let i32_type = context.i32_type();
+    fn current_function(&self) -> FunctionValue<'ctx> {
+        self.builder.get_insert_block().unwrap().get_parent().unwrap()
+    }
 
-        Self {
-            log_target,
-            context,
-            module,
-            builder,
-            value: std::ptr::null_mut(),
-            local_vars: HashMap::new(),
-            break_block: HashMap::new(),
-            result_block: None,
-            global_table,
-            symbol_table,
-            break_values: HashMap::new(),
-            functions,
-            foreign_functions: HashMap::new(),
-            struct_types: HashMap::new(),
-            struct_field_maps: HashMap::new(),
-            loop_labels: VecDeque::new(),
-            lvalue_mode: false,
+    fn get_int_type(&self, type_id: TypeId) -> inkwell::types::IntType<'ctx> {
+        match self.resolved_type(type_id) {
+            ResolvedType::Boolean => self.context.bool_type(),
+            ResolvedType::Integer => self.context.i32_type(),
+            ResolvedType::Char => self.context.i8_type(),
+            ResolvedType::Numeric(numeric_type) if !numeric_type.is_float() => match numeric_type.bit_width() {
+                8 => self.context.i8_type(),
+                16 => self.context.i16_type(),
+                32 => self.context.i32_type(),
+                _ => self.context.i64_type(),
+            },
+            _ => panic!("Expected integer type"),
         }
     }
 
-    pub fn output_ll(&self, file_name: impl AsRef<OsStr>) {
-        self.module.print_to_file(Path::new(&file_name)).unwrap();
+    fn get_float_type(&self, type_id: TypeId) -> inkwell::types::FloatType<'ctx> {
+        match self.resolved_type(type_id).numeric_type() {
+            Some(NumericType::F32) => self.context.f32_type(),
+            Some(NumericType::F64) => self.context.f64_type(),
+            _ => panic!("Expected float type"),
+        }
     }
 
-    pub fn compile_with_clang(&self, output_file: impl AsRef<OsStr>) -> Result<(), String> {
-        // First, write the LLVM IR to a file
-        self.module.print_to_file(Path::new("output.ll"))
-            .map_err(|e| format!("Failed to write LLVM IR: {}", e))?;
+    fn is_float(&self, type_id: TypeId) -> bool {
+        self.compiler.type_arena.get(type_id).numeric_type().is_some_and(|numeric_type| numeric_type.is_float())
+    }
 
-        // Compile the LLVM IR to an executable using clang
-        let output = Command::new("clang")
-            .arg("output.ll")
-            .arg("-o")
-            .arg(&output_file)
-            .output()
-            .map_err(|e| format!("Failed to execute clang: {}", e))?;
+    // Integer ops default to signed for int, char and bool
+    fn is_unsigned(&self, type_id: TypeId) -> bool {
+        self.compiler.type_arena.get(type_id).numeric_type().is_some_and(|numeric_type| !numeric_type.is_signed())
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Clang compilation failed:\n{}", stderr));
+    fn get_type(&self, type_id: TypeId) -> BasicTypeEnum<'ctx> {
+        match self.resolved_type(type_id) {
+            ResolvedType::Boolean | ResolvedType::Integer | ResolvedType::Char => self.get_int_type(type_id).into(),
+            ResolvedType::Numeric(numeric_type) if !numeric_type.is_float() => self.get_int_type(type_id).into(),
+            ResolvedType::Double | ResolvedType::Numeric(_) => self.get_float_type(type_id).into(),
+            ResolvedType::Struct(_) => self.struct_types[&type_id].into(),
+            // A reference is the address of the value it is bound to
+            ResolvedType::Pointer(_) | ResolvedType::Reference(_) => self.context.ptr_type(AddressSpace::default()).into(),
+            ResolvedType::Function(_) => self.closure_type().into(),
+            ResolvedType::Str => self.str_type().into(),
+            ResolvedType::Array(_) => self.slice_type().into(),
+            ResolvedType::FixedArray(element_type_id, length) => self.get_type(element_type_id).array_type(length as u32).into(),
+            ResolvedType::Void => panic!("Void has no values"),
         }
+    }
 
-        Ok(())
+    fn slice_type(&self) -> inkwell::types::StructType<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        self.context.struct_type(&[ptr_type.into(), self.context.i32_type().into()], false)
     }
-}
 
-impl ASTVisitor<'_, ()> for CodeGen<'_> {
-    fn visit_binary(&mut self, node: &BinaryExpr) {
-        node.left.accept_visitor(self);
-        let left_value = self.value;
-        
-        node.right.accept_visitor(self);
-        let right_value = self.value;
-        
-        match node.operator {
-            BinaryOperator::Plus => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_add(left, right, "addtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Minus => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_sub(left, right, "subtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Times => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_mul(left, right, "multmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Divide => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_signed_div(left, right, "divtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Equal => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_compare(
-                    inkwell::IntPredicate::EQ,
-                    left,
-                    right,
-                    "eqtmp",
-                ).unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::NotEqual => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_compare(
-                    inkwell::IntPredicate::NE,
-                    left,
-                    right,
-                    "netmp",
-                ).unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Less => { 
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_compare(
-                    inkwell::IntPredicate::SLT,
-                    left,
-                    right,
-                    "lttmp",
-                ).unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::LessEqual => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_compare(
-                    inkwell::IntPredicate::SLE,
-                    left,
-                    right,
-                    "letmp",
-                ).unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Greater => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_compare(
-                    inkwell::IntPredicate::SGT,
-                    left,
-                    right,
-                    "gttmp",
-                ).unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::GreaterEqual => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_compare(
-                    inkwell::IntPredicate::SGE,
-                    left,
-                    right,
-                    "getmp",
-                ).unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::BitwiseAnd => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_and(left, right, "andtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::BitwiseOr => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_or(left, right, "ortmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Modulo => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_int_signed_rem(left, right, "modtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::Or => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_or(left, right, "ortmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            BinaryOperator::And => {
-                let left = unsafe { IntValue::new(left_value) };
-                let right = unsafe { IntValue::new(right_value) };
-                let result = self.builder.build_and(left, right, "andtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            _ => {
-                println!("Operator {} not implemented", node.operator);
-                unimplemented!("Binary operator not implemented")
-            }
-        }
+    // str is laid out as a char slice
+    fn str_type(&self) -> inkwell::types::StructType<'ctx> {
+        self.slice_type()
     }
 
-    fn visit_cast(&mut self, node: &CastExpr) {
-        node.expr.accept_visitor(self);
+    fn build_slice(&self, ptr: PointerValue<'ctx>, len: IntValue<'ctx>) -> StructValue<'ctx> {
+        let slice = self.builder.build_insert_value(self.slice_type().get_undef(), ptr, 0, "sliceptr").unwrap();
+        self.builder.build_insert_value(slice, len, 1, "slicelen").unwrap().into_struct_value()
+    }
 
-        let target_type = self.global_table.type_arena.get(*self.symbol_table.ast_types.get(&node.get_id()).unwrap());
-        let int_val = unsafe { IntValue::new(self.value) };
+    // The bytes live in a private constant global (still NUL-terminated for C interop). Built without
+    // instructions, so that it can initialize globals as well.
+    fn const_str(&self, value: &str) -> StructValue<'ctx> {
+        let bytes = self.context.const_string(value.as_bytes(), true);
 
-        self.value = self.builder.build_int_cast(int_val, self.get_int_type(&target_type), "casttmp").unwrap().as_value_ref();
-    }
+        let global = self.module.add_global(bytes.get_type(), None, "str");
+        global.set_linkage(Linkage::Private);
+        global.set_initializer(&bytes);
+        global.set_constant(true);
 
-    fn visit_unary(&mut self, node: &UnaryExpr) {
-        match node.operator {
-            UnaryOperator::Negative => {
-                node.expr.accept_visitor(self);
-                let expr = unsafe { IntValue::new(self.value) };
-                let zero = self.context.i32_type().const_int(0, false);
-                let result = self.builder.build_int_sub(zero, expr, "negtmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            UnaryOperator::Not => {
-                node.expr.accept_visitor(self);
-                let expr = unsafe { IntValue::new(self.value) };
-                let one = self.context.i32_type().const_int(1, false);
-                let result = self.builder.build_xor(expr, one, "nottmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            UnaryOperator::BitwiseNot => {
-                node.expr.accept_visitor(self);
-                let expr = unsafe { IntValue::new(self.value) };
-                let all_ones = self.context.i32_type().const_all_ones();
-                let result = self.builder.build_xor(expr, all_ones, "bitwisenottmp").unwrap();
-                self.value = result.as_value_ref();
-            },
-            UnaryOperator::Deref => {
-                if self.lvalue_mode {
-                    self.lvalue_mode = false;
-                    node.expr.accept_visitor(self);
-                } else {
-                    node.expr.accept_visitor(self);
-                    let ptr = unsafe { PointerValue::new(self.value) };
-                    let loaded = self.builder.build_load(
-                        self.context.i32_type(),
-                        ptr,
-                        "derefload",
-                    ).unwrap();
-                    self.value = loaded.as_value_ref();
-                }
-            },
-            UnaryOperator::AddressOf => {
-                self.lvalue_mode = true;
-                node.expr.accept_visitor(self);
-                self.lvalue_mode = false;
-            },
-            _ => unimplemented!("Unary operator not implemented"),
-        }
+        let len = self.context.i32_type().const_int(value.len() as u64, false);
+        self.str_type().const_named_struct(&[global.as_pointer_value().into(), len.into()])
     }
 
-    fn visit_literal(&mut self, node: &LiteralExpr) {
-        match &node.value {
-            Literal::Int(i) => {
-                let int_type = self.context.i32_type();
-                let int_value = int_type.const_int(*i as u64, false);
-                self.value = int_value.as_value_ref();
-            },
-            Literal::Char(c) => {
-                let int_type = self.context.i8_type();
-                let int_value = int_type.const_int(*c as u64, false);
-                self.value = int_value.as_value_ref();
-            },
-            Literal::String(s) => {
-                let string_value = self.builder.build_global_string_ptr(s, "strtmp").unwrap();
-                self.value = string_value.as_value_ref();
-            }
-            _ => unimplemented!("Literal type not implemented"),
+    // Generates an array index or size, which can be of any integer type, widened to i64
+    fn build_index(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> IntValue<'ctx> {
+        let value = self.build_value(expr).into_int_value();
+        let is_signed = self.resolved_type(self.ast_type(expr)).numeric_type().is_none_or(|numeric_type| numeric_type.is_signed());
+        let i64_type = self.context.i64_type();
+
+        if is_signed {
+            self.builder.build_int_s_extend_or_bit_cast(value, i64_type, "index").unwrap()
+        } else {
+            self.builder.build_int_z_extend_or_bit_cast(value, i64_type, "index").unwrap()
         }
     }
 
-    fn visit_member_access(&mut self, node: &MemberAccess) {
-        self.log_debug(self.log_target, format!("Getting member access for id {:?}", node.get_id()));
-        if let Some(type_id) = self.symbol_table.ast_types.get(&node.get_id()) {
-            self.log_debug(self.log_target, format!("Found type id {:?} for member access", type_id));
-        } else {
-            self.log_error(self.log_target, format!("No type id found for member access id {:?}", node.get_id()));
+    // Traps unless 0 <= index < len. The unsigned compare also rejects negative indices.
+    fn build_bounds_check(&self, index: IntValue<'ctx>, len: IntValue<'ctx>, position: &PositionRange) {
+        if !self.compiler.options.bounds_checks {
             return;
         }
-        let mut current_type_id = *self.symbol_table.ast_types.get(&node.expr.get_id()).unwrap();
-
-        for access in &node.member_accesses {
-            match &access {
-                AccessType::Function(args) => {
-                    node.expr.accept_visitor(self);
-
-                    let function = unsafe { FunctionValue::new(self.value).unwrap() };
-                    let mut arg_values = Vec::new();
-
-                    for arg in args {
-                        let arg_type = *self.symbol_table.ast_types.get(&arg.get_id()).unwrap();
-                        arg.accept_visitor(self);
-
-                        match &*self.global_table.type_arena.get(arg_type) {
-                            ResolvedType::Boolean | ResolvedType::Integer | ResolvedType::Char => {
-                                let int_value = unsafe { IntValue::new(self.value) };
-                                arg_values.push(int_value.into());
-                            },
-                            ResolvedType::Pointer(_) => {
-                                let ptr_value = unsafe { PointerValue::new(self.value) };
-                                arg_values.push(ptr_value.into());
-                            },
-                            _ => unimplemented!("Argument type not implemented"),
-                        }
-                    }
 
-                    let call_site = self.builder.build_call(
-                        function,
-                        &arg_values,
-                        "calltmp",
-                    ).unwrap();
+        let function = self.current_function();
+        let len = self.builder.build_int_z_extend_or_bit_cast(len, self.context.i64_type(), "len").unwrap();
+        let in_bounds = self.builder.build_int_compare(IntPredicate::ULT, index, len, "inbounds").unwrap();
 
-                    self.value = call_site.as_value_ref();
-                },
-                AccessType::Array(index_expr) => {
-                    let lvalue_mode_backup = self.lvalue_mode;
+        let fail_block = self.context.append_basic_block(function, "boundsfail");
+        let ok_block = self.context.append_basic_block(function, "boundsok");
 
+        self.builder.build_conditional_branch(in_bounds, ok_block, fail_block).unwrap();
 
-                    self.lvalue_mode = false;
-                    node.expr.accept_visitor(self);
-                    let ptr = unsafe { PointerValue::new(self.value) };
+        self.builder.position_at_end(fail_block);
 
-                    index_expr.accept_visitor(self);
-                    self.lvalue_mode = lvalue_mode_backup;
+        // There is no stderr to report to without a C library, the trap alone has to do
+        if !self.compiler.options.freestanding {
+            let message = self.builder.build_global_string_ptr("%s: index out of bounds: the len is %lld but the index is %lld\n", "boundsmsg").unwrap();
+            let location = self.builder.build_global_string_ptr(&format!("{}:{}", self.source_file, position.start()), "boundsloc").unwrap();
+            let stderr_fd = self.context.i32_type().const_int(2, false);
 
-                    let index_value = unsafe { IntValue::new(self.value) };
+            self.builder.build_call(
+                self.get_dprintf_function(),
+                &[stderr_fd.into(), message.as_pointer_value().into(), location.as_pointer_value().into(), len.into(), index.into()],
+                "",
+            ).unwrap();
+        }
 
-                    current_type_id = match &*self.global_table.type_arena.get(current_type_id) {
-                        ResolvedType::Pointer(pointee_type_id) => *pointee_type_id,
-                        ResolvedType::Array(element_type_id) => *element_type_id,
-                        _ => panic!("Expected pointer or array type for array access"),
-                    };
+        let trap = Intrinsic::find("llvm.trap").unwrap().get_declaration(&self.module, &[]).unwrap();
+        self.builder.build_call(trap, &[], "").unwrap();
+        self.builder.build_unreachable().unwrap();
 
-                    let pointee_ty = self.get_type(&self.global_table.type_arena.get(current_type_id));
+        self.builder.position_at_end(ok_block);
+    }
 
+    fn get_dprintf_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("dprintf") {
+            return function;
+        }
 
-                    let gep = unsafe {
-                        self.builder.build_in_bounds_gep(
-                            pointee_ty,
-                            ptr,
-                            &[index_value],
-                            "arrayidx",
-                        ).unwrap()
-                    };
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let fn_type = self.context.i32_type().fn_type(&[self.context.i32_type().into(), ptr_type.into()], true);
+        self.module.add_function("dprintf", fn_type, Some(Linkage::External))
+    }
 
-                    if self.lvalue_mode {
-                        self.value = gep.as_value_ref();
-                        return;
-                    }
+    // `ptr alloc(i64 size)` of the allocator picked in the options. The libc allocator is malloc
+    // itself, the bump allocator is emitted once per module along with its arena.
+    fn get_alloc_function(&self) -> FunctionValue<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let i64_type = self.context.i64_type();
+        let fn_type = ptr_type.fn_type(&[i64_type.into()], false);
 
-                    let loaded = self.builder.build_load(
-                        pointee_ty,
-                        gep,
-                        "arrayload",
-                    ).unwrap();
+        if self.compiler.options.allocator == Allocator::Libc {
+            return self.module.get_function("malloc")
+                .unwrap_or_else(|| self.module.add_function("malloc", fn_type, Some(Linkage::External)));
+        }
 
-                    self.value = loaded.as_value_ref();
-                },
-                AccessType::Direct(member_name) => {
-                    let lvalue_mode_backup = self.lvalue_mode;
-                    self.lvalue_mode = true;
-                    node.expr.accept_visitor(self);
-                    let struct_ptr = unsafe { PointerValue::new(self.value) };
-                    self.lvalue_mode = lvalue_mode_backup;
-
-                    let struct_type = self.global_table.type_arena.get(current_type_id);
-                    let struct_field_map = match &*struct_type {
-                        ResolvedType::Struct(struct_type) => {
-                            self.struct_field_maps.get(&struct_type.name).unwrap()
-                        },
-                        _ => panic!("Expected struct type for member access"),
-                    };
+        if let Some(function) = self.module.get_function("marlin.alloc") {
+            return function;
+        }
 
-                    let member_index = *struct_field_map.get(&**member_name).unwrap();
-
-                    let gep = unsafe {
-                        self.builder.build_in_bounds_gep(
-                            self.get_type(&struct_type),
-                            struct_ptr,
-                            &[
-                                self.context.i32_type().const_int(0, false),
-                                self.context.i32_type().const_int(member_index as u64, false),
-                            ],
-                            "memberidx",
-                        ).unwrap()
-                    };
+        let arena_type = self.context.i8_type().array_type(self.compiler.options.heap_size as u32);
+        let arena = self.module.add_global(arena_type, None, "marlin.heap");
+        arena.set_linkage(Linkage::Private);
+        arena.set_initializer(&arena_type.const_zero());
+        arena.set_alignment(16);
+
+        let offset = self.module.add_global(i64_type, None, "marlin.heap_offset");
+        offset.set_linkage(Linkage::Private);
+        offset.set_initializer(&i64_type.const_zero());
+
+        let function = self.module.add_function("marlin.alloc", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        let fail_block = self.context.append_basic_block(function, "outofmemory");
+        let ok_block = self.context.append_basic_block(function, "alloc");
+
+        self.builder.position_at_end(entry);
+        let size = function.get_nth_param(0).unwrap().into_int_value();
+
+        // Every allocation starts on a 16 byte boundary, like malloc's
+        let current = self.builder.build_load(i64_type, offset.as_pointer_value(), "offset").unwrap().into_int_value();
+        let padded = self.builder.build_int_add(current, i64_type.const_int(15, false), "padded").unwrap();
+        let start = self.builder.build_and(padded, i64_type.const_int(!15, false), "start").unwrap();
+        let end = self.builder.build_int_add(start, size, "end").unwrap();
+
+        let heap_size = i64_type.const_int(self.compiler.options.heap_size as u64, false);
+        let fits = self.builder.build_int_compare(IntPredicate::ULE, end, heap_size, "fits").unwrap();
+        self.builder.build_conditional_branch(fits, ok_block, fail_block).unwrap();
+
+        self.builder.position_at_end(fail_block);
+        let trap = Intrinsic::find("llvm.trap").unwrap().get_declaration(&self.module, &[]).unwrap();
+        self.builder.build_call(trap, &[], "").unwrap();
+        self.builder.build_unreachable().unwrap();
+
+        self.builder.position_at_end(ok_block);
+        self.builder.build_store(offset.as_pointer_value(), end).unwrap();
+        let ptr = unsafe {
+            self.builder.build_gep(arena_type, arena.as_pointer_value(), &[i64_type.const_zero(), start], "allocptr").unwrap()
+        };
+        self.builder.build_return(Some(&ptr)).unwrap();
 
-                    if self.lvalue_mode {
-                        self.value = gep.as_value_ref();
-                        return;
-                    }
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
+        }
 
-                    let member_type_id = *match &*struct_type {
-                        ResolvedType::Struct(struct_type) => {
-                            struct_type.members.get(member_name.as_ref()).unwrap()
-                        },
-                        _ => panic!("Expected struct type for member access"),
-                    };
+        function
+    }
 
-                    let pointee_ty = self.get_type(&self.global_table.type_arena.get(member_type_id));
+    // `void free(ptr)` of the allocator picked in the options. The bump allocator never reuses memory.
+    fn get_free_function(&self) -> FunctionValue<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let fn_type = self.context.void_type().fn_type(&[ptr_type.into()], false);
 
-                    let loaded = self.builder.build_load(
-                        pointee_ty,
-                        gep,
-                        "memberload",
-                    ).unwrap();
+        let name = match self.compiler.options.allocator {
+            Allocator::Libc => "free",
+            Allocator::Bump => "marlin.free",
+        };
 
-                    self.value = loaded.as_value_ref();
+        if let Some(function) = self.module.get_function(name) {
+            return function;
+        }
 
-                    current_type_id = member_type_id;
-                },
-                AccessType::Indirect(member_name) => {
-                    let lvalue_mode_backup = self.lvalue_mode;
-                    self.lvalue_mode = true;
-                    node.expr.accept_visitor(self);
-                    let struct_ptr_ptr = unsafe { PointerValue::new(self.value) };
-                    self.lvalue_mode = lvalue_mode_backup;
-
-                    let struct_ptr = unsafe { PointerValue::new(self.builder.build_load(
-                        self.context.ptr_type(AddressSpace::default()),
-                        struct_ptr_ptr,
-                        "indirectstructptrload",
-                    ).unwrap().as_value_ref()) };
-
-                    let struct_type = &*self.global_table.type_arena.get(current_type_id);
-                    let struct_field_map = match struct_type {
-                        ResolvedType::Pointer(struct_type) => {
-                            match &*self.global_table.type_arena.get(*struct_type) {
-                                ResolvedType::Struct(struct_type) => {
-                                    self.struct_field_maps.get(&struct_type.name).unwrap()
-                                },
-                                _ => panic!("Expected pointer to struct type for indirect member access"),
-                            }
-                        },
-                        _ => panic!("Expected pointer to struct type for indirect member access"),
-                    };
+        if self.compiler.options.allocator == Allocator::Libc {
+            return self.module.add_function(name, fn_type, Some(Linkage::External));
+        }
 
-                    let member_index = *struct_field_map.get(member_name.as_ref()).unwrap();
-
-                    let gep = unsafe {
-                        if let ResolvedType::Pointer(struct_type) = struct_type {
-                            self.builder.build_in_bounds_gep(
-                                self.get_type(&*self.global_table.type_arena.get(*struct_type)),
-                                struct_ptr,
-                                &[
-                                    self.context.i32_type().const_int(0, false),
-                                    self.context.i32_type().const_int(member_index as u64, false),
-                                ],
-                                "memberidx",
-                            ).unwrap()
-                        } else {
-                            panic!("Expected pointer to struct type for indirect member access");
-                        }
-                        
-                    };
+        let function = self.module.add_function(name, fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
 
-                    if self.lvalue_mode {
-                        self.value = gep.as_value_ref();
-                        return;
-                    }
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
+        self.builder.build_return(None).unwrap();
 
-                    let member_type_id = match struct_type {
-                        ResolvedType::Pointer(struct_type) => {
-                            match &*self.global_table.type_arena.get(*struct_type) {
-                                ResolvedType::Struct(struct_type) => {
-                                    *struct_type.members.get(member_name.as_ref()).unwrap()
-                                },
-                                _ => panic!("Expected pointer to struct type for indirect member access"),
-                            }
-                        },
-                        _ => panic!("Expected pointer to struct type for indirect member access"),
-                    };
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
+        }
+
+        function
+    }
 
-                    let pointee_ty = self.get_type(&self.global_table.type_arena.get(member_type_id));
+    // The memory sanitizer puts a record in front of every allocation and keeps all of them in a list:
+    // { next record, payload size, freed }. The payload starts SANITIZER_HEADER_SIZE bytes in.
+    fn sanitizer_record_type(&self) -> inkwell::types::StructType<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        self.context.struct_type(&[ptr_type.into(), self.context.i64_type().into(), self.context.bool_type().into()], false)
+    }
 
-                    let loaded = self.builder.build_load(
-                        pointee_ty,
-                        gep,
-                        "memberload",
-                    ).unwrap();
+    fn get_sanitizer_records(&self) -> GlobalValue<'ctx> {
+        if let Some(records) = self.module.get_global("marlin.san.records") {
+            return records;
+        }
 
-                    self.value = loaded.as_value_ref();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let records = self.module.add_global(ptr_type, None, "marlin.san.records");
+        records.set_linkage(Linkage::Private);
+        records.set_initializer(&ptr_type.const_null());
+        records
+    }
 
-                    current_type_id = member_type_id;
-                },
-                _ => unimplemented!("Member access type not implemented"),
-            }
+    // Prints `<location>: <message>` to stderr and traps. Freestanding builds only trap.
+    fn build_sanitizer_failure(&self, message: &str, location: PointerValue<'ctx>) {
+        if !self.compiler.options.freestanding {
+            let format = self.builder.build_global_string_ptr(&format!("%s: {}\n", message), "sanmsg").unwrap();
+            let stderr_fd = self.context.i32_type().const_int(2, false);
+
+            self.builder.build_call(
+                self.get_dprintf_function(),
+                &[stderr_fd.into(), format.as_pointer_value().into(), location.into()],
+                "",
+            ).unwrap();
         }
+
+        let trap = Intrinsic::find("llvm.trap").unwrap().get_declaration(&self.module, &[]).unwrap();
+        self.builder.build_call(trap, &[], "").unwrap();
+        self.builder.build_unreachable().unwrap();
     }
 
-    fn visit_var(&mut self, node: &VarExpr) {
-        let identifier = node.path.segments.first().unwrap();
-        if let Some(declaration_id) = self.symbol_table.variables.get(&node.get_id()) {
-            let ptr = self.local_vars.get(declaration_id.value()).unwrap();
-            if self.lvalue_mode {
-                self.value = ptr.as_value_ref();
-                return;
-            } else {
-                let declaration_type = self.symbol_table.declaration_types.get(declaration_id.value()).unwrap();
-                let pointee_ty = self.get_type(&self.global_table.type_arena.get(*declaration_type.value()));
+    // `ptr alloc(i64 size)` that records the allocation before handing out its payload
+    fn get_sanitizer_alloc_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.san.alloc") {
+            return function;
+        }
 
-                let loaded = self.builder.build_load(
-                    pointee_ty,
-                    *ptr,
-                    "varload",
-                ).unwrap();
+        let i8_type = self.context.i8_type();
+        let i64_type = self.context.i64_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let record_type = self.sanitizer_record_type();
+        let records = self.get_sanitizer_records();
 
-                self.value = loaded.as_value_ref();
-                return;
-            }
-        } else {
-            if let Some(func_val) = self.functions.get(identifier) {
-                self.value = func_val.as_value_ref();
-            } else if let Some(func_val) = self.foreign_functions.get(node.path.segments.iter().map(|s| s.clone()).collect::<Vec<_>>().as_slice()) {
-                self.value = func_val.as_value_ref();
-            } else {
-                let module_path = node.path.segments[0..node.path.segments.len() - 1].iter().map(|s| s.clone()).collect::<Vec<_>>();
-                let symbol_table = self.global_table.scopes.get(module_path.as_slice()).unwrap();
-                let fn_name = node.path.segments.last().unwrap();
-                let fn_type_id = symbol_table.functions.get(fn_name).unwrap();
-                let fn_type = self.global_table.type_arena.get(*fn_type_id.value());
-
-                if let ResolvedType::Function(fn_type) = &*fn_type {
-                    let function = self.module.add_function(
-                        fn_name,
-                        self.make_function(fn_type),
-                        None,
-                    );
-
-                    self.foreign_functions.insert(
-                        node.path.segments.iter().map(|s| s.clone()).collect::<Vec<_>>(),
-                        function,
-                    );
-
-                    self.value = function.as_value_ref();
-                    return;
-                }
-            }
+        let fn_type = ptr_type.fn_type(&[i64_type.into()], false);
+        let function = self.module.add_function("marlin.san.alloc", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
+
+        let size = function.get_nth_param(0).unwrap().into_int_value();
+        let total_size = self.builder.build_int_add(size, i64_type.const_int(SANITIZER_HEADER_SIZE, false), "totalsize").unwrap();
+        let record = self.builder.build_call(self.get_alloc_function(), &[total_size.into()], "record").unwrap()
+            .try_as_basic_value().basic().unwrap().into_pointer_value();
+
+        let head = self.builder.build_load(ptr_type, records.as_pointer_value(), "head").unwrap();
+        self.builder.build_store(self.builder.build_struct_gep(record_type, record, 0, "next").unwrap(), head).unwrap();
+        self.builder.build_store(self.builder.build_struct_gep(record_type, record, 1, "size").unwrap(), size).unwrap();
+        self.builder.build_store(self.builder.build_struct_gep(record_type, record, 2, "freed").unwrap(), self.context.bool_type().const_zero()).unwrap();
+        self.builder.build_store(records.as_pointer_value(), record).unwrap();
+
+        let payload = unsafe {
+            self.builder.build_gep(i8_type, record, &[i64_type.const_int(SANITIZER_HEADER_SIZE, false)], "payload").unwrap()
+        };
+        self.builder.build_return(Some(&payload)).unwrap();
+
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
         }
+
+        function
     }
 
-    fn visit_if(&mut self, node: &IfExpr) {
-        let current_block = self.builder.get_insert_block().unwrap();
-        let then_block = self.context.insert_basic_block_after(current_block, "then");
-        let else_block = self.context.insert_basic_block_after(then_block, "else");
-        let merge_block = self.context.insert_basic_block_after(else_block, "ifcont");
+    // `ptr find(ptr p)` returns the record of the allocation `p` points into, or null.
+    // Freed records stay in the list so that later uses can still be recognized.
+    fn get_sanitizer_find_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.san.find") {
+            return function;
+        }
 
-        node.condition.accept_visitor(self);
-        let cond_value = unsafe { IntValue::new(self.value) };
+        let i64_type = self.context.i64_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let record_type = self.sanitizer_record_type();
+        let records = self.get_sanitizer_records();
 
-        self.builder.build_conditional_branch(cond_value, then_block, else_block).unwrap();
-        self.builder.position_at_end(then_block);
-        node.success.accept_visitor(self);
-        let success_value = self.value;
-        let success_block = self.builder.get_insert_block().unwrap();
-        self.builder.build_unconditional_branch(merge_block).unwrap();
+        let fn_type = ptr_type.fn_type(&[ptr_type.into()], false);
+        let function = self.module.add_function("marlin.san.find", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        let loop_block = self.context.append_basic_block(function, "loop");
+        let body_block = self.context.append_basic_block(function, "body");
+        let next_block = self.context.append_basic_block(function, "next");
+        let found_block = self.context.append_basic_block(function, "found");
+        let missing_block = self.context.append_basic_block(function, "missing");
+
+        self.builder.position_at_end(entry);
+        let address = self.builder.build_ptr_to_int(function.get_nth_param(0).unwrap().into_pointer_value(), i64_type, "address").unwrap();
+        let head = self.builder.build_load(ptr_type, records.as_pointer_value(), "head").unwrap().into_pointer_value();
+        self.builder.build_unconditional_branch(loop_block).unwrap();
 
-        self.builder.position_at_end(else_block);
-        let mut fail_value = None;
-        let mut fail_block = None;
-        if let Some(fail) = &node.fail {
-            self.log_debug(self.log_target, "Generating else block");
-            fail.accept_visitor(self);
-            fail_value = Some(self.value);
-            fail_block = self.builder.get_insert_block();
+        self.builder.position_at_end(loop_block);
+        let record = self.builder.build_phi(ptr_type, "record").unwrap();
+        record.add_incoming(&[(&head, entry)]);
+        let record_ptr = record.as_basic_value().into_pointer_value();
+        let is_end = self.builder.build_is_null(record_ptr, "isend").unwrap();
+        self.builder.build_conditional_branch(is_end, missing_block, body_block).unwrap();
+
+        // A zero-sized allocation is only hit by a pointer to its start
+        self.builder.position_at_end(body_block);
+        let record_address = self.builder.build_ptr_to_int(record_ptr, i64_type, "recordaddress").unwrap();
+        let start = self.builder.build_int_add(record_address, i64_type.const_int(SANITIZER_HEADER_SIZE, false), "start").unwrap();
+        let size_ptr = self.builder.build_struct_gep(record_type, record_ptr, 1, "sizeptr").unwrap();
+        let size = self.builder.build_load(i64_type, size_ptr, "size").unwrap().into_int_value();
+        let end = self.builder.build_int_add(start, size, "end").unwrap();
+        let after_start = self.builder.build_int_compare(IntPredicate::UGE, address, start, "afterstart").unwrap();
+        let before_end = self.builder.build_int_compare(IntPredicate::ULT, address, end, "beforeend").unwrap();
+        let at_start = self.builder.build_int_compare(IntPredicate::EQ, address, start, "atstart").unwrap();
+        let in_range = self.builder.build_or(before_end, at_start, "inrange").unwrap();
+        let is_inside = self.builder.build_and(after_start, in_range, "isinside").unwrap();
+        self.builder.build_conditional_branch(is_inside, found_block, next_block).unwrap();
+
+        self.builder.position_at_end(next_block);
+        let next_ptr = self.builder.build_struct_gep(record_type, record_ptr, 0, "nextptr").unwrap();
+        let next = self.builder.build_load(ptr_type, next_ptr, "next").unwrap().into_pointer_value();
+        record.add_incoming(&[(&next, next_block)]);
+        self.builder.build_unconditional_branch(loop_block).unwrap();
+
+        self.builder.position_at_end(found_block);
+        self.builder.build_return(Some(&record_ptr)).unwrap();
+
+        self.builder.position_at_end(missing_block);
+        self.builder.build_return(Some(&ptr_type.const_null())).unwrap();
+
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
         }
-        self.builder.build_unconditional_branch(merge_block).unwrap();
 
-        self.builder.position_at_end(merge_block);
+        function
+    }
 
-        if let Some(fail_value) = fail_value {
-            let success_value = unsafe { IntValue::new(success_value) };
-            let fail_value = unsafe { IntValue::new(fail_value) };
-            
-            if success_value.get_type() == fail_value.get_type() {
-                /*
-                let phi = self.builder.build_phi(self.context.i32_type(), "iftmp").unwrap();
-
-                phi.add_incoming(&[
-                    (&success_value, success_block),
-                    (&fail_value, fail_block.unwrap()),
-                ]);
-                self.value = phi.as_value_ref();
-                */
-            }
-        } else {
-            self.value = success_value;
+    // `void free(ptr p, ptr location)` that rejects double deletes and pointers `new` did not return.
+    // The memory itself is never released, so that any later use of it is still caught.
+    fn get_sanitizer_free_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.san.free") {
+            return function;
+        }
+
+        let i64_type = self.context.i64_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let record_type = self.sanitizer_record_type();
+
+        let fn_type = self.context.void_type().fn_type(&[ptr_type.into(), ptr_type.into()], false);
+        let function = self.module.add_function("marlin.san.free", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        let lookup_block = self.context.append_basic_block(function, "lookup");
+        let start_block = self.context.append_basic_block(function, "checkstart");
+        let freed_block = self.context.append_basic_block(function, "checkfreed");
+        let mark_block = self.context.append_basic_block(function, "mark");
+        let double_block = self.context.append_basic_block(function, "doubledelete");
+        let invalid_block = self.context.append_basic_block(function, "invaliddelete");
+        let done_block = self.context.append_basic_block(function, "done");
+
+        let ptr = function.get_nth_param(0).unwrap().into_pointer_value();
+        let location = function.get_nth_param(1).unwrap().into_pointer_value();
+
+        // Deleting null does nothing, as with free
+        self.builder.position_at_end(entry);
+        let is_null = self.builder.build_is_null(ptr, "isnull").unwrap();
+        self.builder.build_conditional_branch(is_null, done_block, lookup_block).unwrap();
+
+        self.builder.position_at_end(lookup_block);
+        let record = self.builder.build_call(self.get_sanitizer_find_function(), &[ptr.into()], "record").unwrap()
+            .try_as_basic_value().basic().unwrap().into_pointer_value();
+        let is_unknown = self.builder.build_is_null(record, "isunknown").unwrap();
+        self.builder.build_conditional_branch(is_unknown, invalid_block, start_block).unwrap();
+
+        self.builder.position_at_end(start_block);
+        let record_address = self.builder.build_ptr_to_int(record, i64_type, "recordaddress").unwrap();
+        let start = self.builder.build_int_add(record_address, i64_type.const_int(SANITIZER_HEADER_SIZE, false), "start").unwrap();
+        let address = self.builder.build_ptr_to_int(ptr, i64_type, "address").unwrap();
+        let is_start = self.builder.build_int_compare(IntPredicate::EQ, address, start, "isstart").unwrap();
+        self.builder.build_conditional_branch(is_start, freed_block, invalid_block).unwrap();
+
+        self.builder.position_at_end(freed_block);
+        let freed_ptr = self.builder.build_struct_gep(record_type, record, 2, "freedptr").unwrap();
+        let freed = self.builder.build_load(self.context.bool_type(), freed_ptr, "freed").unwrap().into_int_value();
+        self.builder.build_conditional_branch(freed, double_block, mark_block).unwrap();
+
+        self.builder.position_at_end(mark_block);
+        self.builder.build_store(freed_ptr, self.context.bool_type().const_all_ones()).unwrap();
+        self.builder.build_unconditional_branch(done_block).unwrap();
+
+        self.builder.position_at_end(double_block);
+        self.build_sanitizer_failure("double delete", location);
+
+        self.builder.position_at_end(invalid_block);
+        self.build_sanitizer_failure("delete of a pointer that was not returned by new", location);
+
+        self.builder.position_at_end(done_block);
+        self.builder.build_return(None).unwrap();
+
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
         }
+
+        function
     }
 
-    fn visit_assignment(&mut self, node: &AssignmentExpr) {
-        self.lvalue_mode = true;
-        node.assignee.accept_visitor(self);
-        let ptr = unsafe { PointerValue::new(self.value) };
-        self.lvalue_mode = false;
+    // `void check(ptr p, i64 offset, i64 size, ptr location)` for an access of `size` bytes at `p + offset`.
+    // Pointers that don't point into an allocation, like those to locals, are only checked for null.
+    fn get_sanitizer_check_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.san.check") {
+            return function;
+        }
 
-        node.expr.accept_visitor(self);
-        let expr_value = self.value;
+        let i64_type = self.context.i64_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let record_type = self.sanitizer_record_type();
+
+        let fn_type = self.context.void_type().fn_type(&[ptr_type.into(), i64_type.into(), i64_type.into(), ptr_type.into()], false);
+        let function = self.module.add_function("marlin.san.check", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        let null_block = self.context.append_basic_block(function, "nullderef");
+        let lookup_block = self.context.append_basic_block(function, "lookup");
+        let freed_block = self.context.append_basic_block(function, "checkfreed");
+        let use_after_free_block = self.context.append_basic_block(function, "useafterfree");
+        let bounds_block = self.context.append_basic_block(function, "checkbounds");
+        let out_of_bounds_block = self.context.append_basic_block(function, "outofbounds");
+        let done_block = self.context.append_basic_block(function, "done");
+
+        let ptr = function.get_nth_param(0).unwrap().into_pointer_value();
+        let offset = function.get_nth_param(1).unwrap().into_int_value();
+        let size = function.get_nth_param(2).unwrap().into_int_value();
+        let location = function.get_nth_param(3).unwrap().into_pointer_value();
+
+        self.builder.position_at_end(entry);
+        let is_null = self.builder.build_is_null(ptr, "isnull").unwrap();
+        self.builder.build_conditional_branch(is_null, null_block, lookup_block).unwrap();
+
+        self.builder.position_at_end(null_block);
+        self.build_sanitizer_failure("null pointer dereference", location);
+
+        self.builder.position_at_end(lookup_block);
+        let record = self.builder.build_call(self.get_sanitizer_find_function(), &[ptr.into()], "record").unwrap()
+            .try_as_basic_value().basic().unwrap().into_pointer_value();
+        let is_unknown = self.builder.build_is_null(record, "isunknown").unwrap();
+        self.builder.build_conditional_branch(is_unknown, done_block, freed_block).unwrap();
+
+        self.builder.position_at_end(freed_block);
+        let freed_ptr = self.builder.build_struct_gep(record_type, record, 2, "freedptr").unwrap();
+        let freed = self.builder.build_load(self.context.bool_type(), freed_ptr, "freed").unwrap().into_int_value();
+        self.builder.build_conditional_branch(freed, use_after_free_block, bounds_block).unwrap();
+
+        self.builder.position_at_end(use_after_free_block);
+        self.build_sanitizer_failure("use after free", location);
+
+        // The access has to lie within [start, start + size) of the allocation
+        self.builder.position_at_end(bounds_block);
+        let record_address = self.builder.build_ptr_to_int(record, i64_type, "recordaddress").unwrap();
+        let start = self.builder.build_int_add(record_address, i64_type.const_int(SANITIZER_HEADER_SIZE, false), "start").unwrap();
+        let address = self.builder.build_ptr_to_int(ptr, i64_type, "address").unwrap();
+        let relative = self.builder.build_int_sub(address, start, "relative").unwrap();
+        let first = self.builder.build_int_add(relative, offset, "first").unwrap();
+        let last = self.builder.build_int_add(first, size, "last").unwrap();
+        let size_ptr = self.builder.build_struct_gep(record_type, record, 1, "sizeptr").unwrap();
+        let allocation_size = self.builder.build_load(i64_type, size_ptr, "allocationsize").unwrap().into_int_value();
+        let before_start = self.builder.build_int_compare(IntPredicate::SLT, first, i64_type.const_zero(), "beforestart").unwrap();
+        let past_end = self.builder.build_int_compare(IntPredicate::SGT, last, allocation_size, "pastend").unwrap();
+        let is_outside = self.builder.build_or(before_start, past_end, "isoutside").unwrap();
+        self.builder.build_conditional_branch(is_outside, out_of_bounds_block, done_block).unwrap();
+
+        self.builder.position_at_end(out_of_bounds_block);
+        self.build_sanitizer_failure("out of bounds access", location);
+
+        self.builder.position_at_end(done_block);
+        self.builder.build_return(None).unwrap();
+
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
+        }
 
-        self.builder.build_store(
-            ptr,
-            unsafe { IntValue::new(expr_value) },
-        ).unwrap();
+        function
     }
 
-    fn visit_delete(&mut self, _node: &DeleteExpr) {
-        
+    fn build_location(&self, position: &PositionRange) -> PointerValue<'ctx> {
+        self.builder.build_global_string_ptr(&format!("{}:{}", self.source_file, position.start()), "sanloc").unwrap().as_pointer_value()
     }
 
-    fn visit_declaration(&mut self, node: &DeclarationExpr) {
-        let declaration_type_id = self.symbol_table.declaration_types.get(&node.get_id()).unwrap();
-        let declaration_type = self.global_table.type_arena.get(*declaration_type_id);
-        let llvm_type = self.get_type(&declaration_type); 
+    // Checks an access of `value_type` at `ptr + offset` when the memory sanitizer is enabled
+    fn build_memory_check(&self, ptr: PointerValue<'ctx>, offset: IntValue<'ctx>, value_type: BasicTypeEnum<'ctx>, position: &PositionRange) {
+        if !self.compiler.options.sanitize_memory {
+            return;
+        }
 
-        drop(declaration_type);
+        let offset = self.builder.build_int_s_extend_or_bit_cast(offset, self.context.i64_type(), "sanoffset").unwrap();
+        let size = value_type.size_of().unwrap();
 
-        let ptr_value = self.builder.build_alloca(
-            llvm_type,
-            &node.get_id().to_string(),
+        self.builder.build_call(
+            self.get_sanitizer_check_function(),
+            &[ptr.into(), offset.into(), size.into(), self.build_location(position).into()],
+            "",
         ).unwrap();
+    }
 
-        if let Some(expr) = &node.expr {
-            expr.accept_visitor(self);
+    // Allocates room for `count` values of `value_type`, or a single one without a count
+    fn build_alloc(&self, value_type: BasicTypeEnum<'ctx>, count: Option<IntValue<'ctx>>, name: &str) -> PointerValue<'ctx> {
+        let i64_type = self.context.i64_type();
+        let mut size = value_type.size_of().unwrap();
 
-            self.builder.build_store(
-                ptr_value,
-                unsafe { IntValue::new(self.value) },
-            ).unwrap();
+        if let Some(count) = count {
+            let count = self.builder.build_int_s_extend_or_bit_cast(count, i64_type, "count").unwrap();
+            size = self.builder.build_int_mul(size, count, "allocsize").unwrap();
         }
 
-        self.local_vars.insert(node.get_id(), ptr_value);
+        self.build_alloc_bytes(size, name)
     }
 
-    fn visit_block(&mut self, node: &BlockExpr) {
-        let current_block = self.builder.get_insert_block().unwrap();
-        let block_entry = self.context.insert_basic_block_after(current_block, "blockentry");
-        let after_block = self.context.insert_basic_block_after(block_entry, "afterblock");
+    fn build_alloc_bytes(&self, size: IntValue<'ctx>, name: &str) -> PointerValue<'ctx> {
+        let alloc_function = if self.compiler.options.sanitize_memory {
+            self.get_sanitizer_alloc_function()
+        } else {
+            self.get_alloc_function()
+        };
+
+        self.builder.build_call(alloc_function, &[size.into()], name).unwrap()
+            .try_as_basic_value().basic().unwrap().into_pointer_value()
+    }
 
-        self.result_block = Some(after_block);
+    // Lays out `new T[a][b][c]` as a single allocation: a table of `a` pointers to rows, then
+    // `a * b` pointers to the rows of those, then the `a * b * c` elements. Indexing goes through
+    // the tables like any pointer, and one `delete` of the outermost pointer frees every level.
+    fn build_nested_array(&self, element_type: BasicTypeEnum<'ctx>, sizes: &[IntValue<'ctx>]) -> PointerValue<'ctx> {
+        let i8_type = self.context.i8_type();
+        let i64_type = self.context.i64_type();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let ptr_size = ptr_type.size_of();
+        let element_size = element_type.size_of().unwrap();
+
+        // Number of entries at every level, the last level being the elements themselves
+        let mut counts = vec![sizes[0]];
+
+        for &size in &sizes[1..] {
+            let count = self.builder.build_int_mul(*counts.last().unwrap(), size, "levelcount").unwrap();
+            counts.push(count);
+        }
 
-        self.builder.build_unconditional_branch(block_entry).unwrap();
-        self.builder.position_at_end(block_entry);
+        // Byte offset of every level within the block
+        let mut offsets = vec![i64_type.const_zero()];
 
-        for expr in &node.exprs {
-            expr.accept_visitor(self);
+        for &count in &counts[..counts.len() - 1] {
+            let level_size = self.builder.build_int_mul(count, ptr_size, "levelsize").unwrap();
+            let offset = self.builder.build_int_add(*offsets.last().unwrap(), level_size, "leveloffset").unwrap();
+            offsets.push(offset);
         }
 
-        self.builder.build_unconditional_branch(after_block).unwrap();
-        self.builder.position_at_end(after_block);
+        let data_size = self.builder.build_int_mul(*counts.last().unwrap(), element_size, "datasize").unwrap();
+        let total_size = self.builder.build_int_add(*offsets.last().unwrap(), data_size, "blocksize").unwrap();
 
-        self.result_block = None;
-    }
+        let block = self.build_alloc_bytes(total_size, "newarray");
 
-    fn visit_loop(&mut self, node: &LoopExpr) {
-        let label = match &node.label {
-            Some(label) => label.to_string(),
-            None => format!("{}", self.loop_labels.len()),
-        };
+        let function = self.current_function();
 
-        self.loop_labels.push_back(label.clone());
+        // Entry i of a level points at row i of the next one
+        for level in 0..counts.len() - 1 {
+            let entry_size = if level + 1 == counts.len() - 1 { element_size } else { ptr_size };
+            let row_size = self.builder.build_int_mul(sizes[level + 1], entry_size, "rowsize").unwrap();
 
-        self.break_values.insert(label.clone(), Vec::new());
+            let preheader = self.builder.get_insert_block().unwrap();
+            let loop_block = self.context.append_basic_block(function, "fillrows");
+            let after_block = self.context.append_basic_block(function, "fillrowsdone");
 
-        if let Some(initial) = &node.initial {
-            initial.accept_visitor(self);
-        }
-        
-        let current_block = self.builder.get_insert_block().unwrap();
-        let condition_block = self.context.insert_basic_block_after(current_block, "loopcondition");
-        let loop_block = self.context.insert_basic_block_after(condition_block, "loop");
-        let after_loop_block = self.context.insert_basic_block_after(loop_block, "afterloop");
-        
-        self.break_block.insert(label.clone(), after_loop_block);
+            let is_empty = self.builder.build_int_compare(IntPredicate::EQ, counts[level], i64_type.const_zero(), "emptylevel").unwrap();
+            self.builder.build_conditional_branch(is_empty, after_block, loop_block).unwrap();
 
-        self.builder.build_unconditional_branch(condition_block).unwrap();
-        self.builder.position_at_end(condition_block);
+            self.builder.position_at_end(loop_block);
+            let index = self.builder.build_phi(i64_type, "row").unwrap();
+            index.add_incoming(&[(&i64_type.const_zero(), preheader)]);
+            let index_value = index.as_basic_value().into_int_value();
 
-        if let Some(condition) = &node.condition {
-            condition.accept_visitor(self);
-            let cond_value = unsafe { IntValue::new(self.value) };
-            self.builder.build_conditional_branch(cond_value, loop_block, after_loop_block).unwrap();
-        } else {
-            self.builder.build_unconditional_branch(loop_block).unwrap();
-        }
+            let row_offset = self.builder.build_int_mul(index_value, row_size, "rowoffset").unwrap();
+            let row_offset = self.builder.build_int_add(offsets[level + 1], row_offset, "rowstart").unwrap();
+            let entry_offset = self.builder.build_int_mul(index_value, ptr_size, "entryoffset").unwrap();
+            let entry_offset = self.builder.build_int_add(offsets[level], entry_offset, "entrystart").unwrap();
 
-        self.builder.position_at_end(loop_block);
+            let (row_ptr, entry_ptr) = unsafe {
+                (
+                    self.builder.build_gep(i8_type, block, &[row_offset], "rowptr").unwrap(),
+                    self.builder.build_gep(i8_type, block, &[entry_offset], "entryptr").unwrap(),
+                )
+            };
 
-        node.body.accept_visitor(self);
-        if let Some(increment) = &node.increment {
-            increment.accept_visitor(self);
-        }
-            
-        self.builder.build_unconditional_branch(condition_block).unwrap();
-            
-        self.builder.position_at_end(after_loop_block);
+            self.builder.build_store(entry_ptr, row_ptr).unwrap();
 
-        let breaks = self.break_values.get(&label).unwrap();
-        let mut is_safe = true;
+            let next_index = self.builder.build_int_add(index_value, i64_type.const_int(1, false), "nextrow").unwrap();
+            index.add_incoming(&[(&next_index, loop_block)]);
 
-        for (_, val) in breaks {
-            let int_value = unsafe {IntValue::new(*val)};
+            let is_done = self.builder.build_int_compare(IntPredicate::EQ, next_index, counts[level], "rowsdone").unwrap();
+            self.builder.build_conditional_branch(is_done, after_block, loop_block).unwrap();
 
-            if int_value.get_type() != self.context.i32_type() {
-                is_safe = false;
-                break;
-            }
+            self.builder.position_at_end(after_block);
         }
 
-        if is_safe {
-            let phi = self.builder.build_phi(self.context.i32_type(), "looptmp").unwrap();
+        block
+    }
+
+    // Compares two str slices byte by byte. Emitted once per module on first use.
+    fn get_str_eq_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.str_eq") {
+            return function;
+        }
 
-            for (bb, val) in breaks {
-                let int_value = unsafe {IntValue::new(*val)};
+        let i32_type = self.context.i32_type();
+        let i8_type = self.context.i8_type();
+        let bool_type = self.context.bool_type();
+
+        let fn_type = bool_type.fn_type(&[self.str_type().into(), self.str_type().into()], false);
+        let function = self.module.add_function("marlin.str_eq", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        let loop_block = self.context.append_basic_block(function, "loop");
+        let body_block = self.context.append_basic_block(function, "body");
+        let equal_block = self.context.append_basic_block(function, "equal");
+        let not_equal_block = self.context.append_basic_block(function, "notequal");
+
+        self.builder.position_at_end(entry);
+        let left = function.get_nth_param(0).unwrap().into_struct_value();
+        let right = function.get_nth_param(1).unwrap().into_struct_value();
+        let left_ptr = self.builder.build_extract_value(left, 0, "leftptr").unwrap().into_pointer_value();
+        let left_len = self.builder.build_extract_value(left, 1, "leftlen").unwrap().into_int_value();
+        let right_ptr = self.builder.build_extract_value(right, 0, "rightptr").unwrap().into_pointer_value();
+        let right_len = self.builder.build_extract_value(right, 1, "rightlen").unwrap().into_int_value();
+        let same_len = self.builder.build_int_compare(IntPredicate::EQ, left_len, right_len, "samelen").unwrap();
+        self.builder.build_conditional_branch(same_len, loop_block, not_equal_block).unwrap();
 
-                phi.add_incoming(&[
-                    (&int_value, *bb),
-                ]);
-            }
-            self.value = phi.as_value_ref();
+        self.builder.position_at_end(loop_block);
+        let index = self.builder.build_phi(i32_type, "index").unwrap();
+        let index_value = index.as_basic_value().into_int_value();
+        let done = self.builder.build_int_compare(IntPredicate::EQ, index_value, left_len, "done").unwrap();
+        self.builder.build_conditional_branch(done, equal_block, body_block).unwrap();
+
+        self.builder.position_at_end(body_block);
+        let left_char_ptr = unsafe { self.builder.build_in_bounds_gep(i8_type, left_ptr, &[index_value], "leftcharptr").unwrap() };
+        let right_char_ptr = unsafe { self.builder.build_in_bounds_gep(i8_type, right_ptr, &[index_value], "rightcharptr").unwrap() };
+        let left_char = self.builder.build_load(i8_type, left_char_ptr, "leftchar").unwrap().into_int_value();
+        let right_char = self.builder.build_load(i8_type, right_char_ptr, "rightchar").unwrap().into_int_value();
+        let next_index = self.builder.build_int_add(index_value, i32_type.const_int(1, false), "nextindex").unwrap();
+        let chars_equal = self.builder.build_int_compare(IntPredicate::EQ, left_char, right_char, "charseq").unwrap();
+        self.builder.build_conditional_branch(chars_equal, loop_block, not_equal_block).unwrap();
+
+        index.add_incoming(&[(&i32_type.const_zero(), entry), (&next_index, body_block)]);
+
+        self.builder.position_at_end(equal_block);
+        self.builder.build_return(Some(&bool_type.const_int(1, false))).unwrap();
+
+        self.builder.position_at_end(not_equal_block);
+        self.builder.build_return(Some(&bool_type.const_zero())).unwrap();
+
+        if let Some(block) = current_block {
+            self.builder.position_at_end(block);
         }
 
-        self.break_values.remove(&label);
-        self.break_block.remove(&label);
-        self.loop_labels.pop_back();
+        function
     }
 
-    fn visit_exit(&mut self, node: &ExitExpr) {
-        let mut value = None;
-        if let Some(expr) = &node.expr {
-            expr.accept_visitor(self);
+    // Length of a null-terminated char*, used when converting it to a str
+    fn get_strlen_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.strlen") {
+            return function;
+        }
 
-            let expr_type_id = self.symbol_table.ast_types.get(&expr.get_id()).unwrap();
-            let expr_type = self.global_table.type_arena.get(*expr_type_id);
+        let i32_type = self.context.i32_type();
+        let i8_type = self.context.i8_type();
 
-            value = Some(unsafe { self.get_basic_value(&expr_type, self.value) });
-        }
+        let fn_type = i32_type.fn_type(&[self.context.ptr_type(AddressSpace::default()).into()], false);
+        let function = self.module.add_function("marlin.strlen", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
 
-        let label = match &node.label {
-            Some(label) => Some(label.as_ref()),
-            None => self.loop_labels.back(),
-        };
+        let entry = self.context.append_basic_block(function, "entry");
+        let loop_block = self.context.append_basic_block(function, "loop");
+        let done_block = self.context.append_basic_block(function, "done");
 
-        match node.exit_type {
-            ExitType::Break => {
-                self.break_values.get_mut(label.map(|x| x.as_str()).unwrap()).unwrap().push((
-                    self.builder.get_insert_block().unwrap(),
-                    self.value,
-                ));
-                self.builder.build_unconditional_branch(*self.break_block.get(label.unwrap().as_str()).unwrap()).unwrap();
-            },
-            ExitType::Result => {
-                self.builder.build_unconditional_branch(self.result_block.unwrap()).unwrap();
-            },
-            ExitType::Return => {
-                self.builder.build_return(value.as_ref().map(|v| v as &dyn BasicValue)).unwrap();
-            }
-        }
-    }
+        self.builder.position_at_end(entry);
+        let ptr = function.get_nth_param(0).unwrap().into_pointer_value();
+        self.builder.build_unconditional_branch(loop_block).unwrap();
 
-    fn visit_constructor_call(&mut self, _node: &ConstructorCallExpr) {
-        
-    }
+        self.builder.position_at_end(loop_block);
+        let index = self.builder.build_phi(i32_type, "index").unwrap();
+        let index_value = index.as_basic_value().into_int_value();
+        let char_ptr = unsafe { self.builder.build_in_bounds_gep(i8_type, ptr, &[index_value], "charptr").unwrap() };
+        let current_char = self.builder.build_load(i8_type, char_ptr, "char").unwrap().into_int_value();
+        let next_index = self.builder.build_int_add(index_value, i32_type.const_int(1, false), "nextindex").unwrap();
+        let is_nul = self.builder.build_int_compare(IntPredicate::EQ, current_char, i8_type.const_zero(), "isnul").unwrap();
+        self.builder.build_conditional_branch(is_nul, done_block, loop_block).unwrap();
+
+        index.add_incoming(&[(&i32_type.const_zero(), entry), (&next_index, loop_block)]);
 
-    fn visit_new_array(&mut self, node: &NewArrayExpr) {
-        if node.dimension != 1 {
-            unimplemented!("Only single-dimensional arrays are currently supported");
+        self.builder.position_at_end(done_block);
+        self.builder.build_return(Some(&index_value)).unwrap();
+
+        if let Some(block) = current_block {
+            self.builder.position_at_end(block);
         }
 
-        node.sizes[0].accept_visitor(self);
-        let size_value = unsafe { IntValue::new(self.value) };
+        function
+    }
 
-        let array_type = self.global_table.type_arena.get(*self.symbol_table.ast_types.get(&node.get_id()).unwrap());
-        if let ResolvedType::Pointer(element_type_id) = &*array_type {
-            let element_type_id = *element_type_id;
-            drop(array_type);
-            let element_type = self.global_table.type_arena.get(element_type_id);
-            let llvm_element_type = self.get_type(&element_type);
+    // Function values are { fn ptr, env ptr } pairs. The function pointer takes the
+    // environment as a hidden first parameter; named functions get an env of null.
+    fn closure_type(&self) -> inkwell::types::StructType<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        self.context.struct_type(&[ptr_type.into(), ptr_type.into()], false)
+    }
 
-            let array_ptr = self.builder.build_array_alloca(
-                llvm_element_type,
-                size_value,
-                "newarraytmp",
-            ).unwrap();
+    // Environments are reference counted, every function value holding one owns a reference:
+    // { i64 references, ptr drop function, captures... }. The drop function releases the function
+    // values captured by value, and is null when there are none.
+    fn closure_env_type(&self, capture_types: &[BasicTypeEnum<'ctx>]) -> inkwell::types::StructType<'ctx> {
+        let mut field_types: Vec<BasicTypeEnum> = vec![self.context.i64_type().into(), self.context.ptr_type(AddressSpace::default()).into()];
+        field_types.extend(capture_types);
 
-            self.value = array_ptr.as_value_ref();
-        } else {
-            panic!("Expected pointer type for new array expression");
-        }
+        self.context.struct_type(&field_types, false)
     }
 
-    fn visit_impl(&mut self, _node: &ImplItem) {
-        
+    fn closure_env(&self, closure: BasicValueEnum<'ctx>) -> PointerValue<'ctx> {
+        self.builder.build_extract_value(closure.into_struct_value(), 1, "closureenv").unwrap().into_pointer_value()
     }
 
-    fn visit_function(&mut self, node: &FunctionItem) {
-        let fn_type_id = self.symbol_table.functions.get(node.name.as_ref()).unwrap();
-        let fn_type_outer = self.global_table.type_arena.get(*fn_type_id);
+    // `void retain(ptr env)`, taking another reference to an environment. Named functions have none.
+    fn get_closure_retain_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.closure.retain") {
+            return function;
+        }
 
-        if let ResolvedType::Function(fn_type) = &*fn_type_outer {
-            let fn_type = fn_type.clone();
-            drop(fn_type_outer);
-            let mut param_types = Vec::new();
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let i64_type = self.context.i64_type();
 
-            for &parameter in &fn_type.param_types {
-                let param_type = self.global_table.type_arena.get(parameter);
-                match *param_type {
-                    ResolvedType::Integer => param_types.push(self.context.i32_type().into()),
-                    ResolvedType::Char => param_types.push(self.context.i8_type().into()),
-                    ResolvedType::Pointer(_) => param_types.push(self.context.ptr_type(AddressSpace::default()).into()),
-                    _ => unimplemented!("Parameter type not implemented"),
-                }
-            }
+        let fn_type = self.context.void_type().fn_type(&[ptr_type.into()], false);
+        let function = self.module.add_function("marlin.closure.retain", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
 
-            let ret_type_id = fn_type.return_type;
-            let ret_type = self.global_table.type_arena.get(fn_type.return_type);
-            let fn_type = match *ret_type {
-                ResolvedType::Integer => self.context.i32_type().fn_type(&param_types, false),
-                ResolvedType::Void => self.context.void_type().fn_type(&param_types, false),
-                ResolvedType::Char => self.context.i8_type().fn_type(&param_types, false),
-                ResolvedType::Pointer(_) => self.context.ptr_type(AddressSpace::default()).fn_type(&param_types, false),
-                _ => unimplemented!("Return type not implemented"),
-            };
+        let entry = self.context.append_basic_block(function, "entry");
+        let retain_block = self.context.append_basic_block(function, "retain");
+        let done_block = self.context.append_basic_block(function, "done");
 
-            drop(ret_type);
+        let env = function.get_nth_param(0).unwrap().into_pointer_value();
 
-            let function = self.module.add_function(node.name.as_ref(), fn_type, None);
-            self.functions.insert(node.name.to_string(), function);
+        self.builder.position_at_end(entry);
+        let is_null = self.builder.build_is_null(env, "isnull").unwrap();
+        self.builder.build_conditional_branch(is_null, done_block, retain_block).unwrap();
 
-            if let Some(body) = &node.body {
-                let entry = self.context.append_basic_block(function, "entry");
-                self.builder.position_at_end(entry);
+        self.builder.position_at_end(retain_block);
+        let references = self.builder.build_load(i64_type, env, "references").unwrap().into_int_value();
+        let references = self.builder.build_int_add(references, i64_type.const_int(1, false), "retained").unwrap();
+        self.builder.build_store(env, references).unwrap();
+        self.builder.build_unconditional_branch(done_block).unwrap();
 
-                for (i, parameter) in node.parameters.iter().enumerate() {
-                    let param = function.get_nth_param(i as u32).unwrap();
+        self.builder.position_at_end(done_block);
+        self.builder.build_return(None).unwrap();
 
-                    let param_ptr = self.builder.build_alloca(
-                        param.get_type(),
-                        parameter.identifier.as_ref(),
-                    ).unwrap();
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
+        }
 
-                    self.builder.build_store(param_ptr, param).unwrap();
-                    self.local_vars.insert(parameter.get_id(), param_ptr);
-                }
+        function
+    }
 
-                body.accept_visitor(self);
+    // `void release(ptr env)`, giving up a reference to an environment. Giving up the last one runs
+    // the drop function and frees the environment.
+    fn get_closure_release_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("marlin.closure.release") {
+            return function;
+        }
 
-                let ret_type = self.global_table.type_arena.get(ret_type_id);
-                if *ret_type == ResolvedType::Void || !node.body.is_some() {
-                    self.builder.build_return(None).unwrap();
-                } else {
-                    println!("Generating return for function {}", *node.name);
-                    let value = unsafe { self.get_basic_value(&ret_type, self.value) };
-                    self.builder.build_return(Some(&value)).unwrap();
-                }
-            }
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let i64_type = self.context.i64_type();
+        let env_type = self.closure_env_type(&[]);
+
+        let fn_type = self.context.void_type().fn_type(&[ptr_type.into()], false);
+        let function = self.module.add_function("marlin.closure.release", fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
+
+        let entry = self.context.append_basic_block(function, "entry");
+        let release_block = self.context.append_basic_block(function, "release");
+        let last_block = self.context.append_basic_block(function, "lastreference");
+        let drop_block = self.context.append_basic_block(function, "drop");
+        let free_block = self.context.append_basic_block(function, "free");
+        let done_block = self.context.append_basic_block(function, "done");
+
+        let env = function.get_nth_param(0).unwrap().into_pointer_value();
+
+        self.builder.position_at_end(entry);
+        let is_null = self.builder.build_is_null(env, "isnull").unwrap();
+        self.builder.build_conditional_branch(is_null, done_block, release_block).unwrap();
+
+        self.builder.position_at_end(release_block);
+        let references = self.builder.build_load(i64_type, env, "references").unwrap().into_int_value();
+        let references = self.builder.build_int_sub(references, i64_type.const_int(1, false), "released").unwrap();
+        self.builder.build_store(env, references).unwrap();
+        let is_last = self.builder.build_int_compare(IntPredicate::EQ, references, i64_type.const_zero(), "islast").unwrap();
+        self.builder.build_conditional_branch(is_last, last_block, done_block).unwrap();
+
+        self.builder.position_at_end(last_block);
+        let drop_fn_ptr = self.builder.build_struct_gep(env_type, env, 1, "dropfnptr").unwrap();
+        let drop_fn = self.builder.build_load(ptr_type, drop_fn_ptr, "dropfn").unwrap().into_pointer_value();
+        let has_drop_fn = self.builder.build_is_not_null(drop_fn, "hasdropfn").unwrap();
+        self.builder.build_conditional_branch(has_drop_fn, drop_block, free_block).unwrap();
+
+        self.builder.position_at_end(drop_block);
+        self.builder.build_indirect_call(fn_type, drop_fn, &[env.into()], "").unwrap();
+        self.builder.build_unconditional_branch(free_block).unwrap();
+
+        self.builder.position_at_end(free_block);
+
+        if self.compiler.options.sanitize_memory {
+            let location = self.builder.build_global_string_ptr("closure environment", "sanloc").unwrap().as_pointer_value();
+            self.builder.build_call(self.get_sanitizer_free_function(), &[env.into(), location.into()], "").unwrap();
         } else {
-            panic!("Function type expected");
+            self.builder.build_call(self.get_free_function(), &[env.into()], "").unwrap();
+        }
+
+        self.builder.build_unconditional_branch(done_block).unwrap();
+
+        self.builder.position_at_end(done_block);
+        self.builder.build_return(None).unwrap();
+
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
         }
+
+        function
     }
 
-    fn visit_struct(&mut self, node: &StructItem) {
-        let mut field_types = Vec::new();
+    // `void drop(ptr env)` for a lambda capturing the function values at `fields` of `env_type` by value
+    fn build_closure_drop_function(&self, name: &str, env_type: inkwell::types::StructType<'ctx>, fields: &[u32]) -> FunctionValue<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
 
-        let struct_type_id = self.symbol_table.types.get(node.name.as_ref()).unwrap();
-        let struct_type_outer = self.global_table.type_arena.get(*struct_type_id);
+        let fn_type = self.context.void_type().fn_type(&[ptr_type.into()], false);
+        let function = self.module.add_function(name, fn_type, Some(Linkage::Private));
+        let current_block = self.builder.get_insert_block();
 
-        let mut struct_field_map = HashMap::new();
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
 
-        if let ResolvedType::Struct(struct_type) = &*struct_type_outer {
-            let struct_type = struct_type.clone();
-            drop(struct_type_outer);
-            for (field_name, &field_type_id) in &struct_type.members {
-                struct_field_map.insert(field_name.clone(), field_types.len() as u32);
+        let env = function.get_nth_param(0).unwrap().into_pointer_value();
 
-                let field_type = self.global_table.type_arena.get(field_type_id);
-                match *field_type {
-                    ResolvedType::Integer => field_types.push(self.context.i32_type().into()),
-                    ResolvedType::Char => field_types.push(self.context.i8_type().into()),
-                    ResolvedType::Pointer(_) => field_types.push(self.context.ptr_type(AddressSpace::default()).into()),
-                    _ => unimplemented!("Field type not implemented"),
-                }
-            }
-        } else {
-            panic!("Struct type expected");
+        for &field in fields {
+            let field_ptr = self.builder.build_struct_gep(env_type, env, field, "capture").unwrap();
+            let captured = self.builder.build_load(self.closure_type(), field_ptr, "captureload").unwrap();
+            self.builder.build_call(self.get_closure_release_function(), &[self.closure_env(captured).into()], "").unwrap();
         }
 
-        self.struct_field_maps.insert(node.name.to_string(), struct_field_map);
+        self.builder.build_return(None).unwrap();
 
-        let struct_type = self.context.opaque_struct_type(&node.name.to_string());
-        struct_type.set_body(&field_types, false);
+        if let Some(current_block) = current_block {
+            self.builder.position_at_end(current_block);
+        }
 
-        self.struct_types.insert(node.name.to_string(), struct_type);
+        function
     }
 
-    fn visit_constructor(&mut self, _node: &ConstructorItem) {
-        
+    fn make_closure_function(&self, fn_type: &FunctionType) -> inkwell::types::FunctionType<'ctx> {
+        self.with_env_param(self.make_function(fn_type))
     }
 
-    fn visit_scope(&mut self, node: &Scope) -> () {
-        for item in &node.items {
-            item.accept_visitor(self);
+    fn with_env_param(&self, fn_type: inkwell::types::FunctionType<'ctx>) -> inkwell::types::FunctionType<'ctx> {
+        let mut param_types: Vec<BasicMetadataTypeEnum> = vec![self.context.ptr_type(AddressSpace::default()).into()];
+        param_types.extend(fn_type.get_param_types());
+
+        match fn_type.get_return_type() {
+            Some(ret_type) => ret_type.fn_type(&param_types, false),
+            None => self.context.void_type().fn_type(&param_types, false),
         }
     }
-}
\ No newline at end of file
+
+    fn make_closure(&self, function: FunctionValue<'ctx>, env: PointerValue<'ctx>) -> StructValue<'ctx> {
+        let closure = self.builder.build_insert_value(
+            self.closure_type().get_undef(),
+            function.as_global_value().as_pointer_value(),
+            0,
+            "closurefn",
+        ).unwrap();
+
+        self.builder.build_insert_value(closure, env, 1, "closureenv").unwrap().into_struct_value()
+    }
+
+    // Turns a direct reference to a named function into a closure value by wrapping it in
+    // a thunk that drops the environment parameter. Other values are returned unchanged.
+    fn as_closure(&mut self, value: LLVMValueRef) -> LLVMValueRef {
+        let Some(function) = (unsafe { FunctionValue::new(value) }) else {
+            return value;
+        };
+
+        let thunk_name = format!("{}.thunk", function.get_name().to_str().unwrap());
+
+        let thunk = match self.module.get_function(&thunk_name) {
+            Some(thunk) => thunk,
+            None => {
+                let thunk = self.module.add_function(&thunk_name, self.with_env_param(function.get_type()), Some(Linkage::Private));
+                let current_block = self.builder.get_insert_block().unwrap();
+
+                let entry = self.context.append_basic_block(thunk, "entry");
+                self.builder.position_at_end(entry);
+
+                let args: Vec<BasicMetadataValueEnum> = thunk.get_param_iter()
+                    .skip(1)
+                    .map(|param| param.into())
+                    .collect();
+
+                let call_site = self.builder.build_call(function, &args, "thunkcall").unwrap();
+
+                match call_site.try_as_basic_value().basic() {
+                    Some(ret_value) => self.builder.build_return(Some(&ret_value)).unwrap(),
+                    None => self.builder.build_return(None).unwrap(),
+                };
+
+                self.builder.position_at_end(current_block);
+                thunk
+            }
+        };
+
+        let null_env = self.context.ptr_type(AddressSpace::default()).const_null();
+        self.make_closure(thunk, null_env).as_value_ref()
+    }
+
+    // Generates `expr` for its value. Named functions used as values become closures.
+    fn generate_value(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> BasicValueEnum<'ctx> {
+        expr.accept_visitor(self);
+        let value = self.as_closure(self.value);
+
+        unsafe { BasicValueEnum::new(value) }
+    }
+
+    // Generates `expr` for a use that only borrows its value. A new value that has to be cleaned up
+    // lives until the end of the full expression making it.
+    fn build_value(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> BasicValueEnum<'ctx> {
+        let value = self.generate_value(expr);
+
+        if expr.is_temporary() {
+            self.register_temporary(self.ast_type(expr), value);
+        }
+
+        value
+    }
+
+    // Generates `expr` for a variable, member, element or exit value, which takes ownership of it. A new value
+    // is handed over as it is. A function value that lives somewhere gets another reference, and a struct with
+    // a destructor is moved out of its local variable; the struct checker rejects moving one out of anything else.
+    fn build_owned(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> BasicValueEnum<'ctx> {
+        let value = self.generate_value(expr);
+
+        if expr.is_temporary() {
+            return value;
+        }
+
+        match self.cleanup(self.ast_type(expr)) {
+            Some(Cleanup::Release) => {
+                self.builder.build_call(self.get_closure_retain_function(), &[self.closure_env(value).into()], "").unwrap();
+            },
+            Some(Cleanup::Destroy(_)) => {
+                let drop_flag = match expr {
+                    ASTEnum::Var(var) => self.symbol_table().variables.get(&var.get_id())
+                        .and_then(|decl_id| self.owned_locals.get(&*decl_id))
+                        .and_then(|local| local.drop_flag),
+                    _ => None,
+                };
+
+                if let Some(drop_flag) = drop_flag {
+                    self.builder.build_store(drop_flag, self.context.bool_type().const_zero()).unwrap();
+                }
+            },
+            None => {},
+        }
+
+        value
+    }
+
+    // Generates an expression whose value is not used, which is cleaned up right away along with the
+    // temporaries the expression made
+    fn build_statement(&mut self, expr: &'ast ASTEnum<LocalResolved>) {
+        let depth = self.temporaries.len();
+        expr.accept_visitor(self);
+
+        let type_id = self.symbol_table().ast_types.get(&expr.get_id()).map(|type_id| *type_id);
+
+        if expr.is_temporary() && let Some(type_id) = type_id && self.cleanup(type_id).is_some() {
+            let value = unsafe { BasicValueEnum::new(self.value) };
+            self.register_temporary(type_id, value);
+        }
+
+        self.end_full_expression(depth);
+    }
+
+    fn cleanup(&self, type_id: TypeId) -> Option<Cleanup<'ctx>> {
+        match self.resolved_type(type_id) {
+            ResolvedType::Function(_) => Some(Cleanup::Release),
+            _ => self.get_destructor(type_id).map(Cleanup::Destroy),
+        }
+    }
+
+    fn register_temporary(&mut self, type_id: TypeId, value: BasicValueEnum<'ctx>) {
+        let Some(cleanup) = self.cleanup(type_id) else {
+            return;
+        };
+
+        let ptr = match cleanup {
+            // The destructor needs an address, so the value is spilled to the stack
+            Cleanup::Destroy(_) => {
+                let slot = self.build_entry_alloca(value.get_type(), "temp");
+                self.builder.build_store(slot, value).unwrap();
+                slot
+            },
+            Cleanup::Release => self.closure_env(value),
+        };
+
+        self.temporaries.push((ptr, cleanup));
+    }
+
+    fn build_cleanup(&self, ptr: PointerValue<'ctx>, cleanup: Cleanup<'ctx>) {
+        let function = match cleanup {
+            Cleanup::Destroy(destructor) => destructor,
+            Cleanup::Release => self.get_closure_release_function(),
+        };
+
+        self.builder.build_call(function, &[ptr.into()], "").unwrap();
+    }
+
+    // Cleans up the temporaries made since `depth`, latest first. They stay registered, for exits that
+    // leave the full expression on one path only.
+    fn build_temporary_drops(&self, depth: usize) {
+        for &(ptr, cleanup) in self.temporaries[depth..].iter().rev() {
+            self.build_cleanup(ptr, cleanup);
+        }
+    }
+
+    fn end_full_expression(&mut self, depth: usize) {
+        self.build_temporary_drops(depth);
+        self.temporaries.truncate(depth);
+    }
+
+    // Generates the address of what `expr` names, for assignments, `&`, references and for reaching into
+    // fixed-size arrays and structs. Values that live nowhere, like a call's result, are spilled to the stack.
+    fn build_place(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> PointerValue<'ctx> {
+        let is_place = match expr {
+            ASTEnum::Var(_) | ASTEnum::ArrayAccess(_) | ASTEnum::StructAccess(_) => true,
+            ASTEnum::Unary(unary) => matches!(unary.operator, UnaryOperator::Deref),
+            _ => false,
+        };
+
+        if is_place {
+            // Taken back by the node itself before it visits anything else
+            self.lvalue_mode = true;
+            expr.accept_visitor(self);
+
+            return unsafe { PointerValue::new(self.value) };
+        }
+
+        let value = self.build_value(expr);
+        let slot = self.build_entry_alloca(value.get_type(), "temp");
+        self.builder.build_store(slot, value).unwrap();
+
+        slot
+    }
+
+    // Reference parameters are passed the address of their argument, the others its value
+    fn build_arguments(&mut self, arguments: &'ast [ASTEnum<LocalResolved>], param_types: &[TypeId]) -> Vec<BasicMetadataValueEnum<'ctx>> {
+        arguments.iter().zip(param_types)
+            .map(|(argument, &param_type_id)| match self.resolved_type(param_type_id) {
+                ResolvedType::Reference(_) => self.build_place(argument).into(),
+                _ => self.build_value(argument).into(),
+            })
+            .collect()
+    }
+
+    // Generates the body of `function`, whose parameters from `first_param` on are `parameters`. `bind` runs
+    // in the entry block first, for what the body sees besides its parameters. Loops, blocks and locals of
+    // the function being generated so far are set aside meanwhile, for lambdas generated in the middle of one.
+    fn build_body(&mut self, function: FunctionValue<'ctx>, first_param: u32, parameters: &'ast [DeclarationExpr<LocalResolved>], body: &'ast BlockExpr<LocalResolved>, bind: impl FnOnce(&mut Self)) {
+        let outer_block = self.builder.get_insert_block();
+        let outer_vars = std::mem::take(&mut self.local_vars);
+        let outer_owned_locals = std::mem::take(&mut self.owned_locals);
+        let outer_drop_scopes = std::mem::take(&mut self.drop_scopes);
+        let outer_temporaries = std::mem::take(&mut self.temporaries);
+        let outer_loops = std::mem::take(&mut self.loops);
+        let outer_blocks = std::mem::take(&mut self.blocks);
+
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
+
+        bind(self);
+
+        for (i, parameter) in parameters.iter().enumerate() {
+            let param = function.get_nth_param(first_param + i as u32).unwrap();
+
+            let param_ptr = self.builder.build_alloca(
+                param.get_type(),
+                parameter.identifier.as_ref(),
+            ).unwrap();
+
+            self.builder.build_store(param_ptr, param).unwrap();
+            self.local_vars.insert(parameter.get_id(), param_ptr);
+        }
+
+        self.drop_scopes.push(Vec::new());
+
+        for expr in &body.exprs {
+            self.build_statement(expr);
+        }
+
+        let scope = self.drop_scopes.pop().unwrap();
+        self.build_drops(&[scope]);
+
+        // The control flow checker makes sure every path of a function with a result returns it
+        match function.get_type().get_return_type() {
+            Some(_) => self.builder.build_unreachable().unwrap(),
+            None => self.builder.build_return(None).unwrap(),
+        };
+
+        self.local_vars = outer_vars;
+        self.owned_locals = outer_owned_locals;
+        self.drop_scopes = outer_drop_scopes;
+        self.temporaries = outer_temporaries;
+        self.loops = outer_loops;
+        self.blocks = outer_blocks;
+
+        if let Some(outer_block) = outer_block {
+            self.builder.position_at_end(outer_block);
+        }
+    }
+
+    // Nothing can follow a branch or return in the same basic block, so whatever comes after an exit goes into a new one
+    fn start_unreachable_block(&self) {
+        let block = self.context.append_basic_block(self.current_function(), "afterexit");
+        self.builder.position_at_end(block);
+    }
+
+    // The value a loop or block expression evaluates to, a phi at its exit block over the values it was
+    // exited with. Paths that reach the exit without one, like a loop condition turning false, give zero.
+    fn build_exit_value(&self, type_id: TypeId, values: &[(BasicBlock<'ctx>, BasicValueEnum<'ctx>)], valueless: &[BasicBlock<'ctx>]) -> LLVMValueRef {
+        if type_id == self.compiler.type_arena.void() {
+            return std::ptr::null_mut();
+        }
+
+        let value_type = self.get_type(type_id);
+        let zero = value_type.const_zero();
+        let phi = self.builder.build_phi(value_type, "exitvalue").unwrap();
+
+        for (block, value) in values {
+            phi.add_incoming(&[(value, *block)]);
+        }
+
+        for block in valueless {
+            phi.add_incoming(&[(&zero, *block)]);
+        }
+
+        phi.as_value_ref()
+    }
+
+    // Cleans up the owned locals of `scopes` in reverse declaration order
+    fn build_drops(&self, scopes: &[Vec<AstId>]) {
+        for decl_id in scopes.iter().flatten().rev() {
+            self.build_local_cleanup(&self.owned_locals[decl_id]);
+        }
+    }
+
+    // Cleans up the value of a local, a struct only while its drop flag says it holds one
+    fn build_local_cleanup(&self, local: &OwnedLocal<'ctx>) {
+        let ptr = match local.cleanup {
+            Cleanup::Destroy(_) => local.slot,
+            Cleanup::Release => {
+                let env_ptr = self.builder.build_struct_gep(self.closure_type(), local.slot, 1, "envptr").unwrap();
+                self.builder.build_load(self.context.ptr_type(AddressSpace::default()), env_ptr, "closureenv").unwrap().into_pointer_value()
+            },
+        };
+
+        let Some(drop_flag) = local.drop_flag else {
+            self.build_cleanup(ptr, local.cleanup);
+            return;
+        };
+
+        let function = self.current_function();
+        let drop_block = self.context.append_basic_block(function, "drop");
+        let after_drop_block = self.context.append_basic_block(function, "afterdrop");
+
+        let holds_value = self.builder.build_load(self.context.bool_type(), drop_flag, "dropflag").unwrap().into_int_value();
+        self.builder.build_conditional_branch(holds_value, drop_block, after_drop_block).unwrap();
+
+        self.builder.position_at_end(drop_block);
+        self.build_cleanup(ptr, local.cleanup);
+        self.builder.build_unconditional_branch(after_drop_block).unwrap();
+
+        self.builder.position_at_end(after_drop_block);
+    }
+
+    fn get_destructor(&self, type_id: TypeId) -> Option<FunctionValue<'ctx>> {
+        self.destructors.get(&type_id).copied()
+    }
+
+    fn make_function(&self, fn_type: &FunctionType) -> inkwell::types::FunctionType<'ctx> {
+        let param_types: Vec<BasicMetadataTypeEnum> = fn_type.param_types.iter()
+            .map(|&param_type_id| self.get_type(param_type_id).into())
+            .collect();
+
+        if fn_type.return_type == self.compiler.type_arena.void() {
+            self.context.void_type().fn_type(&param_types, false)
+        } else {
+            self.get_type(fn_type.return_type).fn_type(&param_types, false)
+        }
+    }
+
+    // Constructors and destructors write through `self`, which points at the stack slot or heap allocation of the caller
+    fn add_constructor(&self, function_name: &str, param_types: &[TypeId]) -> FunctionValue<'ctx> {
+        let mut llvm_param_types: Vec<BasicMetadataTypeEnum> = vec![self.context.ptr_type(AddressSpace::default()).into()];
+        llvm_param_types.extend(param_types.iter().map(|&param_type_id| BasicMetadataTypeEnum::from(self.get_type(param_type_id))));
+
+        self.module.add_function(function_name, self.context.void_type().fn_type(&llvm_param_types, false), None)
+    }
+
+    // Constant values fold into constants of the declared type, a str into a private global with its bytes
+    fn const_value(&self, value: &ConstValue, type_id: TypeId) -> BasicValueEnum<'ctx> {
+        match value {
+            ConstValue::Int(value, _) => self.get_int_type(type_id).const_int(*value as u64, false).into(),
+            ConstValue::Float(value, _) => self.get_float_type(type_id).const_float(*value).into(),
+            ConstValue::Bool(value) => self.context.bool_type().const_int(*value as u64, false).into(),
+            ConstValue::Char(value) => self.context.i8_type().const_int(*value as u64, false).into(),
+            ConstValue::Str(value) => self.const_str(value).into(),
+        }
+    }
+}
+
+impl<'ctx, 'ast> CodeGen<'ctx, 'ast> {
+    pub fn new(log_target: &'ctx dyn LogTarget, context: &'ctx Context, compiler: &'ctx Compiler<'ast>) -> Self {
+        let module = context.create_module("main_module");
+        let builder = context.create_builder();
+
+        Self {
+            log_target,
+            context,
+            module,
+            builder,
+            compiler,
+            symbol_table: None,
+            source_file: String::new(),
+            value: std::ptr::null_mut(),
+            local_vars: HashMap::new(),
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            struct_types: HashMap::new(),
+            struct_field_maps: HashMap::new(),
+            struct_items: HashMap::new(),
+            constructors: HashMap::new(),
+            destructors: HashMap::new(),
+            owned_locals: HashMap::new(),
+            drop_scopes: Vec::new(),
+            temporaries: Vec::new(),
+            loops: Vec::new(),
+            blocks: Vec::new(),
+            lvalue_mode: false,
+        }
+    }
+
+    // Generates the scopes of the whole program, each given with the file it was parsed from
+    pub fn compile(&mut self, scopes: &[(&Path, &'ast Scope<LocalResolved>)]) {
+        let compiler = self.compiler;
+        let symbol_tables: Vec<_> = scopes.iter()
+            .map(|(_, scope)| &compiler.symbol_tables[scope.path.segments.as_slice()])
+            .collect();
+
+        // Struct types are created first, so that members and signatures can name any of them
+        for (&(_, scope), &symbol_table) in scopes.iter().zip(&symbol_tables) {
+            for item in &scope.items {
+                if let ASTEnum::Struct(struct_item) = item {
+                    let type_id = *symbol_table.types.get(&*struct_item.name).unwrap();
+
+                    self.struct_types.insert(type_id, self.context.opaque_struct_type(&struct_item.name));
+                    self.struct_items.insert(type_id, (struct_item, symbol_table));
+                }
+            }
+        }
+
+        let struct_items: Vec<_> = self.struct_items.iter()
+            .map(|(&type_id, &(struct_item, _))| (type_id, struct_item))
+            .collect();
+
+        // Members are laid out in declaration order
+        for (type_id, struct_item) in struct_items {
+            let ResolvedType::Struct(struct_type) = self.resolved_type(type_id) else {
+                panic!("Expected struct type");
+            };
+
+            let mut field_types: Vec<BasicTypeEnum> = Vec::new();
+            let mut field_map = HashMap::new();
+
+            for (_, _, member_name, _) in &struct_item.members {
+                field_map.insert(member_name.to_string(), field_types.len() as u32);
+                field_types.push(self.get_type(struct_type.members[member_name.as_str()]));
+            }
+
+            self.struct_types[&type_id].set_body(&field_types, false);
+            self.struct_field_maps.insert(type_id, field_map);
+        }
+
+        for (&(_, scope), &symbol_table) in scopes.iter().zip(&symbol_tables) {
+            self.symbol_table = Some(symbol_table);
+            self.declare_items(scope);
+        }
+
+        for (&(file, scope), &symbol_table) in scopes.iter().zip(&symbol_tables) {
+            self.log_debug(self.log_target, format!("Generating scope {}", scope.path.to_string()));
+
+            self.symbol_table = Some(symbol_table);
+            self.source_file = file.display().to_string();
+            scope.accept_visitor(self);
+        }
+    }
+
+    // Adds the functions, constructors and globals of a scope to the module, bodies are generated later
+    fn declare_items(&mut self, scope: &'ast Scope<LocalResolved>) {
+        for item in &scope.items {
+            match item {
+                ASTEnum::Function(function) => {
+                    let type_id = *self.symbol_table().declaration_types.get(&function.get_id()).unwrap();
+                    let fn_type = self.make_function(&self.function_type(type_id));
+
+                    let value = match &function.body {
+                        // Extern functions are declared once, however many scopes declare them
+                        None => self.module.get_function(&function.name)
+                            .unwrap_or_else(|| self.module.add_function(&function.name, fn_type, Some(Linkage::External))),
+                        Some(_) => {
+                            // The startup code branches to `main` once memory is set up, so that is the entry function's symbol
+                            let symbol = if is_entry(&function.attributes) { "main" } else { function.name.as_str() };
+                            self.module.add_function(symbol, fn_type, None)
+                        },
+                    };
+
+                    if let Some(name) = section(&function.attributes) {
+                        value.set_section(Some(name));
+                    }
+
+                    self.functions.insert(function.get_id(), value);
+                },
+                ASTEnum::Struct(struct_item) => {
+                    let type_id = *self.symbol_table().types.get(&*struct_item.name).unwrap();
+
+                    for (index, constructor) in struct_item.constructors.iter().enumerate() {
+                        let param_types: Vec<TypeId> = constructor.parameters.iter()
+                            .map(|parameter| *self.symbol_table().declaration_types.get(&parameter.get_id()).unwrap())
+                            .collect();
+
+                        let function = self.add_constructor(&format!("{}.ctor.{}", *struct_item.name, index), &param_types);
+                        self.constructors.insert(constructor.get_id(), (function, param_types));
+                    }
+
+                    if struct_item.destructor.is_some() {
+                        let function = self.add_constructor(&format!("{}.dtor", *struct_item.name), &[]);
+                        self.destructors.insert(type_id, function);
+                    }
+                },
+                ASTEnum::Global(global) => {
+                    let type_id = *self.symbol_table().declaration_types.get(&global.get_id()).unwrap();
+
+                    // There is no function to emit instructions into, so the initializer is the value folded by the constant evaluator
+                    let initializer = {
+                        let value = self.symbol_table().const_values.get(&global.get_id()).expect("Global initializer was not folded");
+                        self.const_value(&value, type_id)
+                    };
+
+                    let global_value = self.module.add_global(initializer.get_type(), None, &global.name);
+                    global_value.set_initializer(&initializer);
+                    global_value.set_constant(global.kind == GlobalKind::Const);
+
+                    if let Some(name) = section(&global.attributes) {
+                        global_value.set_section(Some(name));
+                    }
+
+                    self.globals.insert(global.get_id(), (global_value, type_id));
+                },
+                _ => {},
+            }
+        }
+    }
+
+    // Builds the machine code generator for --target, --cpu and --features, the host when they are unset.
+    // Triples in TARGETS fill in the CPU and features left unset
+    fn create_target_machine(&self) -> Result<TargetMachine, String> {
+        Target::initialize_all(&InitializationConfig::default());
+
+        let options = &self.compiler.options;
+        let spec = options.target_triple.as_deref().and_then(TargetSpec::find);
+
+        let (triple, cpu, features) = match &options.target_triple {
+            Some(triple) => (
+                TargetTriple::create(triple),
+                options.target_cpu.clone().unwrap_or_else(|| spec.map_or("generic", |spec| spec.cpu).to_string()),
+                options.target_features.clone().unwrap_or_else(|| spec.map_or("", |spec| spec.features).to_string()),
+            ),
+            None => (
+                TargetMachine::get_default_triple(),
+                options.target_cpu.clone().unwrap_or_else(|| TargetMachine::get_host_cpu_name().to_string()),
+                options.target_features.clone().unwrap_or_else(|| TargetMachine::get_host_cpu_features().to_string()),
+            ),
+        };
+
+        // The default linker builds position-independent executables
+        let relocation = match spec {
+            Some(spec) if spec.static_relocation => RelocMode::Static,
+            _ => RelocMode::PIC,
+        };
+
+        let target = Target::from_triple(&triple).map_err(|e| format!("Unknown target {}: {}", triple, e))?;
+
+        let optimization_level = match options.opt_level {
+            OptLevel::O0 => OptimizationLevel::None,
+            OptLevel::O1 => OptimizationLevel::Less,
+            OptLevel::O2 | OptLevel::Os => OptimizationLevel::Default,
+            OptLevel::O3 => OptimizationLevel::Aggressive,
+        };
+
+        target.create_target_machine(
+            &triple,
+            &cpu,
+            &features,
+            optimization_level,
+            relocation,
+            CodeModel::Default,
+        ).ok_or_else(|| format!("Failed to create a target machine for {} ({}, '{}')", triple, cpu, features))
+    }
+
+    // Runs the pass pipeline of the chosen -O level over the module, tuned for the target machine
+    fn optimize(&self, target_machine: &TargetMachine) -> Result<(), String> {
+        self.module.set_triple(&target_machine.get_triple());
+        self.module.set_data_layout(&target_machine.get_target_data().get_data_layout());
+
+        let pipeline = match self.compiler.options.opt_level {
+            OptLevel::O0 => "default<O0>",
+            OptLevel::O1 => "default<O1>",
+            OptLevel::O2 => "default<O2>",
+            OptLevel::O3 => "default<O3>",
+            OptLevel::Os => "default<Os>",
+        };
+
+        self.module.run_passes(pipeline, target_machine, PassBuilderOptions::create())
+            .map_err(|e| format!("Failed to optimize module: {}", e))
+    }
+
+    // Optimizes the module and writes it out in the form asked for by --emit
+    pub fn emit(&self, output_file: impl AsRef<Path>) -> Result<(), String> {
+        let output_file = output_file.as_ref();
+
+        self.module.verify().map_err(|e| format!("Generated an invalid module: {}", e))?;
+
+        let target_machine = self.create_target_machine()?;
+        self.optimize(&target_machine)?;
+
+        match self.compiler.options.emit {
+            Emit::Executable => {
+                let temp_dir = TempDir::create()?;
+                let object_file = temp_dir.path().join("output.o");
+
+                self.write_machine_code(&target_machine, FileType::Object, &object_file)?;
+                self.link(&object_file, output_file)
+            },
+            Emit::Object => self.write_machine_code(&target_machine, FileType::Object, output_file),
+            Emit::Assembly => self.write_machine_code(&target_machine, FileType::Assembly, output_file),
+            Emit::LlvmIr => {
+                self.module.print_to_file(output_file)
+                    .map_err(|e| format!("Failed to write LLVM IR: {}", e))
+            },
+        }
+    }
+
+    fn write_machine_code(&self, target_machine: &TargetMachine, file_type: FileType, path: &Path) -> Result<(), String> {
+        target_machine.write_to_file(&self.module, file_type, path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    // Runs the --linker program as `<linker> <object> <link args>... -o <output>`. Freestanding builds
+    // pass -nostdlib, so the C library and its startup files stay out and the link args bring the rest
+    fn link(&self, object_file: &Path, output_file: &Path) -> Result<(), String> {
+        let options = &self.compiler.options;
+
+        let mut command = Command::new(&options.linker);
+        command.arg(object_file);
+
+        if options.freestanding {
+            command.arg("-nostdlib");
+        }
+
+        let output = command
+            .args(&options.link_args)
+            .arg("-o")
+            .arg(output_file)
+            .output()
+            .map_err(|e| format!("Failed to execute {}: {}", options.linker, e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Linking with {} failed:\n{}", options.linker, stderr));
+        }
+
+        Ok(())
+    }
+
+    // Allocas outside the entry block are dynamic, and the optimizer can only promote static ones to registers
+    fn build_entry_alloca(&self, value_type: BasicTypeEnum<'ctx>, name: &str) -> PointerValue<'ctx> {
+        let entry = self.current_function().get_first_basic_block().unwrap();
+
+        let entry_builder = self.context.create_builder();
+
+        match entry.get_first_instruction() {
+            Some(instruction) => entry_builder.position_before(&instruction),
+            None => entry_builder.position_at_end(entry),
+        }
+
+        entry_builder.build_alloca(value_type, name).unwrap()
+    }
+}
+
+// Code generation defaults for a triple the compiler has been set up for
+pub struct TargetSpec {
+    pub triple: &'static str,
+    pub cpu: &'static str,
+    pub features: &'static str,
+    // No dynamic loader to fix up addresses, so code is linked at a fixed location
+    pub static_relocation: bool,
+}
+
+pub const TARGETS: &[TargetSpec] = &[
+    // Cortex-M4F: Thumb-2 with the single precision FPv4 unit, floats passed in VFP registers
+    TargetSpec {
+        triple: "thumbv7em-none-eabihf",
+        cpu: "cortex-m4",
+        features: "+thumb-mode,+vfp4d16sp",
+        static_relocation: true,
+    },
+];
+
+impl TargetSpec {
+    pub fn find(triple: &str) -> Option<&'static TargetSpec> {
+        TARGETS.iter().find(|target| target.triple == triple)
+    }
+}
+
+// A directory only the current user can access, removed with everything in it when dropped
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn create() -> Result<TempDir, String> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+        let path = env::temp_dir().join(format!("marlin-{}-{}", process::id(), nanos));
+
+        let mut builder = DirBuilder::new();
+
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+        // Fails if the directory already exists instead of reusing one somebody else created
+        builder.create(&path)
+            .map_err(|e| format!("Failed to create temporary directory {}: {}", path.display(), e))?;
+
+        Ok(TempDir { path })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, LocalResolved, CodeGenWitness> for CodeGen<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<LocalResolved>) -> CodeGenResult {
+        let lvalue = std::mem::take(&mut self.lvalue_mode);
+        let element_type = self.get_type(self.ast_type(node));
+        let position = node.index.get_position();
+
+        let ptr = match self.resolved_type(self.ast_type(&node.expr)) {
+            ResolvedType::Str | ResolvedType::Array(_) => {
+                let slice = self.build_value(&node.expr).into_struct_value();
+                let index = self.build_index(&node.index);
+
+                let len = self.builder.build_extract_value(slice, 1, "slicelen").unwrap().into_int_value();
+                self.build_bounds_check(index, len, position);
+
+                let ptr = self.builder.build_extract_value(slice, 0, "sliceptr").unwrap().into_pointer_value();
+                unsafe { self.builder.build_in_bounds_gep(element_type, ptr, &[index], "arrayidx").unwrap() }
+            },
+            // Fixed-size arrays are indexed in place, so we need the array's address rather than its value
+            ResolvedType::FixedArray(_, length) => {
+                let ptr = self.build_place(&node.expr);
+                let index = self.build_index(&node.index);
+
+                let len = self.context.i32_type().const_int(length as u64, false);
+                self.build_bounds_check(index, len, position);
+
+                unsafe { self.builder.build_in_bounds_gep(element_type, ptr, &[index], "arrayidx").unwrap() }
+            },
+            // Only pointers can lead into memory from `new`, slices and fixed-size arrays have their own bounds checks
+            ResolvedType::Pointer(_) => {
+                let ptr = self.build_value(&node.expr).into_pointer_value();
+                let index = self.build_index(&node.index);
+
+                let offset = self.builder.build_int_mul(index, element_type.size_of().unwrap(), "sanoffset").unwrap();
+                self.build_memory_check(ptr, offset, element_type, node.get_position());
+
+                unsafe { self.builder.build_in_bounds_gep(element_type, ptr, &[index], "arrayidx").unwrap() }
+            },
+            _ => panic!("Expected pointer or array type for array access"),
+        };
+
+        self.value = if lvalue {
+            ptr.as_value_ref()
+        } else {
+            self.builder.build_load(element_type, ptr, "arrayload").unwrap().as_value_ref()
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<LocalResolved>) -> CodeGenResult {
+        let llvm_array_type = self.get_type(self.ast_type(node)).into_array_type();
+        let mut array_value = llvm_array_type.get_undef();
+
+        for (i, element) in node.elements.iter().enumerate() {
+            let element_value = self.build_owned(element);
+            array_value = self.builder.build_insert_value(array_value, element_value, i as u32, "arrayelem").unwrap().into_array_value();
+        }
+
+        self.value = array_value.as_value_ref();
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<LocalResolved>) -> CodeGenResult {
+        let owned_local = match &node.assignee {
+            ASTEnum::Var(var) => self.symbol_table().variables.get(&var.get_id()).and_then(|decl_id| self.owned_locals.get(&*decl_id).copied()),
+            _ => None,
+        };
+
+        let ptr = self.build_place(&node.assignee);
+        let value = self.build_owned(&node.expr);
+
+        // A local cleans up the value it is losing. Any other place isn't cleaned up at all, the value it loses
+        // and the one it gets are leaked.
+        if let Some(local) = &owned_local {
+            self.build_local_cleanup(local);
+        }
+
+        self.builder.build_store(ptr, value).unwrap();
+
+        if let Some(drop_flag) = owned_local.and_then(|local| local.drop_flag) {
+            self.builder.build_store(drop_flag, self.context.bool_type().const_all_ones()).unwrap();
+        }
+
+        self.value = value.as_value_ref();
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<LocalResolved>) -> CodeGenResult {
+        // The right operand of `and` and `or` only runs when the left one doesn't decide the result
+        if matches!(node.operator, BinaryOperator::And | BinaryOperator::Or) {
+            let left = self.build_value(&node.left).into_int_value();
+            let left_block = self.builder.get_insert_block().unwrap();
+
+            let function = self.current_function();
+            let right_block = self.context.append_basic_block(function, "rhs");
+            let merge_block = self.context.append_basic_block(function, "logicalcont");
+
+            if matches!(node.operator, BinaryOperator::And) {
+                self.builder.build_conditional_branch(left, right_block, merge_block).unwrap();
+            } else {
+                self.builder.build_conditional_branch(left, merge_block, right_block).unwrap();
+            }
+
+            self.builder.position_at_end(right_block);
+            let depth = self.temporaries.len();
+            let right = self.build_value(&node.right).into_int_value();
+            self.end_full_expression(depth);
+            let right_end_block = self.builder.get_insert_block().unwrap();
+            self.builder.build_unconditional_branch(merge_block).unwrap();
+
+            self.builder.position_at_end(merge_block);
+            let phi = self.builder.build_phi(self.context.bool_type(), "logicaltmp").unwrap();
+            phi.add_incoming(&[(&left, left_block), (&right, right_end_block)]);
+
+            self.value = phi.as_value_ref();
+            return self.witness(node);
+        }
+
+        let left_value = self.build_value(&node.left);
+        let right_value = self.build_value(&node.right);
+
+        let left_type_id = self.ast_type(&node.left);
+
+        if self.resolved_type(left_type_id) == ResolvedType::Str {
+            let call_site = self.builder.build_call(self.get_str_eq_function(), &[left_value.into(), right_value.into()], "streq").unwrap();
+            let equal = call_site.try_as_basic_value().basic().unwrap().into_int_value();
+
+            self.value = match node.operator {
+                BinaryOperator::Equal => equal.as_value_ref(),
+                BinaryOperator::NotEqual => self.builder.build_not(equal, "strne").unwrap().as_value_ref(),
+                _ => panic!("Binary operator not implemented for str"),
+            };
+
+            return self.witness(node);
+        }
+
+        if let (BasicValueEnum::PointerValue(left), BasicValueEnum::PointerValue(right)) = (left_value, right_value) {
+            let predicate = match node.operator {
+                BinaryOperator::Equal => IntPredicate::EQ,
+                BinaryOperator::NotEqual => IntPredicate::NE,
+                _ => panic!("Binary operator not implemented for pointers"),
+            };
+
+            self.value = self.builder.build_int_compare(predicate, left, right, "ptrcmptmp").unwrap().as_value_ref();
+            return self.witness(node);
+        }
+
+        if self.is_float(left_type_id) {
+            let left = left_value.into_float_value();
+            let right = right_value.into_float_value();
+
+            // Comparisons are ordered (false if either side is NaN), except != which is unordered
+            self.value = match node.operator {
+                BinaryOperator::Plus => self.builder.build_float_add(left, right, "faddtmp").unwrap().as_value_ref(),
+                BinaryOperator::Minus => self.builder.build_float_sub(left, right, "fsubtmp").unwrap().as_value_ref(),
+                BinaryOperator::Times => self.builder.build_float_mul(left, right, "fmultmp").unwrap().as_value_ref(),
+                BinaryOperator::Divide => self.builder.build_float_div(left, right, "fdivtmp").unwrap().as_value_ref(),
+                BinaryOperator::Equal => self.builder.build_float_compare(FloatPredicate::OEQ, left, right, "feqtmp").unwrap().as_value_ref(),
+                BinaryOperator::NotEqual => self.builder.build_float_compare(FloatPredicate::UNE, left, right, "fnetmp").unwrap().as_value_ref(),
+                BinaryOperator::Less => self.builder.build_float_compare(FloatPredicate::OLT, left, right, "flttmp").unwrap().as_value_ref(),
+                BinaryOperator::LessEqual => self.builder.build_float_compare(FloatPredicate::OLE, left, right, "fletmp").unwrap().as_value_ref(),
+                BinaryOperator::Greater => self.builder.build_float_compare(FloatPredicate::OGT, left, right, "fgttmp").unwrap().as_value_ref(),
+                BinaryOperator::GreaterEqual => self.builder.build_float_compare(FloatPredicate::OGE, left, right, "fgetmp").unwrap().as_value_ref(),
+                _ => panic!("Binary operator not implemented for floats"),
+            };
+
+            return self.witness(node);
+        }
+
+        let unsigned = self.is_unsigned(left_type_id);
+        let left = left_value.into_int_value();
+        let mut right = right_value.into_int_value();
+
+        // The shift amount may be of any integer type, LLVM wants it as wide as the shifted value
+        if matches!(node.operator, BinaryOperator::LeftShift | BinaryOperator::RightShift) {
+            let right_unsigned = self.is_unsigned(self.ast_type(&node.right));
+            right = self.builder.build_int_cast_sign_flag(right, left.get_type(), !right_unsigned, "shiftamount").unwrap();
+        }
+
+        let signed_or_unsigned = |signed, unsigned_predicate| if unsigned { unsigned_predicate } else { signed };
+
+        self.value = match node.operator {
+            BinaryOperator::Plus => self.builder.build_int_add(left, right, "addtmp").unwrap(),
+            BinaryOperator::Minus => self.builder.build_int_sub(left, right, "subtmp").unwrap(),
+            BinaryOperator::Times => self.builder.build_int_mul(left, right, "multmp").unwrap(),
+            BinaryOperator::Divide if unsigned => self.builder.build_int_unsigned_div(left, right, "divtmp").unwrap(),
+            BinaryOperator::Divide => self.builder.build_int_signed_div(left, right, "divtmp").unwrap(),
+            BinaryOperator::Modulo if unsigned => self.builder.build_int_unsigned_rem(left, right, "modtmp").unwrap(),
+            BinaryOperator::Modulo => self.builder.build_int_signed_rem(left, right, "modtmp").unwrap(),
+            BinaryOperator::Equal => self.builder.build_int_compare(IntPredicate::EQ, left, right, "eqtmp").unwrap(),
+            BinaryOperator::NotEqual => self.builder.build_int_compare(IntPredicate::NE, left, right, "netmp").unwrap(),
+            BinaryOperator::Less => self.builder.build_int_compare(signed_or_unsigned(IntPredicate::SLT, IntPredicate::ULT), left, right, "lttmp").unwrap(),
+            BinaryOperator::LessEqual => self.builder.build_int_compare(signed_or_unsigned(IntPredicate::SLE, IntPredicate::ULE), left, right, "letmp").unwrap(),
+            BinaryOperator::Greater => self.builder.build_int_compare(signed_or_unsigned(IntPredicate::SGT, IntPredicate::UGT), left, right, "gttmp").unwrap(),
+            BinaryOperator::GreaterEqual => self.builder.build_int_compare(signed_or_unsigned(IntPredicate::SGE, IntPredicate::UGE), left, right, "getmp").unwrap(),
+            BinaryOperator::BitwiseAnd => self.builder.build_and(left, right, "andtmp").unwrap(),
+            BinaryOperator::BitwiseOr => self.builder.build_or(left, right, "ortmp").unwrap(),
+            BinaryOperator::BitwiseXor => self.builder.build_xor(left, right, "xortmp").unwrap(),
+            BinaryOperator::LeftShift => self.builder.build_left_shift(left, right, "shltmp").unwrap(),
+            // Arithmetic shift for signed operands, logical shift (lshr) for unsigned ones
+            BinaryOperator::RightShift => self.builder.build_right_shift(left, right, !unsigned, "shrtmp").unwrap(),
+            BinaryOperator::And | BinaryOperator::Or => unreachable!(),
+        }.as_value_ref();
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<LocalResolved>) -> CodeGenResult {
+        let exit_block = self.context.append_basic_block(self.current_function(), "afterblock");
+
+        self.blocks.push(ExitFrame {
+            label: None,
+            exit_block,
+            continue_block: None,
+            values: Vec::new(),
+            drop_depth: self.drop_scopes.len(),
+            temporary_depth: self.temporaries.len(),
+        });
+        self.drop_scopes.push(Vec::new());
+
+        for expr in &node.exprs {
+            self.build_statement(expr);
+        }
+
+        let scope = self.drop_scopes.pop().unwrap();
+        self.build_drops(&[scope]);
+
+        let frame = self.blocks.pop().unwrap();
+        let fallthrough_block = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(exit_block).unwrap();
+
+        self.builder.position_at_end(exit_block);
+        self.value = self.build_exit_value(self.ast_type(node), &frame.values, &[fallthrough_block]);
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<LocalResolved>) -> CodeGenResult {
+        let source_type_id = self.ast_type(&node.expr);
+        let target_type_id = self.ast_type(node);
+
+        let source_type = self.resolved_type(source_type_id);
+        let target_type = self.resolved_type(target_type_id);
+
+        // Slicing a fixed-size array borrows its storage, so we need its address
+        if let (ResolvedType::FixedArray(_, length), ResolvedType::Array(_)) = (&source_type, &target_type) {
+            let ptr = self.build_place(&node.expr);
+            let len = self.context.i32_type().const_int(*length as u64, false);
+
+            self.value = self.build_slice(ptr, len).as_value_ref();
+            return self.witness(node);
+        }
+
+        let value = self.build_value(&node.expr);
+
+        self.value = match (&source_type, &target_type) {
+            (ResolvedType::Str, ResolvedType::Pointer(_)) => {
+                self.builder.build_extract_value(value.into_struct_value(), 0, "strptr").unwrap().as_value_ref()
+            },
+            (ResolvedType::Pointer(_), ResolvedType::Str) => {
+                let ptr = value.into_pointer_value();
+                let call_site = self.builder.build_call(self.get_strlen_function(), &[ptr.into()], "strlen").unwrap();
+                let len = call_site.try_as_basic_value().basic().unwrap().into_int_value();
+
+                self.build_slice(ptr, len).as_value_ref()
+            },
+            (ResolvedType::Pointer(_), ResolvedType::Pointer(_)) => value.as_value_ref(),
+            (ResolvedType::Pointer(_), _) => {
+                self.builder.build_ptr_to_int(value.into_pointer_value(), self.get_int_type(target_type_id), "ptrtoint").unwrap().as_value_ref()
+            },
+            (_, ResolvedType::Pointer(_)) => {
+                let ptr_type = self.context.ptr_type(AddressSpace::default());
+                self.builder.build_int_to_ptr(value.into_int_value(), ptr_type, "inttoptr").unwrap().as_value_ref()
+            },
+            (source, target) if source.numeric_type().is_some() && target.numeric_type().is_some() => {
+                let source_numeric = source.numeric_type().unwrap();
+                let target_numeric = target.numeric_type().unwrap();
+
+                match NumericCast::between(source_numeric, target_numeric) {
+                    NumericCast::Identity => value.as_value_ref(),
+                    NumericCast::Truncate => self.builder.build_int_truncate(value.into_int_value(), self.get_int_type(target_type_id), "trunctmp").unwrap().as_value_ref(),
+                    NumericCast::SignExtend => self.builder.build_int_s_extend(value.into_int_value(), self.get_int_type(target_type_id), "sexttmp").unwrap().as_value_ref(),
+                    NumericCast::ZeroExtend => self.builder.build_int_z_extend(value.into_int_value(), self.get_int_type(target_type_id), "zexttmp").unwrap().as_value_ref(),
+                    NumericCast::FloatTruncate => self.builder.build_float_trunc(value.into_float_value(), self.get_float_type(target_type_id), "fptrunctmp").unwrap().as_value_ref(),
+                    NumericCast::FloatExtend => self.builder.build_float_ext(value.into_float_value(), self.get_float_type(target_type_id), "fpexttmp").unwrap().as_value_ref(),
+                    NumericCast::SignedToFloat => self.builder.build_signed_int_to_float(value.into_int_value(), self.get_float_type(target_type_id), "sitofptmp").unwrap().as_value_ref(),
+                    NumericCast::UnsignedToFloat => self.builder.build_unsigned_int_to_float(value.into_int_value(), self.get_float_type(target_type_id), "uitofptmp").unwrap().as_value_ref(),
+                    NumericCast::FloatToSigned => self.builder.build_float_to_signed_int(value.into_float_value(), self.get_int_type(target_type_id), "fptositmp").unwrap().as_value_ref(),
+                    NumericCast::FloatToUnsigned => self.builder.build_float_to_unsigned_int(value.into_float_value(), self.get_int_type(target_type_id), "fptouitmp").unwrap().as_value_ref(),
+                }
+            },
+            // Between char, bool and the integer types. A bool widens to 0 or 1, char is signed like int.
+            _ => {
+                let signed = source_type != ResolvedType::Boolean && !self.is_unsigned(source_type_id);
+                self.builder.build_int_cast_sign_flag(value.into_int_value(), self.get_int_type(target_type_id), signed, "casttmp").unwrap().as_value_ref()
+            },
+        };
+
+        self.witness(node)
+    }
+
+    // Generated along with their struct by visit_struct
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<LocalResolved>) -> CodeGenResult {
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<LocalResolved>) -> CodeGenResult {
+        let constructor_id = *self.symbol_table().constructor_calls.get(&node.get_id()).unwrap();
+        let (constructor, param_types) = self.constructors[&constructor_id].clone();
+
+        let struct_type_id = match self.resolved_type(self.ast_type(node)) {
+            ResolvedType::Pointer(struct_type_id) => struct_type_id,
+            _ => self.ast_type(node),
+        };
+        let struct_type = self.struct_types[&struct_type_id];
+
+        let instance_ptr = if node.is_heap {
+            self.build_alloc(struct_type.into(), None, "newstruct")
+        } else {
+            self.build_entry_alloca(struct_type.into(), "structtmp")
+        };
+
+        let mut arg_values: Vec<BasicMetadataValueEnum> = vec![instance_ptr.into()];
+        arg_values.extend(self.build_arguments(&node.arguments, &param_types));
+
+        self.builder.build_call(constructor, &arg_values, "").unwrap();
+
+        // A stack instance is used by value like any other struct, a heap instance through its pointer
+        self.value = if node.is_heap {
+            instance_ptr.as_value_ref()
+        } else {
+            self.builder.build_load(struct_type, instance_ptr, "structload").unwrap().as_value_ref()
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<LocalResolved>) -> CodeGenResult {
+        let declaration_type_id = *self.symbol_table().declaration_types.get(&node.get_id()).unwrap();
+        let ptr_value = self.build_entry_alloca(self.get_type(declaration_type_id), node.identifier.as_ref());
+        let cleanup = self.cleanup(declaration_type_id);
+
+        match &node.expr {
+            Some(expr) => {
+                // A reference is bound to the place of its initializer, not to a copy of its value
+                let value = match self.resolved_type(declaration_type_id) {
+                    ResolvedType::Reference(_) => self.build_place(expr).as_basic_value_enum(),
+                    _ => self.build_owned(expr),
+                };
+
+                self.builder.build_store(ptr_value, value).unwrap();
+            },
+            // Releasing the null environment of a function value that was never assigned does nothing
+            None if cleanup == Some(Cleanup::Release) => {
+                self.builder.build_store(ptr_value, self.closure_type().const_zero()).unwrap();
+            },
+            None => {},
+        }
+
+        self.local_vars.insert(node.get_id(), ptr_value);
+
+        if let Some(cleanup) = cleanup && !self.drop_scopes.is_empty() {
+            // A struct declared without a value only gets one by assigning it as a whole, not member by member
+            let drop_flag = match cleanup {
+                Cleanup::Destroy(_) => {
+                    let drop_flag = self.build_entry_alloca(self.context.bool_type().into(), "dropflag");
+                    self.builder.build_store(drop_flag, self.context.bool_type().const_int(node.expr.is_some() as u64, false)).unwrap();
+                    Some(drop_flag)
+                },
+                Cleanup::Release => None,
+            };
+
+            self.owned_locals.insert(node.get_id(), OwnedLocal { slot: ptr_value, cleanup, drop_flag });
+            self.drop_scopes.last_mut().unwrap().push(node.get_id());
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<LocalResolved>) -> CodeGenResult {
+        let ptr = self.build_value(&node.expr).into_pointer_value();
+
+        let destructor = match self.resolved_type(self.ast_type(&node.expr)) {
+            ResolvedType::Pointer(pointee_type_id) => self.get_destructor(pointee_type_id),
+            _ => None,
+        };
+
+        // The destructor runs on the pointed-to value before its memory goes, deleting null skips both
+        if let Some(destructor) = destructor {
+            let function = self.current_function();
+            let drop_block = self.context.append_basic_block(function, "drop");
+            let after_drop_block = self.context.append_basic_block(function, "afterdrop");
+
+            let is_null = self.builder.build_is_null(ptr, "isnull").unwrap();
+            self.builder.build_conditional_branch(is_null, after_drop_block, drop_block).unwrap();
+
+            self.builder.position_at_end(drop_block);
+            self.builder.build_call(destructor, &[ptr.into()], "").unwrap();
+            self.builder.build_unconditional_branch(after_drop_block).unwrap();
+
+            self.builder.position_at_end(after_drop_block);
+        }
+
+        if self.compiler.options.sanitize_memory {
+            let location = self.build_location(node.get_position());
+            self.builder.build_call(self.get_sanitizer_free_function(), &[ptr.into(), location.into()], "").unwrap();
+        } else {
+            self.builder.build_call(self.get_free_function(), &[ptr.into()], "").unwrap();
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<LocalResolved>) -> CodeGenResult {
+        // A returned local is moved out, so that it isn't dropped along with the others
+        let value = node.expr.as_ref().map(|expr| self.build_owned(expr));
+
+        if node.exit_type == ExitType::Return {
+            // Leaving the function drops the temporaries and locals of every block it is in
+            self.build_temporary_drops(0);
+            self.build_drops(&self.drop_scopes);
+            self.builder.build_return(value.as_ref().map(|value| value as &dyn BasicValue)).unwrap();
+
+            self.start_unreachable_block();
+            return self.witness(node);
+        }
+
+        let is_result = node.exit_type == ExitType::Result;
+        let frames = if is_result { &self.blocks } else { &self.loops };
+
+        let frame_index = match &node.label {
+            Some(label) => frames.iter().rposition(|frame| frame.label == Some(&**label)).unwrap(),
+            None => frames.len() - 1,
+        };
+
+        // Leaving blocks early still drops their temporaries and locals, from the innermost block out to the target
+        self.build_temporary_drops(frames[frame_index].temporary_depth);
+        self.build_drops(&self.drop_scopes[frames[frame_index].drop_depth..]);
+
+        let current_block = self.builder.get_insert_block().unwrap();
+        let frame = if is_result { &mut self.blocks[frame_index] } else { &mut self.loops[frame_index] };
+
+        let target = match node.exit_type {
+            ExitType::Continue => frame.continue_block.unwrap(),
+            _ => {
+                if let Some(value) = value {
+                    frame.values.push((current_block, value));
+                }
+
+                frame.exit_block
+            },
+        };
+
+        self.builder.build_unconditional_branch(target).unwrap();
+        self.start_unreachable_block();
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<LocalResolved>) -> CodeGenResult {
+        if let Some(body) = &node.body {
+            let function = self.functions[&node.get_id()];
+            self.build_body(function, 0, &node.parameters, body, |_| {});
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<LocalResolved>) -> CodeGenResult {
+        let fn_type = self.function_type(self.ast_type(&node.expr));
+
+        node.expr.accept_visitor(self);
+        let callee = self.value;
+
+        // A function value made just for this call is released once the call is done
+        if node.expr.is_temporary() && unsafe { FunctionValue::new(callee) }.is_none() {
+            self.register_temporary(self.ast_type(&node.expr), unsafe { BasicValueEnum::new(callee) });
+        }
+
+        let mut arg_values = self.build_arguments(&node.arguments, &fn_type.param_types);
+
+        let call_site = match unsafe { FunctionValue::new(callee) } {
+            Some(function) => self.builder.build_call(function, &arg_values, "calltmp").unwrap(),
+            None => {
+                let closure = unsafe { StructValue::new(callee) };
+                let fn_ptr = self.builder.build_extract_value(closure, 0, "closurefn").unwrap().into_pointer_value();
+                let env_ptr = self.builder.build_extract_value(closure, 1, "closureenv").unwrap();
+
+                arg_values.insert(0, env_ptr.into());
+
+                self.builder.build_indirect_call(
+                    self.make_closure_function(&fn_type),
+                    fn_ptr,
+                    &arg_values,
+                    "closurecall",
+                ).unwrap()
+            }
+        };
+
+        self.value = call_site.as_value_ref();
+
+        self.witness(node)
+    }
+
+    // Defined up front by declare_items, so that functions generated before it can already use it
+    fn visit_global(&mut self, node: &'ast GlobalItem<LocalResolved>) -> CodeGenResult {
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<LocalResolved>) -> CodeGenResult {
+        let depth = self.temporaries.len();
+        let condition = self.build_value(&node.condition).into_int_value();
+        self.end_full_expression(depth);
+
+        let function = self.current_function();
+        let then_block = self.context.append_basic_block(function, "then");
+        let else_block = node.fail.as_ref().map(|_| self.context.append_basic_block(function, "else"));
+        let merge_block = self.context.append_basic_block(function, "ifcont");
+
+        self.builder.build_conditional_branch(condition, then_block, else_block.unwrap_or(merge_block)).unwrap();
+
+        self.builder.position_at_end(then_block);
+        let success_value = self.build_value_or_void(&node.success);
+        let success_block = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(merge_block).unwrap();
+
+        let mut incoming = vec![(success_block, success_value)];
+
+        if let (Some(fail), Some(else_block)) = (&node.fail, else_block) {
+            self.builder.position_at_end(else_block);
+            let fail_value = self.build_value_or_void(fail);
+            let fail_block = self.builder.get_insert_block().unwrap();
+            self.builder.build_unconditional_branch(merge_block).unwrap();
+
+            incoming.push((fail_block, fail_value));
+        }
+
+        self.builder.position_at_end(merge_block);
+
+        // Only an if with an else branch can have a value
+        let type_id = self.ast_type(node);
+
+        self.value = if type_id == self.compiler.type_arena.void() {
+            std::ptr::null_mut()
+        } else {
+            let phi = self.builder.build_phi(self.get_type(type_id), "iftmp").unwrap();
+
+            for (block, value) in incoming {
+                phi.add_incoming(&[(&value.unwrap(), block)]);
+            }
+
+            phi.as_value_ref()
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<LocalResolved>) -> CodeGenResult {
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<LocalResolved>) -> CodeGenResult {
+        let captures = self.symbol_table().captures.get(&node.get_id())
+            .map(|captures| captures.clone())
+            .unwrap_or_default();
+
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let name = format!("lambda.{}", node.get_id().to_string());
+
+        // By-value captures are copied into the environment, by-reference captures store the variable's address
+        let capture_types: Vec<TypeId> = captures.iter()
+            .map(|decl_id| *self.symbol_table().declaration_types.get(decl_id).unwrap())
+            .collect();
+
+        let env_field_types: Vec<BasicTypeEnum> = capture_types.iter()
+            .map(|&type_id| match node.capture_mode {
+                CaptureMode::Value => self.get_type(type_id),
+                CaptureMode::Reference => ptr_type.into(),
+            })
+            .collect();
+
+        let env_type = self.closure_env_type(&env_field_types);
+
+        // Function values captured by value keep a reference of their own until the environment goes
+        let captured_closures: Vec<u32> = capture_types.iter().enumerate()
+            .filter(|&(_, &type_id)| node.capture_mode == CaptureMode::Value && self.cleanup(type_id) == Some(Cleanup::Release))
+            .map(|(i, _)| i as u32 + CLOSURE_ENV_HEADER)
+            .collect();
+
+        // Without captures there is nothing to put in an environment, and nothing to allocate
+        let env_ptr = if captures.is_empty() {
+            ptr_type.const_null()
+        } else {
+            let env_ptr = self.build_alloc(env_type.into(), None, "closureenv");
+
+            let drop_fn = if captured_closures.is_empty() {
+                ptr_type.const_null()
+            } else {
+                self.build_closure_drop_function(&format!("{}.drop", name), env_type, &captured_closures).as_global_value().as_pointer_value()
+            };
+
+            let references_ptr = self.builder.build_struct_gep(env_type, env_ptr, 0, "references").unwrap();
+            self.builder.build_store(references_ptr, self.context.i64_type().const_int(1, false)).unwrap();
+            let drop_fn_ptr = self.builder.build_struct_gep(env_type, env_ptr, 1, "dropfnptr").unwrap();
+            self.builder.build_store(drop_fn_ptr, drop_fn).unwrap();
+
+            for (i, decl_id) in captures.iter().enumerate() {
+                let var_ptr = self.local_vars[decl_id];
+                let field = i as u32 + CLOSURE_ENV_HEADER;
+                let field_ptr = self.builder.build_struct_gep(env_type, env_ptr, field, "envfield").unwrap();
+
+                match node.capture_mode {
+                    CaptureMode::Value => {
+                        let captured = self.builder.build_load(env_field_types[i], var_ptr, "captureload").unwrap();
+
+                        if captured_closures.contains(&field) {
+                            self.builder.build_call(self.get_closure_retain_function(), &[self.closure_env(captured).into()], "").unwrap();
+                        }
+
+                        self.builder.build_store(field_ptr, captured).unwrap();
+                    },
+                    CaptureMode::Reference => {
+                        self.builder.build_store(field_ptr, var_ptr).unwrap();
+                    },
+                }
+            }
+
+            env_ptr
+        };
+
+        let fn_type = self.function_type(self.ast_type(node));
+
+        // Only reachable through the function values made here, so it stays out of the object's symbols
+        let function = self.module.add_function(&name, self.make_closure_function(&fn_type), Some(Linkage::Private));
+
+        // Inside the body, captured variables live in the environment
+        self.build_body(function, 1, &node.parameters, &node.body, |codegen| {
+            let env_param = function.get_nth_param(0).unwrap().into_pointer_value();
+
+            for (i, decl_id) in captures.iter().enumerate() {
+                let field_ptr = codegen.builder.build_struct_gep(env_type, env_param, i as u32 + CLOSURE_ENV_HEADER, "capture").unwrap();
+
+                let var_ptr = match node.capture_mode {
+                    CaptureMode::Value => field_ptr,
+                    CaptureMode::Reference => {
+                        codegen.builder.build_load(ptr_type, field_ptr, "captureptr").unwrap().into_pointer_value()
+                    },
+                };
+
+                codegen.local_vars.insert(*decl_id, var_ptr);
+            }
+        });
+
+        self.value = self.make_closure(function, env_ptr).as_value_ref();
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<LocalResolved>) -> CodeGenResult {
+        self.value = match &node.value {
+            // Unsuffixed literals take the integer type their context gave them
+            Literal::Int(i) => self.get_int_type(self.ast_type(node)).const_int(*i as u64, false).as_value_ref(),
+            Literal::Double(d) => self.context.f64_type().const_float(*d).as_value_ref(),
+            Literal::SizedInt(i, _) => self.get_int_type(self.ast_type(node)).const_int(*i, false).as_value_ref(),
+            Literal::SizedFloat(f, _) => self.get_float_type(self.ast_type(node)).const_float(*f).as_value_ref(),
+            Literal::Bool(b) => self.context.bool_type().const_int(*b as u64, false).as_value_ref(),
+            Literal::Char(c) => self.context.i8_type().const_int(*c as u64, false).as_value_ref(),
+            Literal::String(s) => self.const_str(s).as_value_ref(),
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<LocalResolved>) -> CodeGenResult {
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        let function = self.current_function();
+        let condition_block = self.context.append_basic_block(function, "loopcondition");
+        let loop_block = self.context.append_basic_block(function, "loop");
+        // continue runs the increment of a for loop, otherwise it goes straight to the condition
+        let increment_block = node.increment.as_ref().map(|_| self.context.append_basic_block(function, "loopincrement"));
+        let after_loop_block = self.context.append_basic_block(function, "afterloop");
+
+        self.builder.build_unconditional_branch(condition_block).unwrap();
+        self.builder.position_at_end(condition_block);
+
+        let mut valueless = Vec::new();
+
+        if let Some(condition) = &node.condition {
+            let depth = self.temporaries.len();
+            let condition = self.build_value(condition).into_int_value();
+            self.end_full_expression(depth);
+            self.builder.build_conditional_branch(condition, loop_block, after_loop_block).unwrap();
+            valueless.push(self.builder.get_insert_block().unwrap());
+        } else {
+            self.builder.build_unconditional_branch(loop_block).unwrap();
+        }
+
+        self.builder.position_at_end(loop_block);
+
+        self.loops.push(ExitFrame {
+            label: node.label.as_deref(),
+            exit_block: after_loop_block,
+            continue_block: Some(increment_block.unwrap_or(condition_block)),
+            values: Vec::new(),
+            drop_depth: self.drop_scopes.len(),
+            temporary_depth: self.temporaries.len(),
+        });
+
+        node.body.accept_visitor(self);
+
+        let frame = self.loops.pop().unwrap();
+
+        if let (Some(increment), Some(increment_block)) = (&node.increment, increment_block) {
+            self.builder.build_unconditional_branch(increment_block).unwrap();
+            self.builder.position_at_end(increment_block);
+            self.build_statement(increment);
+        }
+
+        self.builder.build_unconditional_branch(condition_block).unwrap();
+
+        self.builder.position_at_end(after_loop_block);
+        self.value = self.build_exit_value(self.ast_type(node), &frame.values, &valueless);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<LocalResolved>) -> CodeGenResult {
+        let sizes: Vec<_> = node.sizes.iter()
+            .map(|size| self.build_index(size))
+            .collect();
+
+        // `new T[a][b]` has type T**, peel one pointer per dimension to get to T
+        let mut element_type_id = self.ast_type(node);
+
+        for _ in 0..node.dimension {
+            element_type_id = match self.resolved_type(element_type_id) {
+                ResolvedType::Pointer(pointee) => pointee,
+                _ => panic!("Expected pointer type for new array expression"),
+            };
+        }
+
+        let llvm_element_type = self.get_type(element_type_id);
+
+        self.value = match sizes.as_slice() {
+            [size] => self.build_alloc(llvm_element_type, Some(*size), "newarray"),
+            _ => self.build_nested_array(llvm_element_type, &sizes),
+        }.as_value_ref();
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<LocalResolved>) -> CodeGenResult {
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<LocalResolved>) -> CodeGenResult {
+        for constructor in &node.constructors {
+            let (function, _) = self.constructors[&constructor.get_id()];
+            self.build_constructor_body(function, constructor);
+        }
+
+        if let Some(destructor) = &node.destructor {
+            let type_id = *self.symbol_table().types.get(&*node.name).unwrap();
+            self.build_constructor_body(self.destructors[&type_id], destructor);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<LocalResolved>) -> CodeGenResult {
+        let lvalue = std::mem::take(&mut self.lvalue_mode);
+        let expr_type_id = self.ast_type(&node.expr);
+
+        let (struct_type_id, struct_ptr) = match (node.is_direct, self.resolved_type(expr_type_id)) {
+            (true, ResolvedType::FixedArray(_, length)) => {
+                self.value = self.context.i32_type().const_int(length as u64, false).as_value_ref();
+                return self.witness(node);
+            },
+            (true, ResolvedType::Str | ResolvedType::Array(_)) => {
+                let slice = self.build_value(&node.expr).into_struct_value();
+                self.value = self.builder.build_extract_value(slice, 1, "slicelen").unwrap().as_value_ref();
+                return self.witness(node);
+            },
+            // A struct value is reached into in place when its address is wanted, and taken apart otherwise
+            (true, ResolvedType::Struct(_)) if !lvalue => {
+                let member_index = self.struct_field_maps[&expr_type_id][node.member_name.as_str()];
+                let struct_value = self.build_value(&node.expr).into_struct_value();
+
+                self.value = self.builder.build_extract_value(struct_value, member_index, "memberload").unwrap().as_value_ref();
+                return self.witness(node);
+            },
+            (true, ResolvedType::Struct(_)) => (expr_type_id, self.build_place(&node.expr)),
+            (false, ResolvedType::Pointer(struct_type_id)) => {
+                let struct_ptr = self.build_value(&node.expr).into_pointer_value();
+
+                let llvm_struct_type = self.get_type(struct_type_id);
+                self.build_memory_check(struct_ptr, self.context.i64_type().const_zero(), llvm_struct_type, node.get_position());
+
+                (struct_type_id, struct_ptr)
+            },
+            _ => panic!("Expected struct type for member access"),
+        };
+
+        let member_index = self.struct_field_maps[&struct_type_id][node.member_name.as_str()];
+        let member_ptr = self.builder.build_struct_gep(self.struct_types[&struct_type_id], struct_ptr, member_index, "memberidx").unwrap();
+
+        self.value = if lvalue {
+            member_ptr.as_value_ref()
+        } else {
+            self.builder.build_load(self.get_type(self.ast_type(node)), member_ptr, "memberload").unwrap().as_value_ref()
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<LocalResolved>) -> CodeGenResult {
+        let struct_type_id = self.ast_type(node);
+        let field_map = self.struct_field_maps[&struct_type_id].clone();
+        let mut struct_value = self.struct_types[&struct_type_id].get_undef();
+
+        for (field_name, value) in &node.fields {
+            let field_value = self.build_owned(value);
+            struct_value = self.builder.build_insert_value(struct_value, field_value, field_map[field_name.as_str()], "structfield").unwrap().into_struct_value();
+        }
+
+        // Members left out take their default value, generated in the scope declaring the struct
+        let (struct_item, owner) = self.struct_items[&struct_type_id];
+        let symbol_table = self.symbol_table.replace(owner);
+
+        for (_, _, member_name, default_value) in &struct_item.members {
+            let is_given = node.fields.iter().any(|(field_name, _)| **field_name == **member_name);
+
+            if let Some(default_value) = default_value && !is_given {
+                let field_value = self.build_owned(default_value);
+                struct_value = self.builder.build_insert_value(struct_value, field_value, field_map[member_name.as_str()], "structdefault").unwrap().into_struct_value();
+            }
+        }
+
+        self.symbol_table = symbol_table;
+        self.value = struct_value.as_value_ref();
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<LocalResolved>) -> CodeGenResult {
+        let lvalue = std::mem::take(&mut self.lvalue_mode);
+
+        self.value = match node.operator {
+            UnaryOperator::Negative => {
+                let value = self.build_value(&node.expr);
+
+                if self.is_float(self.ast_type(&node.expr)) {
+                    self.builder.build_float_neg(value.into_float_value(), "fnegtmp").unwrap().as_value_ref()
+                } else {
+                    self.builder.build_int_neg(value.into_int_value(), "negtmp").unwrap().as_value_ref()
+                }
+            },
+            UnaryOperator::Not => {
+                let value = self.build_value(&node.expr).into_int_value();
+                self.builder.build_not(value, "nottmp").unwrap().as_value_ref()
+            },
+            UnaryOperator::BitwiseNot => {
+                let value = self.build_value(&node.expr).into_int_value();
+                self.builder.build_not(value, "bitwisenottmp").unwrap().as_value_ref()
+            },
+            UnaryOperator::Deref => {
+                let pointee_type = self.get_type(self.ast_type(node));
+                let ptr = self.build_value(&node.expr).into_pointer_value();
+
+                self.build_memory_check(ptr, self.context.i64_type().const_zero(), pointee_type, node.get_position());
+
+                if lvalue {
+                    ptr.as_value_ref()
+                } else {
+                    self.builder.build_load(pointee_type, ptr, "derefload").unwrap().as_value_ref()
+                }
+            },
+            UnaryOperator::AddressOf => self.build_place(&node.expr).as_value_ref(),
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<LocalResolved>) -> CodeGenResult {
+        let lvalue = std::mem::take(&mut self.lvalue_mode);
+        let declaration = self.symbol_table().variables.get(&node.get_id()).map(|decl_id| *decl_id);
+
+        if let Some(decl_id) = declaration && let Some(&slot) = self.local_vars.get(&decl_id) {
+            let declaration_type_id = *self.symbol_table().declaration_types.get(&decl_id).unwrap();
+
+            // The slot of a reference holds the address it is bound to, every use goes through it
+            let (ptr, value_type_id) = match self.resolved_type(declaration_type_id) {
+                ResolvedType::Reference(referenced_type_id) => {
+                    let ptr_type = self.context.ptr_type(AddressSpace::default());
+                    (self.builder.build_load(ptr_type, slot, "refload").unwrap().into_pointer_value(), referenced_type_id)
+                },
+                _ => (slot, declaration_type_id),
+            };
+
+            self.value = if lvalue {
+                ptr.as_value_ref()
+            } else {
+                self.builder.build_load(self.get_type(value_type_id), ptr, "varload").unwrap().as_value_ref()
+            };
+        } else if let Some(decl_id) = declaration && let Some(&(global, type_id)) = self.globals.get(&decl_id) {
+            let ptr = global.as_pointer_value();
+
+            // Constants are folded into their uses
+            self.value = match global.get_initializer() {
+                _ if lvalue => ptr.as_value_ref(),
+                Some(initializer) if global.is_constant() => initializer.as_value_ref(),
+                _ => self.builder.build_load(self.get_type(type_id), ptr, "globalload").unwrap().as_value_ref(),
+            };
+        } else {
+            // Anything else is a function, used by name
+            let (_, decl_id) = self.compiler.resolve_item(self.symbol_table(), &node.path).unwrap();
+            self.value = self.functions[&decl_id].as_value_ref();
+        }
+
+        self.witness(node)
+    }
+}
+
+impl<'ctx, 'ast> CodeGen<'ctx, 'ast> {
+    // An if branch has a value only when the if as a whole does, which the if takes ownership of
+    fn build_value_or_void(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> Option<BasicValueEnum<'ctx>> {
+        if self.ast_type(expr) == self.compiler.type_arena.void() {
+            self.build_statement(expr);
+            return None;
+        }
+
+        let depth = self.temporaries.len();
+        let value = self.build_owned(expr);
+        self.end_full_expression(depth);
+
+        Some(value)
+    }
+
+    // `self` is the pointer the caller passed as the first parameter, it is never copied into a slot
+    fn build_constructor_body(&mut self, function: FunctionValue<'ctx>, constructor: &'ast ConstructorItem<LocalResolved>) {
+        self.build_body(function, 1, &constructor.parameters, &constructor.body, |codegen| {
+            let self_ptr = function.get_nth_param(0).unwrap().into_pointer_value();
+            codegen.local_vars.insert(constructor.get_id(), self_ptr);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{CompilerOptions, const_eval::ConstEvaluator, structs::StructChecker, type_checker::TypeChecker};
+    use crate::compiler::test_util::{messages, parse_scopes, resolve_locals};
+    use crate::logger::CONSOLE_LOGGER;
+
+    // Checks the scopes the way the runner does and generates them into one module, which `inspect` gets to look at
+    fn generate(code: &str, options: CompilerOptions, inspect: impl FnOnce(&CodeGen)) {
+        let scopes = parse_scopes(code);
+        let compiler = Compiler::new(scopes.iter(), options);
+        let mut diagnostics = Vec::new();
+
+        for scope in &scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            ConstEvaluator::new(&compiler, symbol_table, &mut diagnostics).visit_scope(scope);
+        }
+
+        diagnostics.extend(compiler.register_types(scopes.iter()));
+
+        let mut resolved_scopes = Vec::new();
+
+        for scope in &scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            let local_resolved = resolve_locals(&compiler, scope);
+
+            TypeChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+            StructChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+            resolved_scopes.push((Path::new("test.mar"), local_resolved));
+        }
+
+        assert!(compiler.diagnostics.is_empty() && diagnostics.is_empty(), "Expected code to check without diagnostics: {:?}", messages(&diagnostics));
+
+        let context = Context::create();
+        let mut codegen = CodeGen::new(&CONSOLE_LOGGER, &context, &compiler);
+        codegen.compile(&resolved_scopes);
+
+        codegen.module.verify().unwrap();
+        inspect(&codegen);
+    }
+
+    fn generate_ir(code: &str) -> String {
+        let mut ir = String::new();
+        generate(code, CompilerOptions::default(), |codegen| ir = codegen.module.print_to_string().to_string());
+
+        ir
+    }
+
+    // Emits the module as asked for by the options and returns the written file
+    fn emit(code: &str, options: CompilerOptions) -> Vec<u8> {
+        let temp_dir = TempDir::create().unwrap();
+        let output_file = temp_dir.path().join(options.emit.default_output());
+
+        generate(code, options, |codegen| codegen.emit(&output_file).unwrap());
+
+        fs::read(output_file).unwrap()
+    }
+
+    // Links the program against the host C library and runs it
+    fn run(code: &str, options: CompilerOptions) -> process::Output {
+        let temp_dir = TempDir::create().unwrap();
+        let executable = temp_dir.path().join(Emit::Executable.default_output());
+
+        generate(code, CompilerOptions { emit: Emit::Executable, ..options }, |codegen| codegen.emit(&executable).unwrap());
+
+        Command::new(&executable).output().unwrap()
+    }
+
+    fn run_stdout(code: &str) -> String {
+        String::from_utf8(run(code, CompilerOptions::default()).stdout).unwrap()
+    }
+
+    fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    // Names of the function symbols in the symbol table of a little-endian ELF32 object
+    fn elf32_functions(object: &[u8]) -> Vec<String> {
+        let section_headers = read_u32(object, 32) as usize;
+        let header_size = read_u16(object, 46) as usize;
+        let header_count = read_u16(object, 48) as usize;
+        let section = |index: usize| section_headers + index * header_size;
+
+        let Some(symtab) = (0..header_count).map(section).find(|&header| read_u32(object, header + 4) == 2) else {
+            return Vec::new();
+        };
+
+        let strtab = read_u32(object, section(read_u32(object, symtab + 24) as usize) + 16) as usize;
+        let symbols = read_u32(object, symtab + 16) as usize;
+        let symbol_count = read_u32(object, symtab + 20) as usize / 16;
+
+        (0..symbol_count)
+            .map(|index| symbols + index * 16)
+            .filter(|&symbol| object[symbol + 12] & 0xf == 2)
+            .map(|symbol| {
+                let name = &object[strtab + read_u32(object, symbol) as usize..];
+                String::from_utf8_lossy(&name[..name.iter().position(|&byte| byte == 0).unwrap()]).into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scopes_share_one_module() {
+        let ir = generate_ir("
+            scope Main {
+                from Main::Math require twice;
+                fn main() { let int four = twice(2); }
+                scope Math { pub fn twice(int x) -> int { return x * 2; } }
+            }
+        ");
+
+        assert!(ir.contains("define void @main()"));
+        assert!(ir.contains("define i32 @twice(i32 %0)"));
+        assert!(ir.contains("call i32 @twice(i32 2)"));
+    }
+
+    #[test]
+    fn test_emit_optimized_llvm_ir() {
+        let code = "scope Main { fn add_three(int a) -> int { let int b = a + 1; return b + 2; } }";
+
+        let unoptimized = String::from_utf8(emit(code, CompilerOptions { emit: Emit::LlvmIr, ..CompilerOptions::default() })).unwrap();
+        let optimized = String::from_utf8(emit(code, CompilerOptions { emit: Emit::LlvmIr, opt_level: OptLevel::O2, ..CompilerOptions::default() })).unwrap();
+
+        assert!(unoptimized.contains("alloca i32"));
+        assert!(optimized.contains("define i32 @add_three(i32 %0)"));
+        assert!(optimized.contains("add i32 %0, 3"));
+        assert!(!optimized.contains("alloca"));
+    }
+
+    #[test]
+    fn test_emit_thumbv7em_object() {
+        let object = emit(include_str!("tests/codegen/thumbv7em.mar"), CompilerOptions {
+            emit: Emit::Object,
+            target_triple: Some("thumbv7em-none-eabihf".to_string()),
+            ..CompilerOptions::default()
+        });
+
+        assert_eq!(&object[..4], b"\x7fELF");
+        // EM_ARM, and EABI version 5 with the hard-float ABI
+        assert_eq!(read_u16(&object, 18), 0x28);
+        assert_eq!(read_u32(&object, 36), 0x5000400);
+
+        let functions = elf32_functions(&object);
+        assert!(functions.iter().any(|name| name == "scale"));
+        assert!(functions.iter().any(|name| name == "average"));
+    }
+
+    #[test]
+    fn test_freestanding_attributes() {
+        let ir = String::from_utf8(emit(include_str!("tests/codegen/freestanding.mar"), CompilerOptions {
+            emit: Emit::LlvmIr,
+            freestanding: true,
+            allocator: Allocator::Bump,
+            target_triple: Some("thumbv7em-none-eabihf".to_string()),
+            ..CompilerOptions::default()
+        })).unwrap();
+
+        // The entry function is what the startup code calls
+        assert!(ir.contains("define void @main()"));
+        assert!(!ir.contains("@start"));
+        assert!(ir.contains("define void @tick() section \".text.fast\""));
+        assert!(ir.contains("@TICKS = global i32 0, section \".data.counters\""));
+        assert!(ir.contains("@marlin.alloc"));
+        assert!(!ir.contains("@malloc"));
+    }
+
+    #[test]
+    fn test_freestanding_bounds_check_only_traps() {
+        let code = "scope Main { fn get(int[] values, int i) -> int { return values[i]; } }";
+
+        let hosted = generate_ir(code);
+        let mut freestanding = String::new();
+        generate(code, CompilerOptions { freestanding: true, ..CompilerOptions::default() }, |codegen| {
+            freestanding = codegen.module.print_to_string().to_string();
+        });
+
+        assert!(hosted.contains("@dprintf"));
+        assert!(freestanding.contains("@llvm.trap"));
+        assert!(!freestanding.contains("@dprintf"));
+    }
+
+    #[test]
+    fn test_closures() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                fn square(int x) -> int { return x * x; }
+                fn apply(fn(int) -> int f, int x) -> int { return f(x); }
+                fn make_adder(int offset) -> fn(int) -> int { return fn(int x) -> int { return x + offset; }; }
+
+                fn main() {
+                    putchar((apply(square, 3) as char) + '0');
+
+                    let fn(int) -> int add_two = make_adder(2);
+                    putchar((add_two(5) as char) + '0');
+
+                    let int seen = 1;
+                    let fn() -> int get = fn() -> int { return seen; };
+                    seen = 5;
+                    putchar((get() as char) + '0');
+
+                    let int count = 0;
+                    let fn() increment = fn&() { count = count + 1; };
+                    increment();
+                    increment();
+                    putchar((count as char) + '0');
+                }
+            }
+        ");
+
+        assert_eq!(output, "9712");
+    }
+
+    #[test]
+    fn test_str() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                fn print(str s) {
+                    for (let int i = 0; i < s.len; i = i + 1) {
+                        putchar(s[i]);
+                    }
+                }
+
+                fn main() {
+                    let str greeting = \"hi\";
+                    print(greeting);
+                    putchar((greeting.len as char) + '0');
+
+                    if greeting == \"hi\" { putchar('='); }
+                    if greeting != \"ho\" { putchar('!'); }
+
+                    let char *raw = greeting as char*;
+                    print(raw as str);
+                }
+            }
+        ");
+
+        assert_eq!(output, "hi2=!hi");
+    }
+
+    #[test]
+    fn test_str_index_out_of_bounds() {
+        let output = run("scope Main { fn main() { let str s = \"hi\"; let int i = 2; let char c = s[i]; } }", CompilerOptions::default());
+
+        assert!(!output.status.success());
+        assert_eq!(String::from_utf8(output.stderr).unwrap(), "test.mar:1:74: index out of bounds: the len is 2 but the index is 2\n");
+    }
+
+    #[test]
+    fn test_sized_integer_index() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                fn main() {
+                    let char[3] letters = ['a', 'b', 'c'];
+                    let i64 last_letter = 2;
+                    putchar(letters[last_letter]);
+
+                    // Unsigned indices are zero extended, 255 must not turn into -1
+                    let u8 last_byte = 255;
+                    let u16 size = 256;
+                    let char *buffer = new char[size];
+                    buffer[last_byte] = 'z';
+                    putchar(buffer[255]);
+                    delete buffer;
+                }
+            }
+        ");
+
+        assert_eq!(output, "cz");
+    }
+
+    #[test]
+    fn test_references() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/reference.mar")), "4");
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/double.mar")), "yhG3");
+    }
+
+    #[test]
+    fn test_float_comparisons_with_nan() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                fn main() {
+                    let double zero = 0.0;
+                    let double nan = zero / zero;
+
+                    if nan == nan { putchar('e'); }
+                    if nan != nan { putchar('n'); }
+                    if nan < 1.0 or nan >= 1.0 { putchar('o'); }
+                    if 1.5f32 < 2.5f32 { putchar('f'); }
+                }
+            }
+        ");
+
+        assert_eq!(output, "nf");
+    }
+
+    #[test]
+    fn test_globals_and_constants() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/globals.mar")), "3cC");
+    }
+
+    #[test]
+    fn test_constants_across_scopes() {
+        let code = "
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                fn main() {
+                    Main::Regs::writes = Main::Regs::writes + 1;
+                    putchar((Main::Regs::GPIOA_ODR >> 16u32) as char + 'a');
+                    putchar(Main::Regs::writes as char + '0');
+                }
+
+                scope Regs {
+                    pub const GPIOA_ODR: u32 = 0x40020000 + 0x14;
+                    pub static writes: int = 0;
+                }
+            }
+        ";
+
+        let ir = generate_ir(code);
+        assert!(ir.contains("@GPIOA_ODR = constant i32 1073872916"));
+        assert!(ir.contains("@writes = global i32 0"));
+
+        assert_eq!(run_stdout(code), "c1");
+    }
+
+    #[test]
+    fn test_multi_dimensional_new() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/matrix.mar")), "100\n010\n001\nx");
+    }
+
+    #[test]
+    fn test_multi_dimensional_delete_frees_every_level() {
+        let output = run("
+            scope Main {
+                fn main() {
+                    let int **grid = new int[2][3];
+                    let int *row = grid[1];
+                    delete grid;
+                    row[2] = 1;
+                }
+            }
+        ", CompilerOptions { sanitize_memory: true, ..CompilerOptions::default() });
+
+        assert!(!output.status.success());
+        assert_eq!(String::from_utf8(output.stderr).unwrap(), "test.mar:7:25: use after free\n");
+    }
+
+    // What a program built with --sanitize=memory reported before it trapped
+    fn sanitizer_report(code: &str) -> String {
+        let output = run(code, CompilerOptions { sanitize_memory: true, ..CompilerOptions::default() });
+        assert!(!output.status.success());
+
+        String::from_utf8(output.stderr).unwrap()
+    }
+
+    #[test]
+    fn test_sanitizer_out_of_bounds() {
+        assert_eq!(sanitizer_report(include_str!("tests/codegen/sanitize.mar")), "test.mar:18:17: out of bounds access\n");
+    }
+
+    #[test]
+    fn test_sanitizer_double_delete() {
+        let report = sanitizer_report("scope Main { fn main() { let int *p = new int[1]; delete p; delete p; } }");
+
+        assert_eq!(report, "test.mar:1:68: double delete\n");
+    }
+
+    #[test]
+    fn test_sanitizer_null_dereference() {
+        let report = sanitizer_report("scope Main { fn main() { let int *p = 0 as int*; let int v = *p; } }");
+
+        assert_eq!(report, "test.mar:1:63: null pointer dereference\n");
+    }
+
+    #[test]
+    fn test_sanitizer_null_struct_access() {
+        let report = sanitizer_report("scope Main { struct Node { int value; } fn main() { let Node *n = 0 as Node*; n->value = 1; } }");
+
+        assert_eq!(report, "test.mar:1:80: null pointer dereference\n");
+    }
+
+    #[test]
+    fn test_sanitizer_allows_valid_accesses() {
+        let output = run(include_str!("tests/codegen/heap.mar"), CompilerOptions { sanitize_memory: true, ..CompilerOptions::default() });
+
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "97");
+        assert!(output.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_destructors() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/destructor.mar")), "ballrh");
+    }
+
+    #[test]
+    fn test_destructors_on_continue_and_result() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                struct Guard {
+                    char tag;
+
+                    $(char tag) { self.tag = tag; }
+                    ~ { putchar(self.tag); }
+                }
+
+                fn pick() -> int {
+                    let int value = {
+                        let Guard outer = $Guard('o');
+                        {
+                            let Guard inner = $Guard('i');
+                        }
+
+                        result 4;
+                    };
+
+                    return value;
+                }
+
+                fn main() {
+                    for (let int i = 0; i < 2; i = i + 1) {
+                        let Guard step = $Guard('c');
+                        continue;
+                    }
+
+                    putchar((pick() as char) + '0');
+                }
+            }
+        ");
+
+        assert_eq!(output, "ccio4");
+    }
+
+    #[test]
+    fn test_destructors_of_moved_and_returned_structs() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                struct Guard {
+                    char tag;
+
+                    $(char tag) { self.tag = tag; }
+                    ~ { putchar(self.tag); }
+                }
+
+                fn make(char tag) -> Guard {
+                    let Guard guard = $Guard(tag);
+                    return guard;
+                }
+
+                fn main() {
+                    let Guard made = make('m');
+                    putchar('|');
+                    let Guard moved = made;
+                    putchar('|');
+                    let Guard later;
+                    later = make('l');
+                    later = make('r');
+                    putchar('|');
+                }
+            }
+        ");
+
+        // Each value is destroyed once, by its last owner
+        assert_eq!(output, "||l|rm");
+    }
+
+    #[test]
+    fn test_destructors_of_temporaries() {
+        let output = run_stdout("
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                struct Guard {
+                    char tag;
+
+                    $(char tag) { self.tag = tag; }
+                    ~ { putchar(self.tag); }
+                }
+
+                fn tag(Guard guard) -> char { return guard.tag; }
+
+                fn main() {
+                    $Guard('s');
+                    putchar('|');
+                    putchar(tag($Guard('a')));
+                    putchar('|');
+                    putchar($Guard('t').tag);
+                    putchar('|');
+
+                    if (tag($Guard('c')) == 'c') {
+                        putchar('y');
+                    }
+                }
+            }
+        ");
+
+        // Temporaries are destroyed at the end of the full expression, a condition's before its branch runs
+        assert_eq!(output, "s|aa|tt|cy");
+    }
+
+    #[test]
+    fn test_closure_environments_are_released() {
+        let code = "
+            scope Main {
+                extern fn putchar(char c) -> int;
+
+                fn make_adder(int offset) -> fn(int) -> int { return fn(int x) -> int { return x + offset; }; }
+                fn twice(fn(int) -> int f) -> fn(int) -> int { return fn(int x) -> int { return f(f(x)); }; }
+
+                fn main() {
+                    let fn(int) -> int kept = make_adder(0);
+
+                    for (let int i = 0; i < 100; i = i + 1) {
+                        let fn(int) -> int add = make_adder(i);
+                        let fn(int) -> int copy = add;
+                        kept = copy;
+                    }
+
+                    putchar((twice(make_adder(2))(1) as char) + '0');
+                    putchar((kept(-90) as char) + '0');
+                }
+            }
+        ";
+
+        // A release too many would be reported as a double delete
+        let output = run(code, CompilerOptions { sanitize_memory: true, ..CompilerOptions::default() });
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "59");
+        assert!(output.stderr.is_empty());
+
+        // Lambdas, thunks and drop functions are only reachable through function values
+        let ir = generate_ir(code);
+        assert!(ir.contains("define private i32 @lambda."));
+        assert!(ir.contains("define private void @lambda.") && ir.contains(".drop(ptr"));
+        assert!(ir.contains("call void @marlin.closure.release("));
+        assert!(!ir.contains("define i32 @lambda."));
+    }
+
+    #[test]
+    fn test_constructors() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/constructor.mar")), "aabc");
+    }
+
+    #[test]
+    fn test_heap_allocation() {
+        assert_eq!(run_stdout(include_str!("tests/codegen/heap.mar")), "97");
+    }
+
+    #[test]
+    fn test_bump_allocator() {
+        let code = include_str!("tests/codegen/heap.mar");
+        let options = || CompilerOptions { allocator: Allocator::Bump, heap_size: 1024, ..CompilerOptions::default() };
+
+        assert_eq!(String::from_utf8(run(code, options()).stdout).unwrap(), "97");
+
+        // Memory comes out of the static arena, the C library is never asked for any
+        let mut ir = String::new();
+        generate(code, options(), |codegen| ir = codegen.module.print_to_string().to_string());
+        assert!(ir.contains("@marlin.heap = private global [1024 x i8]"));
+        assert!(!ir.contains("@malloc") && !ir.contains("@free"));
+    }
+}
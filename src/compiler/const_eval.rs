@@ -0,0 +1,979 @@
+use std::fmt;
+
+use crate::{ast::*, compiler::{Compiler, NumericCast, SymbolTable, visit::{ASTVisitor, AcceptsASTVisitor, Parsed, Phase, PhaseWitness, VisitResult}}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::{NumericType, PositionRange, Positioned}};
+
+impl PhaseWitness<Parsed> for ConstEvalWitness {}
+
+pub type ConstEvalResult = VisitResult<Parsed, ConstEvalWitness>;
+
+pub struct ConstEvalWitness {
+    _private: (),
+}
+
+// A value folded at compile time
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstValue {
+    // Integers of every width are held in an i128 and always lie in range of their type
+    Int(i128, NumericType),
+    Float(f64, NumericType),
+    Bool(bool),
+    Char(char),
+    Str(String),
+}
+
+impl ConstValue {
+    pub fn type_name(&self) -> String {
+        match self {
+            ConstValue::Int(_, numeric_type) | ConstValue::Float(_, numeric_type) => numeric_type_name(*numeric_type),
+            ConstValue::Bool(_) => "bool".to_string(),
+            ConstValue::Char(_) => "char".to_string(),
+            ConstValue::Str(_) => "str".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstValue::Int(value, _) => write!(f, "{}", value),
+            ConstValue::Float(value, _) => write!(f, "{}", value),
+            ConstValue::Bool(value) => write!(f, "{}", value),
+            ConstValue::Char(value) => write!(f, "'{}'", value),
+            ConstValue::Str(value) => write!(f, "\"{}\"", value),
+        }
+    }
+}
+
+// int and double are the i32 and f64 types under their original names
+pub fn numeric_type_name(numeric_type: NumericType) -> String {
+    match numeric_type {
+        NumericType::I32 => "int".to_string(),
+        NumericType::F64 => "double".to_string(),
+        _ => numeric_type.to_string(),
+    }
+}
+
+// Name of a declared type that a folded value can have
+fn declared_type_name(parsed_type: &ParsedType) -> Option<String> {
+    Some(match parsed_type.parsed_type {
+        ParsedTypeEnum::Integer => numeric_type_name(NumericType::I32),
+        ParsedTypeEnum::Double => numeric_type_name(NumericType::F64),
+        ParsedTypeEnum::Numeric(numeric_type) => numeric_type_name(numeric_type),
+        ParsedTypeEnum::Boolean => "bool".to_string(),
+        ParsedTypeEnum::Char => "char".to_string(),
+        ParsedTypeEnum::Str => "str".to_string(),
+        _ => return None,
+    })
+}
+
+// Unsuffixed integer literals have no type of their own, they take the integer type
+// their context expects and are ints only when nothing expects one. This holds for
+// the operators built from them too, so `1 << 4` is whatever `1` turns out to be.
+pub fn takes_context_type<P: Phase>(expr: &ASTEnum<P>) -> bool {
+    match expr {
+        ASTEnum::Literal(node) => matches!(node.value, Literal::Int(_)),
+        ASTEnum::Unary(node) => matches!(node.operator, UnaryOperator::Negative | UnaryOperator::BitwiseNot) && takes_context_type(&node.expr),
+        ASTEnum::Binary(node) => match node.operator {
+            BinaryOperator::LeftShift | BinaryOperator::RightShift => takes_context_type(&node.left),
+            operator if is_comparison(operator) => false,
+            _ => takes_context_type(&node.left) && takes_context_type(&node.right),
+        },
+        _ => false,
+    }
+}
+
+pub fn is_comparison(operator: BinaryOperator) -> bool {
+    matches!(operator,
+        BinaryOperator::Greater | BinaryOperator::GreaterEqual | BinaryOperator::Less | BinaryOperator::LessEqual |
+        BinaryOperator::Equal | BinaryOperator::NotEqual | BinaryOperator::And | BinaryOperator::Or
+    )
+}
+
+// The integer type a folded value gives the unsuffixed literals next to it
+fn context_type(value: &ConstValue) -> Option<NumericType> {
+    match value {
+        ConstValue::Int(_, numeric_type) => Some(*numeric_type),
+        _ => None,
+    }
+}
+
+fn cast_target(parsed_type: &ParsedType) -> Option<NumericType> {
+    match parsed_type.parsed_type {
+        ParsedTypeEnum::Integer => Some(NumericType::I32),
+        ParsedTypeEnum::Double => Some(NumericType::F64),
+        ParsedTypeEnum::Numeric(numeric_type) => Some(numeric_type),
+        _ => None,
+    }
+}
+
+pub fn int_range(numeric_type: NumericType) -> (i128, i128) {
+    let bits = numeric_type.bit_width();
+
+    if numeric_type.is_signed() {
+        (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+    } else {
+        (0, (1i128 << bits) - 1)
+    }
+}
+
+// Truncates to the width of the type the way a two's complement machine would
+fn wrap(value: i128, numeric_type: NumericType) -> i128 {
+    let bits = numeric_type.bit_width();
+    let value = value & ((1i128 << bits) - 1);
+
+    if numeric_type.is_signed() && value >= 1i128 << (bits - 1) {
+        value - (1i128 << bits)
+    } else {
+        value
+    }
+}
+
+fn round_float(value: f64, numeric_type: NumericType) -> f64 {
+    match numeric_type {
+        NumericType::F32 => value as f32 as f64,
+        _ => value,
+    }
+}
+
+// Folds constant initializers and the lengths of `T[NAME]` array types.
+// Only literals, constants and the pure operators can be folded. The language
+// has no enums yet, so there are no discriminants to fold.
+pub struct ConstEvaluator<'ctx, 'ast> {
+    compiler: &'ctx Compiler<'ast>,
+    symbol_table: &'ctx SymbolTable<'ast>,
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+    // The global whose initializer is being visited
+    visiting: Option<AstId>,
+    // Globals whose initializers are being folded, outermost first
+    evaluating: Vec<AstId>,
+    // How many constant references deep the evaluator is. Errors inside another
+    // constant's initializer are left to that constant to report.
+    nested: usize,
+}
+
+impl<'ctx, 'ast> ConstEvaluator<'ctx, 'ast> {
+    pub fn new(compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>, diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            compiler,
+            symbol_table,
+            diagnostics,
+            visiting: None,
+            evaluating: Vec::new(),
+            nested: 0,
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<Parsed>) -> ConstEvalResult {
+        VisitResult::new(node.get_id(), ConstEvalWitness { _private: () })
+    }
+
+    fn report(&mut self, err_msg: ErrMsg, position: PositionRange) {
+        if self.nested == 0 {
+            self.diagnostics.push(err_msg.make_diagnostic(position));
+        }
+    }
+
+    fn visit_type(&mut self, parsed_type: &ParsedType) {
+        for length in parsed_type.array_lengths() {
+            self.evaluate_array_length(length);
+        }
+    }
+
+    fn evaluate_array_length(&mut self, length: &Path) {
+        if self.symbol_table.array_lengths.contains_key(length) {
+            return;
+        }
+
+        let position = PositionRange::concat(length.locations.first().unwrap(), length.locations.last().unwrap());
+
+        match self.evaluate_path(length, position) {
+            Some(ConstValue::Int(value, _)) if usize::try_from(value).is_ok() => {
+                self.symbol_table.array_lengths.insert(length.clone(), value as usize);
+            },
+            Some(value) => {
+                self.report(ErrMsg::InvalidArrayLength(value.to_string()), position);
+            },
+            None => {},
+        }
+    }
+
+    // Folds a global's initializer and checks it against the declared type
+    fn evaluate_global(&mut self, global: &'ast GlobalItem<Parsed>) -> Option<ConstValue> {
+        let expected = cast_target(&global.declaration_type).filter(|numeric_type| !numeric_type.is_float());
+
+        self.evaluating.push(global.get_id());
+        let value = self.evaluate(&global.expr, expected);
+        self.evaluating.pop();
+
+        let value = value?;
+
+        // Only primitive types can be folded
+        let Some(declared_type) = declared_type_name(&global.declaration_type) else {
+            self.report(ErrMsg::NotConstant, *global.expr.get_position());
+            return None;
+        };
+
+        if declared_type != value.type_name() {
+            self.report(ErrMsg::IncompatibleAssignment(declared_type, value.type_name()), *global.expr.get_position());
+            return None;
+        }
+
+        Some(value)
+    }
+
+    fn evaluate_path(&mut self, path: &Path, position: PositionRange) -> Option<ConstValue> {
+        let Some((symbol_table, decl_id)) = self.compiler.resolve_global(self.symbol_table, path) else {
+            self.report(ErrMsg::UnknownVariable(path.to_string()), position);
+            return None;
+        };
+
+        // Statics can change at runtime, so only constants fold
+        let Some(global) = symbol_table.constants.get(&decl_id).map(|global| *global) else {
+            self.report(ErrMsg::NotConstant, position);
+            return None;
+        };
+
+        if let Some(value) = symbol_table.const_values.get(&decl_id) {
+            return Some(value.clone());
+        }
+
+        if self.evaluating.contains(&decl_id) {
+            // Every constant on the cycle reports it when its own initializer is visited
+            if self.visiting == Some(decl_id) {
+                self.diagnostics.push(ErrMsg::RecursiveConstant(global.name.to_string()).make_diagnostic(*global.name.get_position()));
+            }
+
+            return None;
+        }
+
+        self.nested += 1;
+        let value = self.evaluate_global(global);
+        self.nested -= 1;
+
+        if let Some(value) = &value {
+            symbol_table.const_values.insert(decl_id, value.clone());
+        }
+
+        value
+    }
+
+    // `expected` is the integer type unsuffixed literals in `expr` take
+    fn evaluate(&mut self, expr: &'ast ASTEnum<Parsed>, expected: Option<NumericType>) -> Option<ConstValue> {
+        match expr {
+            ASTEnum::Literal(node) => self.evaluate_literal(node, expected),
+            ASTEnum::Binary(node) => self.evaluate_binary(node, expected),
+            ASTEnum::Unary(node) => self.evaluate_unary(node, expected),
+            ASTEnum::Cast(node) => self.evaluate_cast(node),
+            ASTEnum::Var(node) => self.evaluate_path(&node.path, *node.get_position()),
+            _ => {
+                self.report(ErrMsg::NotConstant, *expr.get_position());
+                None
+            },
+        }
+    }
+
+    // Checks that an integer result fits its type
+    fn fit_int(&mut self, value: Option<i128>, numeric_type: NumericType, position: PositionRange) -> Option<ConstValue> {
+        let (min, max) = int_range(numeric_type);
+
+        match value {
+            Some(value) if (min..=max).contains(&value) => Some(ConstValue::Int(value, numeric_type)),
+            _ => {
+                self.report(ErrMsg::ConstOverflow(numeric_type_name(numeric_type)), position);
+                None
+            },
+        }
+    }
+
+    fn fit_float(&mut self, value: f64, numeric_type: NumericType, position: PositionRange) -> Option<ConstValue> {
+        let value = round_float(value, numeric_type);
+
+        if value.is_infinite() {
+            self.report(ErrMsg::ConstOverflow(numeric_type_name(numeric_type)), position);
+            return None;
+        }
+
+        Some(ConstValue::Float(value, numeric_type))
+    }
+
+    fn evaluate_literal(&mut self, node: &LiteralExpr<Parsed>, expected: Option<NumericType>) -> Option<ConstValue> {
+        let position = *node.get_position();
+
+        match &node.value {
+            Literal::Int(value) => self.fit_int(Some(*value as u64 as i128), expected.unwrap_or(NumericType::I32), position),
+            Literal::Double(value) => Some(ConstValue::Float(*value, NumericType::F64)),
+            Literal::SizedInt(value, numeric_type) if numeric_type.is_float() => self.fit_float(*value as f64, *numeric_type, position),
+            Literal::SizedInt(value, numeric_type) => self.fit_int(Some(*value as i128), *numeric_type, position),
+            Literal::SizedFloat(value, numeric_type) => self.fit_float(*value, *numeric_type, position),
+            Literal::Bool(value) => Some(ConstValue::Bool(*value)),
+            Literal::Char(value) => Some(ConstValue::Char(*value)),
+            Literal::String(value) => Some(ConstValue::Str(value.clone())),
+        }
+    }
+
+    fn evaluate_binary(&mut self, node: &'ast BinaryExpr<Parsed>, expected: Option<NumericType>) -> Option<ConstValue> {
+        let position = *node.get_position();
+        let operator = node.operator;
+
+        let expected = if is_comparison(operator) { None } else { expected };
+
+        // The shift amount is typed on its own, any integer type can shift any other
+        let (left, right) = if matches!(operator, BinaryOperator::LeftShift | BinaryOperator::RightShift) {
+            (self.evaluate(&node.left, expected)?, self.evaluate(&node.right, None)?)
+        } else if takes_context_type(&node.left) && !takes_context_type(&node.right) {
+            let right = self.evaluate(&node.right, expected)?;
+            (self.evaluate(&node.left, context_type(&right))?, right)
+        } else {
+            let left = self.evaluate(&node.left, expected)?;
+            let right = self.evaluate(&node.right, context_type(&left).or(expected))?;
+            (left, right)
+        };
+
+        match (left, right) {
+            (ConstValue::Int(left, numeric_type), ConstValue::Int(right, _)) if matches!(operator, BinaryOperator::LeftShift | BinaryOperator::RightShift) => {
+                // Shifting by the width of the type or more is undefined
+                if right < 0 || right >= numeric_type.bit_width() as i128 {
+                    self.report(ErrMsg::ConstOverflow(numeric_type_name(numeric_type)), position);
+                    return None;
+                }
+
+                // Bits shifted out of the type are an overflow like any other
+                match operator {
+                    BinaryOperator::LeftShift => self.fit_int(Some(left << right), numeric_type, position),
+                    _ => Some(ConstValue::Int(left >> right, numeric_type)),
+                }
+            },
+            (ConstValue::Int(left, numeric_type), ConstValue::Int(right, right_type)) if numeric_type == right_type => {
+                let value = match operator {
+                    BinaryOperator::Plus => left.checked_add(right),
+                    BinaryOperator::Minus => left.checked_sub(right),
+                    BinaryOperator::Times => left.checked_mul(right),
+                    BinaryOperator::Divide | BinaryOperator::Modulo if right == 0 => {
+                        self.report(ErrMsg::DivisionByZero, position);
+                        return None;
+                    },
+                    BinaryOperator::Divide => left.checked_div(right),
+                    BinaryOperator::Modulo => left.checked_rem(right),
+                    BinaryOperator::BitwiseAnd => Some(left & right),
+                    BinaryOperator::BitwiseOr => Some(left | right),
+                    BinaryOperator::BitwiseXor => Some(left ^ right),
+                    BinaryOperator::Greater => return Some(ConstValue::Bool(left > right)),
+                    BinaryOperator::GreaterEqual => return Some(ConstValue::Bool(left >= right)),
+                    BinaryOperator::Less => return Some(ConstValue::Bool(left < right)),
+                    BinaryOperator::LessEqual => return Some(ConstValue::Bool(left <= right)),
+                    BinaryOperator::Equal => return Some(ConstValue::Bool(left == right)),
+                    BinaryOperator::NotEqual => return Some(ConstValue::Bool(left != right)),
+                    _ => {
+                        let type_name = numeric_type_name(numeric_type);
+                        self.report(ErrMsg::IncompatibleBinaryTypes(type_name.clone(), type_name, operator), position);
+                        return None;
+                    },
+                };
+
+                self.fit_int(value, numeric_type, position)
+            },
+            (ConstValue::Float(left, numeric_type), ConstValue::Float(right, right_type)) if numeric_type == right_type => {
+                let value = match operator {
+                    BinaryOperator::Plus => left + right,
+                    BinaryOperator::Minus => left - right,
+                    BinaryOperator::Times => left * right,
+                    BinaryOperator::Divide | BinaryOperator::Modulo if right == 0.0 => {
+                        self.report(ErrMsg::DivisionByZero, position);
+                        return None;
+                    },
+                    BinaryOperator::Divide => left / right,
+                    BinaryOperator::Modulo => left % right,
+                    BinaryOperator::Greater => return Some(ConstValue::Bool(left > right)),
+                    BinaryOperator::GreaterEqual => return Some(ConstValue::Bool(left >= right)),
+                    BinaryOperator::Less => return Some(ConstValue::Bool(left < right)),
+                    BinaryOperator::LessEqual => return Some(ConstValue::Bool(left <= right)),
+                    BinaryOperator::Equal => return Some(ConstValue::Bool(left == right)),
+                    BinaryOperator::NotEqual => return Some(ConstValue::Bool(left != right)),
+                    _ => {
+                        let type_name = numeric_type_name(numeric_type);
+                        self.report(ErrMsg::IncompatibleBinaryTypes(type_name.clone(), type_name, operator), position);
+                        return None;
+                    },
+                };
+
+                self.fit_float(value, numeric_type, position)
+            },
+            (ConstValue::Bool(left), ConstValue::Bool(right)) if matches!(operator, BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Equal | BinaryOperator::NotEqual) => {
+                Some(ConstValue::Bool(match operator {
+                    BinaryOperator::And => left && right,
+                    BinaryOperator::Or => left || right,
+                    BinaryOperator::Equal => left == right,
+                    _ => left != right,
+                }))
+            },
+            (ConstValue::Char(left), ConstValue::Char(right)) if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual) => {
+                Some(ConstValue::Bool((left == right) == matches!(operator, BinaryOperator::Equal)))
+            },
+            (left, right) => {
+                self.report(ErrMsg::IncompatibleBinaryTypes(left.type_name(), right.type_name(), operator), position);
+                None
+            },
+        }
+    }
+
+    fn evaluate_unary(&mut self, node: &'ast UnaryExpr<Parsed>, expected: Option<NumericType>) -> Option<ConstValue> {
+        let position = *node.get_position();
+
+        // Pointers only exist at runtime
+        if matches!(node.operator, UnaryOperator::Deref | UnaryOperator::AddressOf) {
+            self.report(ErrMsg::NotConstant, position);
+            return None;
+        }
+
+        // The magnitude of the most negative value does not fit the type on its own, as in `-2147483648`
+        if let (UnaryOperator::Negative, ASTEnum::Literal(literal)) = (node.operator, &node.expr) {
+            // Literals are never negative, so the magnitude of i64::MIN is read back as unsigned
+            let magnitude = match literal.value {
+                Literal::Int(value) => Some((value as u64, expected.unwrap_or(NumericType::I32))),
+                Literal::SizedInt(value, numeric_type) => Some((value, numeric_type)),
+                _ => None,
+            };
+
+            if let Some((value, numeric_type)) = magnitude
+                && numeric_type.is_signed() && !numeric_type.is_float()
+            {
+                return self.fit_int(Some(-(value as i128)), numeric_type, position);
+            }
+        }
+
+        let value = self.evaluate(&node.expr, expected)?;
+
+        match (node.operator, value) {
+            (UnaryOperator::Negative, ConstValue::Int(value, numeric_type)) if numeric_type.is_signed() => {
+                self.fit_int(value.checked_neg(), numeric_type, position)
+            },
+            (UnaryOperator::Negative, ConstValue::Float(value, numeric_type)) => Some(ConstValue::Float(-value, numeric_type)),
+            (UnaryOperator::Not, ConstValue::Bool(value)) => Some(ConstValue::Bool(!value)),
+            (UnaryOperator::BitwiseNot, ConstValue::Int(value, numeric_type)) => Some(ConstValue::Int(wrap(!value, numeric_type), numeric_type)),
+            (operator, value) => {
+                self.report(ErrMsg::IncompatibleUnaryType(value.type_name(), operator), position);
+                None
+            },
+        }
+    }
+
+    fn evaluate_cast(&mut self, node: &'ast CastExpr<Parsed>) -> Option<ConstValue> {
+        let position = *node.get_position();
+
+        let Some(target) = cast_target(&node.cast_type) else {
+            self.report(ErrMsg::NotConstant, position);
+            return None;
+        };
+
+        let value = self.evaluate(&node.expr, None)?;
+
+        let cast = match value {
+            ConstValue::Int(_, source) | ConstValue::Float(_, source) => Some(NumericCast::between(source, target)),
+            _ => None,
+        };
+
+        match (cast, value) {
+            (_, ConstValue::Char(value)) if !target.is_float() => Some(ConstValue::Int(wrap(value as i128, target), target)),
+            (_, ConstValue::Bool(value)) if !target.is_float() => Some(ConstValue::Int(value as i128, target)),
+            // Integer casts truncate or extend like they do at runtime
+            (Some(NumericCast::Identity | NumericCast::Truncate | NumericCast::SignExtend | NumericCast::ZeroExtend), ConstValue::Int(value, _)) => {
+                Some(ConstValue::Int(wrap(value, target), target))
+            },
+            (Some(NumericCast::SignedToFloat | NumericCast::UnsignedToFloat), ConstValue::Int(value, _)) => self.fit_float(value as f64, target, position),
+            (Some(NumericCast::Identity | NumericCast::FloatTruncate | NumericCast::FloatExtend), ConstValue::Float(value, _)) => {
+                self.fit_float(value, target, position)
+            },
+            (Some(NumericCast::FloatToSigned | NumericCast::FloatToUnsigned), ConstValue::Float(value, _)) => {
+                // A float that does not fit the integer type has no defined result
+                let (min, max) = int_range(target);
+                let truncated = value.trunc();
+
+                if truncated.is_nan() || truncated < min as f64 || truncated > max as f64 {
+                    self.report(ErrMsg::ConstOverflow(numeric_type_name(target)), position);
+                    return None;
+                }
+
+                Some(ConstValue::Int(truncated as i128, target))
+            },
+            _ => {
+                self.report(ErrMsg::NotConstant, position);
+                None
+            },
+        }
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, ConstEvalWitness> for ConstEvaluator<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<Parsed>) -> ConstEvalResult {
+        node.expr.accept_visitor(self);
+        node.index.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<Parsed>) -> ConstEvalResult {
+        for element in &node.elements {
+            element.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<Parsed>) -> ConstEvalResult {
+        node.assignee.accept_visitor(self);
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<Parsed>) -> ConstEvalResult {
+        node.left.accept_visitor(self);
+        node.right.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<Parsed>) -> ConstEvalResult {
+        for expr in &node.exprs {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<Parsed>) -> ConstEvalResult {
+        node.expr.accept_visitor(self);
+        self.visit_type(&node.cast_type);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<Parsed>) -> ConstEvalResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<Parsed>) -> ConstEvalResult {
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<Parsed>) -> ConstEvalResult {
+        self.visit_type(&node.declaration_type);
+
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<Parsed>) -> ConstEvalResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<Parsed>) -> ConstEvalResult {
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<Parsed>) -> ConstEvalResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        self.visit_type(&node.return_type);
+
+        if let Some(body) = &node.body {
+            body.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<Parsed>) -> ConstEvalResult {
+        node.expr.accept_visitor(self);
+
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<Parsed>) -> ConstEvalResult {
+        self.visit_type(&node.declaration_type);
+
+        // Statics are folded too, since their initial value is baked into the binary
+        self.visiting = Some(node.get_id());
+
+        if let Some(value) = self.evaluate_global(node) {
+            self.symbol_table.const_values.insert(node.get_id(), value);
+        }
+
+        self.visiting = None;
+
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<Parsed>) -> ConstEvalResult {
+        node.condition.accept_visitor(self);
+        node.success.accept_visitor(self);
+
+        if let Some(fail) = &node.fail {
+            fail.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<Parsed>) -> ConstEvalResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<Parsed>) -> ConstEvalResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        self.visit_type(&node.return_type);
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<Parsed>) -> ConstEvalResult {
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<Parsed>) -> ConstEvalResult {
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        if let Some(condition) = &node.condition {
+            condition.accept_visitor(self);
+        }
+
+        if let Some(increment) = &node.increment {
+            increment.accept_visitor(self);
+        }
+
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<Parsed>) -> ConstEvalResult {
+        for size in &node.sizes {
+            size.accept_visitor(self);
+        }
+
+        self.visit_type(&node.array_type);
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<Parsed>) -> ConstEvalResult {
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<Parsed>) -> ConstEvalResult {
+        for (_, member_type, _, default_value) in &node.members {
+            self.visit_type(member_type);
+
+            if let Some(default_value) = default_value {
+                default_value.accept_visitor(self);
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<Parsed>) -> ConstEvalResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<Parsed>) -> ConstEvalResult {
+        for (_, value) in &node.fields {
+            value.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<Parsed>) -> ConstEvalResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<Parsed>) -> ConstEvalResult {
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilerOptions;
+    use crate::compiler::test_util::{messages, parse_scopes};
+
+    // Evaluates every scope, returning the diagnostics and the folded value of each global by name
+    fn evaluate(code: &str) -> (Vec<Diagnostic>, Vec<(String, ConstValue)>) {
+        let scopes = parse_scopes(code);
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let mut diagnostics = Vec::new();
+        let mut values = Vec::new();
+
+        for scope in &scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            ConstEvaluator::new(&compiler, symbol_table, &mut diagnostics).visit_scope(scope);
+
+            for item in &scope.items {
+                if let ASTEnum::Global(global) = item
+                    && let Some(value) = symbol_table.const_values.get(&global.get_id())
+                {
+                    values.push((global.name.to_string(), value.clone()));
+                }
+            }
+        }
+
+        (diagnostics, values)
+    }
+
+    fn value<'a>(values: &'a [(String, ConstValue)], name: &str) -> Option<&'a ConstValue> {
+        values.iter().find(|(global, _)| global == name).map(|(_, value)| value)
+    }
+
+    #[test]
+    fn test_fold_arithmetic() {
+        let (diagnostics, values) = evaluate("scope test { const A: int = 2 + 3 * 4; const B: int = A << 2; const C: int = -A % 5; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "A"), Some(&ConstValue::Int(14, NumericType::I32)));
+        assert_eq!(value(&values, "B"), Some(&ConstValue::Int(56, NumericType::I32)));
+        assert_eq!(value(&values, "C"), Some(&ConstValue::Int(-4, NumericType::I32)));
+    }
+
+    #[test]
+    fn test_fold_bitwise_on_sized_ints() {
+        let (diagnostics, values) = evaluate("scope test { const MASK: u8 = ~15u8; const PIN: u32 = (1u32 << 13u32) | 1u32; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "MASK"), Some(&ConstValue::Int(0xf0, NumericType::U8)));
+        assert_eq!(value(&values, "PIN"), Some(&ConstValue::Int(0x2001, NumericType::U32)));
+    }
+
+    #[test]
+    fn test_fold_comparisons() {
+        let (diagnostics, values) = evaluate("scope test { const A: bool = 3 > 2 and !(1.5 == 2.5); const B: bool = 'a' != 'a' or false; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "A"), Some(&ConstValue::Bool(true)));
+        assert_eq!(value(&values, "B"), Some(&ConstValue::Bool(false)));
+    }
+
+    #[test]
+    fn test_fold_casts() {
+        let (diagnostics, values) = evaluate("scope test { const A: u8 = 300 as u8; const B: int = 3.9 as int; const C: double = 7 as double / 2.0; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "A"), Some(&ConstValue::Int(44, NumericType::U8)));
+        assert_eq!(value(&values, "B"), Some(&ConstValue::Int(3, NumericType::I32)));
+        assert_eq!(value(&values, "C"), Some(&ConstValue::Float(3.5, NumericType::F64)));
+    }
+
+    #[test]
+    fn test_fold_widening_casts_by_source_signedness() {
+        let (diagnostics, values) = evaluate("scope test { const A: u32 = -1i8 as u32; const B: int = 255u8 as int; const C: f32 = -2i16 as f32; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "A"), Some(&ConstValue::Int(0xFFFFFFFF, NumericType::U32)));
+        assert_eq!(value(&values, "B"), Some(&ConstValue::Int(255, NumericType::I32)));
+        assert_eq!(value(&values, "C"), Some(&ConstValue::Float(-2.0, NumericType::F32)));
+    }
+
+    #[test]
+    fn test_fold_minimum_sized_literals() {
+        let (diagnostics, values) = evaluate("scope test { const A: i8 = -128i8; const B: i64 = -9223372036854775808i64; const C: i64 = -9223372036854775808; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "A"), Some(&ConstValue::Int(-128, NumericType::I8)));
+        assert_eq!(value(&values, "B"), Some(&ConstValue::Int(i64::MIN as i128, NumericType::I64)));
+        assert_eq!(value(&values, "C"), Some(&ConstValue::Int(i64::MIN as i128, NumericType::I64)));
+    }
+
+    #[test]
+    fn test_constants_across_scopes() {
+        let (diagnostics, values) = evaluate("scope Main { scope Config { const SIZE: int = 4; } const BYTES: int = Main::Config::SIZE * 8; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "SIZE"), Some(&ConstValue::Int(4, NumericType::I32)));
+        assert_eq!(value(&values, "BYTES"), Some(&ConstValue::Int(32, NumericType::I32)));
+    }
+
+    #[test]
+    fn test_overflow_reports_expression_span() {
+        let scopes = parse_scopes("scope test { const A: u8 = 1u8 + (200u8 + 100u8); }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let symbol_table = compiler.symbol_tables.get(scopes[0].path.segments.as_slice()).unwrap();
+
+        let mut diagnostics = Vec::new();
+        ConstEvaluator::new(&compiler, symbol_table, &mut diagnostics).visit_scope(&scopes[0]);
+
+        assert_eq!(messages(&diagnostics), vec!["constant expression overflows type 'u8'"]);
+
+        // The span is that of the inner addition, not the whole initializer
+        let ASTEnum::Global(global) = &scopes[0].items[0] else { panic!("Expected a global") };
+        let ASTEnum::Binary(outer) = &global.expr else { panic!("Expected a binary expression") };
+
+        assert_eq!(diagnostics[0].position.to_string(), outer.right.get_position().to_string());
+    }
+
+    #[test]
+    fn test_signed_overflow() {
+        let (diagnostics, values) = evaluate("scope test { const A: i8 = 127i8 + 1i8; const B: int = 1 << 32; }");
+
+        assert_eq!(messages(&diagnostics), vec!["constant expression overflows type 'i8'", "constant expression overflows type 'int'"]);
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_range_float_cast() {
+        let (diagnostics, _) = evaluate("scope test { const A: u8 = 1000.0 as u8; }");
+
+        assert_eq!(messages(&diagnostics), vec!["constant expression overflows type 'u8'"]);
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let (diagnostics, values) = evaluate("scope test { const A: int = 1 / (2 - 2); const B: double = 1.0 % 0.0; }");
+
+        assert_eq!(messages(&diagnostics), vec!["division by zero in constant expression", "division by zero in constant expression"]);
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_statics_are_not_constant() {
+        let (diagnostics, values) = evaluate("scope test { static COUNT: int = 1; const A: int = COUNT + 1; }");
+
+        assert_eq!(messages(&diagnostics), vec!["expression cannot be evaluated at compile time"]);
+        assert_eq!(value(&values, "COUNT"), Some(&ConstValue::Int(1, NumericType::I32)));
+    }
+
+    #[test]
+    fn test_recursive_constants() {
+        let (diagnostics, _) = evaluate("scope test { const A: int = B + 1; const B: int = A; }");
+
+        assert_eq!(messages(&diagnostics), vec!["constant 'A' depends on its own value", "constant 'B' depends on its own value"]);
+    }
+
+    #[test]
+    fn test_errors_reported_once() {
+        let (diagnostics, _) = evaluate("scope test { const A: int = 1 / 0; const B: int = A + 1; fn f() { let u8[A] buffer; } }");
+
+        assert_eq!(messages(&diagnostics), vec!["division by zero in constant expression"]);
+    }
+
+    #[test]
+    fn test_declared_type_mismatch() {
+        let (diagnostics, values) = evaluate("scope test { const A: u8 = 1; const B: u8 = 1u16; const C: double = 1; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "cannot assign expression of type 'u16' to variable of type 'u8'",
+            "cannot assign expression of type 'int' to variable of type 'double'",
+        ]);
+        assert_eq!(value(&values, "A"), Some(&ConstValue::Int(1, NumericType::U8)));
+    }
+
+    #[test]
+    fn test_unsuffixed_literals_take_declared_type() {
+        let (diagnostics, values) = evaluate("scope test { const GPIOA_BASE: u32 = 0x40020000; const GPIOA_ODR: u32 = GPIOA_BASE + 0x14; const SCB_BASE: u32 = 0xE000ED00; const MASK: u8 = ~0x0F; const LOW: i8 = -128; const MIN: int = -2147483648; const IS_HIGH: bool = SCB_BASE > 0x80000000; }");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(value(&values, "GPIOA_ODR"), Some(&ConstValue::Int(0x40020014, NumericType::U32)));
+        assert_eq!(value(&values, "SCB_BASE"), Some(&ConstValue::Int(0xE000ED00, NumericType::U32)));
+        assert_eq!(value(&values, "MASK"), Some(&ConstValue::Int(0xF0, NumericType::U8)));
+        assert_eq!(value(&values, "LOW"), Some(&ConstValue::Int(-128, NumericType::I8)));
+        assert_eq!(value(&values, "MIN"), Some(&ConstValue::Int(i32::MIN as i128, NumericType::I32)));
+        assert_eq!(value(&values, "IS_HIGH"), Some(&ConstValue::Bool(true)));
+    }
+
+    #[test]
+    fn test_unsuffixed_literals_out_of_range() {
+        let (diagnostics, values) = evaluate("scope test { const A: u8 = 256; const B: u32 = 4294967296; const C: i8 = -129; const D: int = 0xE000ED00; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "constant expression overflows type 'u8'",
+            "constant expression overflows type 'u32'",
+            "constant expression overflows type 'i8'",
+            "constant expression overflows type 'int'",
+        ]);
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_left_shift_overflow() {
+        let (diagnostics, values) = evaluate("scope test { const A: int = 1 << 31; const B: u8 = 0x81u8 << 1u8; const C: u32 = 1 << 31; }");
+
+        assert_eq!(messages(&diagnostics), vec!["constant expression overflows type 'int'", "constant expression overflows type 'u8'"]);
+        assert_eq!(value(&values, "C"), Some(&ConstValue::Int(1 << 31, NumericType::U32)));
+    }
+
+    #[test]
+    fn test_array_lengths() {
+        let scopes = parse_scopes("scope test { const N: int = 4 * 2; struct Packet { u8[N] data; } fn f() { let int[N][] rows; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let symbol_table = compiler.symbol_tables.get(scopes[0].path.segments.as_slice()).unwrap();
+
+        let mut diagnostics = Vec::new();
+        ConstEvaluator::new(&compiler, symbol_table, &mut diagnostics).visit_scope(&scopes[0]);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(symbol_table.array_lengths.len(), 1);
+        assert_eq!(symbol_table.array_lengths.iter().next().map(|entry| *entry.value()), Some(8));
+    }
+
+    #[test]
+    fn test_invalid_array_lengths() {
+        let (diagnostics, _) = evaluate("scope test { const N: int = -1; const C: char = 'c'; fn f() { let u8[N] a; let u8[C] b; let u8[M] c; } }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "array length must be a non-negative integer, found -1",
+            "array length must be a non-negative integer, found 'c'",
+            "unknown variable: 'M'",
+        ]);
+    }
+}
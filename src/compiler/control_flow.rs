@@ -0,0 +1,482 @@
+use crate::{ast::*, compiler::visit::{ASTVisitor, AcceptsASTVisitor, Parsed, PhaseWitness, VisitResult}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::Positioned};
+
+impl PhaseWitness<Parsed> for ControlFlowWitness {}
+
+pub type ControlFlowResult = VisitResult<Parsed, ControlFlowWitness>;
+
+pub struct ControlFlowWitness {
+    _private: (),
+}
+
+// A loop enclosing the node being checked
+struct LoopFrame<'ast> {
+    label: Option<&'ast String>,
+    broken: bool,
+}
+
+// Checks that every break, continue and result has a valid target and that
+// non-void functions return on every path.
+pub struct ControlFlowChecker<'ctx, 'ast> {
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+    // Enclosing loops, innermost last. Reset at function and lambda boundaries.
+    loops: Vec<LoopFrame<'ast>>,
+    // Enclosing block expressions, innermost last, flagged once they hit a `result`
+    blocks: Vec<bool>,
+    // Whether control can never fall through past the node that was just visited
+    diverges: bool,
+}
+
+impl<'ctx, 'ast> ControlFlowChecker<'ctx, 'ast> {
+    pub fn new(diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            diagnostics,
+            loops: Vec::new(),
+            blocks: Vec::new(),
+            diverges: false,
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<Parsed>) -> ControlFlowResult {
+        VisitResult::new(node.get_id(), ControlFlowWitness { _private: () })
+    }
+
+    // Visits nodes in order, returning whether any of them diverges
+    fn visit_sequence(&mut self, nodes: impl IntoIterator<Item = &'ast ASTEnum<Parsed>>) -> bool {
+        let mut diverges = false;
+
+        for node in nodes {
+            node.accept_visitor(self);
+            diverges |= self.diverges;
+        }
+
+        diverges
+    }
+
+    // Function, lambda and constructor bodies are not block expressions, so
+    // `result` cannot target them and loops outside are out of reach.
+    fn visit_body(&mut self, body: &'ast BlockExpr<Parsed>) -> bool {
+        let loops = std::mem::take(&mut self.loops);
+        let blocks = std::mem::take(&mut self.blocks);
+
+        let diverges = self.visit_sequence(&body.exprs);
+
+        self.loops = loops;
+        self.blocks = blocks;
+
+        diverges
+    }
+
+    fn check_loop_exit(&mut self, node: &ExitExpr<Parsed>) {
+        let keyword = match node.exit_type {
+            ExitType::Continue => "continue",
+            _ => "break",
+        };
+
+        let inside_loop = !self.loops.is_empty();
+        let target = match &node.label {
+            Some(label) => self.loops.iter_mut().rev().find(|frame| frame.label == Some(label.as_ref())),
+            None => self.loops.last_mut(),
+        };
+
+        match target {
+            Some(frame) => frame.broken |= node.exit_type == ExitType::Break,
+            None if !inside_loop => {
+                self.diagnostics.push(ErrMsg::ExitOutsideLoop(keyword.to_string()).make_diagnostic(*node.get_position()));
+            },
+            None => {
+                let label = node.label.as_ref().unwrap();
+                self.diagnostics.push(ErrMsg::UnknownLoopLabel(label.to_string()).make_diagnostic(*label.get_position()));
+            },
+        }
+    }
+
+    fn check_result(&mut self, node: &ExitExpr<Parsed>) {
+        // Blocks cannot be labeled, so a labeled result never has a target
+        if let Some(label) = &node.label {
+            self.diagnostics.push(ErrMsg::UnknownBlockLabel(label.to_string()).make_diagnostic(*label.get_position()));
+        } else if let Some(yielded) = self.blocks.last_mut() {
+            *yielded = true;
+        } else {
+            self.diagnostics.push(ErrMsg::ResultOutsideBlock.make_diagnostic(*node.get_position()));
+        }
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, ControlFlowWitness> for ControlFlowChecker<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence([&node.expr, &node.index]);
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence(&node.elements);
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence([&node.assignee, &node.expr]);
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<Parsed>) -> ControlFlowResult {
+        node.left.accept_visitor(self);
+        let left_diverges = self.diverges;
+
+        node.right.accept_visitor(self);
+
+        // The right side of `and`/`or` may be skipped
+        self.diverges = match node.operator {
+            BinaryOperator::And | BinaryOperator::Or => left_diverges,
+            _ => left_diverges || self.diverges,
+        };
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<Parsed>) -> ControlFlowResult {
+        self.blocks.push(false);
+        let diverges = self.visit_sequence(&node.exprs);
+        let yielded = self.blocks.pop().unwrap();
+
+        // A `result` leaves the block normally with a value
+        self.diverges = diverges && !yielded;
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<Parsed>) -> ControlFlowResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<Parsed>) -> ControlFlowResult {
+        self.visit_body(&node.body);
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence(&node.arguments);
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence(&node.expr);
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<Parsed>) -> ControlFlowResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<Parsed>) -> ControlFlowResult {
+        self.visit_sequence(&node.expr);
+
+        match node.exit_type {
+            ExitType::Break | ExitType::Continue => self.check_loop_exit(node),
+            ExitType::Result => self.check_result(node),
+            ExitType::Return => {},
+        }
+
+        self.diverges = true;
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<Parsed>) -> ControlFlowResult {
+        if let Some(body) = &node.body {
+            let returns = self.visit_body(body);
+
+            if !returns && node.return_type.parsed_type != ParsedTypeEnum::Void {
+                self.diagnostics.push(ErrMsg::MissingReturn(node.name.to_string()).make_diagnostic(*node.name.get_position()));
+            }
+        }
+
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence(std::iter::once(&node.expr).chain(&node.arguments));
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<Parsed>) -> ControlFlowResult {
+        node.expr.accept_visitor(self);
+
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<Parsed>) -> ControlFlowResult {
+        node.condition.accept_visitor(self);
+        let condition_diverges = self.diverges;
+
+        node.success.accept_visitor(self);
+        let success_diverges = self.diverges;
+
+        let fail_diverges = match &node.fail {
+            Some(fail) => {
+                fail.accept_visitor(self);
+                self.diverges
+            },
+            None => false,
+        };
+
+        self.diverges = condition_diverges || (success_diverges && fail_diverges);
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<Parsed>) -> ControlFlowResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<Parsed>) -> ControlFlowResult {
+        let returns = self.visit_body(&node.body);
+
+        if !returns && node.return_type.parsed_type != ParsedTypeEnum::Void {
+            self.diagnostics.push(ErrMsg::MissingLambdaReturn.make_diagnostic(*node.get_position()));
+        }
+
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<Parsed>) -> ControlFlowResult {
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<Parsed>) -> ControlFlowResult {
+        let mut diverges = false;
+
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+            diverges |= self.diverges;
+        }
+
+        diverges |= self.visit_sequence(node.condition.iter().chain(&node.increment));
+
+        self.loops.push(LoopFrame {
+            label: node.label.as_ref().map(|label| label.as_ref()),
+            broken: false,
+        });
+
+        node.body.accept_visitor(self);
+
+        let frame = self.loops.pop().unwrap();
+
+        // Only an unconditional loop that is never broken out of runs forever
+        self.diverges = diverges || (node.condition.is_none() && !frame.broken);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence(&node.sizes);
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<Parsed>) -> ControlFlowResult {
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        self.visit_sequence(&node.items);
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<Parsed>) -> ControlFlowResult {
+        for (_, _, _, default_value) in &node.members {
+            if let Some(default_value) = default_value {
+                default_value.accept_visitor(self);
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.diverges = false;
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<Parsed>) -> ControlFlowResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<Parsed>) -> ControlFlowResult {
+        self.diverges = self.visit_sequence(node.fields.iter().map(|(_, value)| value));
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<Parsed>) -> ControlFlowResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<Parsed>) -> ControlFlowResult {
+        self.diverges = false;
+
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::test_util::{messages, parse_items};
+
+    fn check(items: &str) -> Vec<Diagnostic> {
+        let scope = parse_items(items);
+        let mut diagnostics = Vec::new();
+        ControlFlowChecker::new(&mut diagnostics).visit_scope(&scope);
+
+        diagnostics
+    }
+
+    #[test]
+    fn test_continue_inside_loops() {
+        let diagnostics = check("fn f() { while true { continue; } for (let int i = 0; i < 3; i = i + 1) { continue; } }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_labeled_exits_target_outer_loop() {
+        let diagnostics = check("fn f() { loop:outer { while true { continue:outer; break:outer; } } }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_continue_outside_loop() {
+        let diagnostics = check("fn f() { continue; }");
+
+        assert_eq!(messages(&diagnostics), vec!["'continue' outside of a loop"]);
+    }
+
+    #[test]
+    fn test_break_outside_loop() {
+        let diagnostics = check("fn f() { if true { break; } }");
+
+        assert_eq!(messages(&diagnostics), vec!["'break' outside of a loop"]);
+    }
+
+    #[test]
+    fn test_unknown_loop_label() {
+        let diagnostics = check("fn f() { loop:outer { break:inner; } }");
+
+        assert_eq!(messages(&diagnostics), vec!["unknown loop label: 'inner'"]);
+    }
+
+    #[test]
+    fn test_lambda_cannot_continue_enclosing_loop() {
+        let diagnostics = check("fn f() { loop { let fn() g = fn() { continue; }; break; } }");
+
+        assert_eq!(messages(&diagnostics), vec!["'continue' outside of a loop"]);
+    }
+
+    #[test]
+    fn test_result_inside_block_expression() {
+        let diagnostics = check("fn f() -> int { let int x = { result 1; }; return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_result_in_function_body() {
+        let diagnostics = check("fn f() -> int { result 1; }");
+
+        assert_eq!(messages(&diagnostics), vec!["'result' outside of a block expression"]);
+    }
+
+    #[test]
+    fn test_labeled_result() {
+        let diagnostics = check("fn f() { let int x = { result:value 1; }; }");
+
+        assert_eq!(messages(&diagnostics), vec!["unknown block label: 'value'"]);
+    }
+
+    #[test]
+    fn test_returns_on_every_branch() {
+        let diagnostics = check("fn f(int x) -> int { if x > 0 { return 1; } else if x < 0 { return -1; } else { return 0; } }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_missing_return_on_one_branch() {
+        let diagnostics = check("fn f(int x) -> int { if x > 0 { return 1; } }");
+
+        assert_eq!(messages(&diagnostics), vec!["function 'f' does not return a value on every path"]);
+    }
+
+    #[test]
+    fn test_missing_return_after_code_in_result_block() {
+        let diagnostics = check("fn f() -> int { let int x = { return 1; }; }");
+
+        assert!(diagnostics.is_empty());
+
+        let diagnostics = check("fn f() -> int { let int x = { if true { return 1; } result 2; }; }");
+
+        assert_eq!(messages(&diagnostics), vec!["function 'f' does not return a value on every path"]);
+    }
+
+    #[test]
+    fn test_infinite_loop_needs_no_return() {
+        let diagnostics = check("fn f() -> int { loop { } }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_broken_loop_needs_return() {
+        let diagnostics = check("fn f() -> int { loop:outer { loop { break:outer; } } }");
+
+        assert_eq!(messages(&diagnostics), vec!["function 'f' does not return a value on every path"]);
+    }
+
+    #[test]
+    fn test_void_and_extern_functions_need_no_return() {
+        let diagnostics = check("fn f() { } extern fn g() -> int;");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_lambda_missing_return() {
+        let diagnostics = check("fn f() { let fn() -> int g = fn() -> int { }; }");
+
+        assert_eq!(messages(&diagnostics), vec!["lambda does not return a value on every path"]);
+    }
+}
@@ -0,0 +1,729 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{ast::*, compiler::{Compiler, SymbolTable, visit::{ASTVisitor, AcceptsASTVisitor, LocalResolved, PhaseWitness, VisitResult}}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::{Located, Positioned}};
+
+impl PhaseWitness<LocalResolved> for DefiniteAssignmentWitness {}
+
+pub type DefiniteAssignmentResult = VisitResult<LocalResolved, DefiniteAssignmentWitness>;
+
+pub struct DefiniteAssignmentWitness {
+    _private: (),
+}
+
+// Declarations that may still be unassigned at some program point, along with the members
+// already assigned of the unassigned struct variables among them.
+// An unreachable state (after return, break, ...) is the identity for `merge`.
+#[derive(Clone)]
+struct State<'ast> {
+    unassigned: HashSet<AstId>,
+    assigned_members: HashMap<AstId, HashSet<&'ast str>>,
+    reachable: bool,
+}
+
+impl<'ast> State<'ast> {
+    fn new() -> Self {
+        Self {
+            unassigned: HashSet::new(),
+            assigned_members: HashMap::new(),
+            reachable: true,
+        }
+    }
+
+    fn unreachable() -> Self {
+        Self {
+            unassigned: HashSet::new(),
+            assigned_members: HashMap::new(),
+            reachable: false,
+        }
+    }
+
+    fn is_member_assigned(&self, decl_id: AstId, member: &str) -> bool {
+        self.assigned_members.get(&decl_id).is_some_and(|members| members.contains(member))
+    }
+
+    // The state where two paths join: a variable or member is only assigned if it is on both paths
+    fn merge(self, other: State<'ast>) -> State<'ast> {
+        match (self.reachable, other.reachable) {
+            (true, true) => {
+                let unassigned: HashSet<AstId> = self.unassigned.union(&other.unassigned).copied().collect();
+
+                // A variable assigned as a whole on one path keeps the members assigned on the other
+                let members = |state: &State<'ast>, decl_id: &AstId| state.assigned_members.get(decl_id).cloned().unwrap_or_default();
+                let assigned_members = unassigned.iter()
+                    .map(|decl_id| {
+                        let members = match (self.unassigned.contains(decl_id), other.unassigned.contains(decl_id)) {
+                            (true, true) => members(&self, decl_id).intersection(&members(&other, decl_id)).copied().collect(),
+                            (true, false) => members(&self, decl_id),
+                            _ => members(&other, decl_id),
+                        };
+
+                        (*decl_id, members)
+                    })
+                    .filter(|(_, members)| !members.is_empty())
+                    .collect();
+
+                State {
+                    unassigned,
+                    assigned_members,
+                    reachable: true,
+                }
+            },
+            (true, false) => self,
+            _ => other,
+        }
+    }
+}
+
+struct LoopFrame<'ast> {
+    label: Option<&'ast String>,
+    breaks: Vec<State<'ast>>,
+    continues: Vec<State<'ast>>,
+}
+
+// Reports variables declared without an initializer that are read before
+// being assigned on every path. Taking the address of a variable counts as
+// initializing it, since it may be written through the pointer. A struct
+// variable can also be assigned member by member, `p.x = 1; p.y = 2;`, and
+// counts as assigned once all of its members are. Its assigned members can
+// be read before that. Only members of the variable itself are tracked,
+// assigning `p.inner.x` leaves `p.inner` unassigned. A struct with a
+// destructor is only assigned as a whole, the destructor runs on all of it
+// once it is. References have no unbound state and must be initialized
+// where they are declared.
+pub struct DefiniteAssignmentChecker<'ctx, 'ast> {
+    compiler: &'ctx Compiler<'ast>,
+    symbol_table: &'ctx SymbolTable<'ast>,
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+    state: State<'ast>,
+    loops: Vec<LoopFrame<'ast>>,
+    // States at each `result` of the enclosing block expressions, innermost last
+    block_results: Vec<Vec<State<'ast>>>,
+    // Member names of the struct variables declared without an initializer
+    struct_members: HashMap<AstId, Vec<&'ast str>>,
+    // Each declaration is reported at most once
+    reported: HashSet<AstId>,
+}
+
+impl<'ctx, 'ast> DefiniteAssignmentChecker<'ctx, 'ast> {
+    pub fn new(compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>, diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            compiler,
+            symbol_table,
+            diagnostics,
+            state: State::new(),
+            loops: Vec::new(),
+            block_results: Vec::new(),
+            struct_members: HashMap::new(),
+            reported: HashSet::new(),
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<LocalResolved>) -> DefiniteAssignmentResult {
+        VisitResult::new(node.get_id(), DefiniteAssignmentWitness { _private: () })
+    }
+
+    fn decl_id(&self, node: &VarExpr<LocalResolved>) -> Option<AstId> {
+        self.symbol_table.variables.get(&node.get_id()).map(|decl_id| *decl_id)
+    }
+
+    // Member names of the struct a declaration has the type of
+    fn struct_members(&self, node: &'ast DeclarationExpr<LocalResolved>) -> Option<Vec<&'ast str>> {
+        let ParsedTypeEnum::TypeName(type_name) = &node.declaration_type.parsed_type else {
+            return None;
+        };
+
+        let path = Path::new(vec![Located::new(type_name.clone(), *node.declaration_type.get_position())]);
+        let (owner, decl_id) = self.compiler.resolve_item(self.symbol_table, &path)?;
+        let struct_item = *owner.structs.get(&decl_id)?;
+
+        if struct_item.destructor.is_some() {
+            return None;
+        }
+
+        Some(struct_item.members.iter().map(|(_, _, member_name, _)| member_name.as_str()).collect())
+    }
+
+    // Visits the target of an assignment or `&`. The variable at its root is
+    // written rather than read, returned along with the member written when
+    // the target is a member of the variable itself.
+    fn visit_place(&mut self, node: &'ast ASTEnum<LocalResolved>) -> Option<(AstId, Option<&'ast str>)> {
+        match node {
+            ASTEnum::Var(var) => self.decl_id(var).map(|decl_id| (decl_id, None)),
+            ASTEnum::StructAccess(access) if access.is_direct => {
+                match (self.visit_place(&access.expr), &access.expr) {
+                    (Some((decl_id, None)), ASTEnum::Var(_)) => Some((decl_id, Some(access.member_name.as_str()))),
+                    _ => None,
+                }
+            },
+            // `p->x` writes to what the pointer points to, the pointer itself is read
+            ASTEnum::StructAccess(access) => {
+                access.expr.accept_visitor(self);
+                None
+            },
+            ASTEnum::ArrayAccess(access) => {
+                self.visit_place(&access.expr);
+                access.index.accept_visitor(self);
+                None
+            },
+            _ => {
+                node.accept_visitor(self);
+                None
+            },
+        }
+    }
+
+    // Assigning a member only assigns the whole struct once every other member is assigned too
+    fn assign(&mut self, decl_id: AstId, member: Option<&'ast str>) {
+        if let Some(member) = member {
+            let Some(members) = self.struct_members.get(&decl_id) else {
+                return;
+            };
+
+            if !self.state.unassigned.contains(&decl_id) {
+                return;
+            }
+
+            let assigned_members = self.state.assigned_members.entry(decl_id).or_default();
+            assigned_members.insert(member);
+
+            if !members.iter().all(|member| assigned_members.contains(member)) {
+                return;
+            }
+        }
+
+        self.state.unassigned.remove(&decl_id);
+        self.state.assigned_members.remove(&decl_id);
+    }
+
+    // Function, lambda and constructor bodies start from a fresh state and
+    // cannot reach loops or blocks outside of them.
+    fn visit_body(&mut self, body: &'ast BlockExpr<LocalResolved>, state: State<'ast>) {
+        let outer_state = std::mem::replace(&mut self.state, state);
+        let loops = std::mem::take(&mut self.loops);
+        let block_results = std::mem::take(&mut self.block_results);
+
+        for expr in &body.exprs {
+            expr.accept_visitor(self);
+        }
+
+        self.state = outer_state;
+        self.loops = loops;
+        self.block_results = block_results;
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, LocalResolved, DefiniteAssignmentWitness> for DefiniteAssignmentChecker<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<LocalResolved>) -> DefiniteAssignmentResult {
+        node.expr.accept_visitor(self);
+        node.index.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<LocalResolved>) -> DefiniteAssignmentResult {
+        for element in &node.elements {
+            element.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        node.expr.accept_visitor(self);
+
+        if let Some((decl_id, member)) = self.visit_place(&node.assignee) {
+            self.assign(decl_id, member);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        node.left.accept_visitor(self);
+
+        match node.operator {
+            // The right side of `and`/`or` may be skipped
+            BinaryOperator::And | BinaryOperator::Or => {
+                let skipped = self.state.clone();
+                node.right.accept_visitor(self);
+                self.state = std::mem::replace(&mut self.state, State::unreachable()).merge(skipped);
+            },
+            _ => {
+                node.right.accept_visitor(self);
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        self.block_results.push(Vec::new());
+
+        for expr in &node.exprs {
+            expr.accept_visitor(self);
+        }
+
+        let results = self.block_results.pop().unwrap();
+        let fallthrough = std::mem::replace(&mut self.state, State::unreachable());
+
+        self.state = results.into_iter().fold(fallthrough, State::merge);
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<LocalResolved>) -> DefiniteAssignmentResult {
+        self.visit_body(&node.body, State::new());
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        match &node.expr {
+            Some(expr) => {
+                expr.accept_visitor(self);
+            },
+            // A reference can't be bound later, since assigning to it writes to what it refers to
+            None if matches!(node.declaration_type.parsed_type, ParsedTypeEnum::Reference(_)) => {
+                self.diagnostics.push(ErrMsg::UninitializedReference(node.identifier.to_string()).make_diagnostic(*node.get_position()));
+            },
+            None => {
+                if let Some(members) = self.struct_members(node) {
+                    self.struct_members.insert(node.get_id(), members);
+                }
+
+                self.state.unassigned.insert(node.get_id());
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+        }
+
+        // Targets were validated by the control-flow pass
+        let state = std::mem::replace(&mut self.state, State::unreachable());
+
+        match node.exit_type {
+            ExitType::Break | ExitType::Continue => {
+                let target = match &node.label {
+                    Some(label) => self.loops.iter_mut().rev().find(|frame| frame.label == Some(label.as_ref())),
+                    None => self.loops.last_mut(),
+                };
+
+                if let Some(frame) = target {
+                    match node.exit_type {
+                        ExitType::Break => frame.breaks.push(state),
+                        _ => frame.continues.push(state),
+                    }
+                }
+            },
+            ExitType::Result => {
+                if let Some(results) = self.block_results.last_mut() {
+                    results.push(state);
+                }
+            },
+            ExitType::Return => {},
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<LocalResolved>) -> DefiniteAssignmentResult {
+        if let Some(body) = &node.body {
+            self.visit_body(body, State::new());
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<LocalResolved>) -> DefiniteAssignmentResult {
+        node.expr.accept_visitor(self);
+
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<LocalResolved>) -> DefiniteAssignmentResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        node.condition.accept_visitor(self);
+        let fail_state = self.state.clone();
+
+        node.success.accept_visitor(self);
+        let success_state = std::mem::replace(&mut self.state, fail_state);
+
+        if let Some(fail) = &node.fail {
+            fail.accept_visitor(self);
+        }
+
+        self.state = std::mem::replace(&mut self.state, State::unreachable()).merge(success_state);
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<LocalResolved>) -> DefiniteAssignmentResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        // Captured variables are read when the lambda is created
+        self.visit_body(&node.body, self.state.clone());
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        if let Some(condition) = &node.condition {
+            condition.accept_visitor(self);
+        }
+
+        // Assignments only ever shrink the unassigned set, so the state on the
+        // first iteration is also the state at the top of every later one.
+        let exit_state = match node.condition {
+            Some(_) => self.state.clone(),
+            None => State::unreachable(),
+        };
+
+        self.loops.push(LoopFrame {
+            label: node.label.as_ref().map(|label| label.as_ref()),
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        });
+
+        node.body.accept_visitor(self);
+
+        let frame = self.loops.pop().unwrap();
+
+        if let Some(increment) = &node.increment {
+            let body_end = std::mem::replace(&mut self.state, State::unreachable());
+            self.state = frame.continues.into_iter().fold(body_end, State::merge);
+
+            increment.accept_visitor(self);
+        }
+
+        self.state = frame.breaks.into_iter().fold(exit_state, State::merge);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        for size in &node.sizes {
+            size.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<LocalResolved>) -> DefiniteAssignmentResult {
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<LocalResolved>) -> DefiniteAssignmentResult {
+        for (_, _, _, default_value) in &node.members {
+            if let Some(default_value) = default_value {
+                default_value.accept_visitor(self);
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<LocalResolved>) -> DefiniteAssignmentResult {
+        // A member assigned on its own can be read before the rest of the struct is
+        if let ASTEnum::Var(var) = &node.expr
+            && node.is_direct
+            && let Some(decl_id) = self.decl_id(var)
+            && self.state.is_member_assigned(decl_id, &node.member_name)
+        {
+            return self.witness(node);
+        }
+
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<LocalResolved>) -> DefiniteAssignmentResult {
+        for (_, value) in &node.fields {
+            value.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        match node.operator {
+            UnaryOperator::AddressOf => {
+                if let Some((decl_id, member)) = self.visit_place(&node.expr) {
+                    self.assign(decl_id, member);
+                }
+            },
+            _ => {
+                node.expr.accept_visitor(self);
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<LocalResolved>) -> DefiniteAssignmentResult {
+        if let Some(decl_id) = self.decl_id(node)
+            && self.state.reachable
+            && self.state.unassigned.contains(&decl_id)
+            && self.reported.insert(decl_id)
+        {
+            self.diagnostics.push(ErrMsg::UseBeforeInit(node.path.to_string()).make_diagnostic(*node.get_position()));
+        }
+
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{Compiler, CompilerOptions};
+    use crate::compiler::test_util::{messages, parse_items, resolve_locals};
+
+    fn check(items: &str) -> Vec<Diagnostic> {
+        let scope = parse_items(items);
+        let mut diagnostics = Vec::new();
+        let compiler = Compiler::new(std::iter::once(&scope), CompilerOptions::default());
+        let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+
+        let local_resolved = resolve_locals(&compiler, &scope);
+
+        DefiniteAssignmentChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+
+        diagnostics
+    }
+
+    #[test]
+    fn test_assigned_before_use() {
+        let diagnostics = check("fn f() -> int { let int x; x = 1; return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_use_before_assignment() {
+        let diagnostics = check("fn f() -> int { let int x; let int y = x; x = 1; return y; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_reported_once() {
+        let diagnostics = check("fn f() -> int { let int x; let int y = x + x; return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_assigned_in_both_branches() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; if c { x = 1; } else { x = 2; } return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_assigned_in_one_branch() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; if c { x = 1; } return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_branch_that_returns() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; if c { x = 1; } else { return 0; } return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_assigned_in_while_body() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; while c { x = 1; } return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_assigned_before_break() {
+        let diagnostics = check("fn f() -> int { let int x; loop { x = 1; break; } return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_break_before_assignment() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; loop { if c { break; } x = 1; break; } return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_labeled_break_to_outer_loop() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; loop:outer { loop { if c { break:outer; } x = 1; break; } break; } return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_continue_skips_assignment_before_increment() {
+        let diagnostics = check("fn f(bool c) { let int x; for (let int i = 0; i < 3; i = i + x) { if c { continue; } x = 1; } }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_address_of_initializes() {
+        let diagnostics = check("fn init(int* p) { *p = 1; } fn f() -> int { let int x; init(&x); return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_short_circuit_assignment() {
+        let diagnostics = check("fn f(bool c) -> int { let int x; let bool b = c and { x = 1; result true; }; return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_assigned_before_result() {
+        let diagnostics = check("fn f() -> int { let int x; let int y = { x = 1; result x; }; return x + y; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_lambda_captures_uninitialized() {
+        let diagnostics = check("fn f() { let int x; let fn() -> int g = fn() -> int { return x; }; x = 1; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'x' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_assigned_member_by_member() {
+        let diagnostics = check("struct Point { int x; int y; } fn f() -> Point { let Point p; p.x = 1; let int x = p.x; p.y = x; return p; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_member_left_unassigned() {
+        let diagnostics = check("struct Point { int x; int y; } fn f() -> Point { let Point p; p.x = 1; return p; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'p' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_read_unassigned_member() {
+        let diagnostics = check("struct Point { int x; int y; } fn f() -> int { let Point p; p.x = 1; return p.y; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'p' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_members_assigned_in_both_branches() {
+        let diagnostics = check("struct Point { int x; int y; } fn f(bool c) -> Point { let Point p; if c { p.x = 1; p.y = 2; } else { p.y = 3; p.x = 4; } return p; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_member_assigned_in_one_branch() {
+        let diagnostics = check("struct Point { int x; int y; } fn f(bool c) -> Point { let Point p; p.x = 1; if c { p.y = 2; } return p; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'p' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_nested_member_does_not_assign() {
+        let diagnostics = check("struct Inner { int v; } struct Outer { Inner inner; } fn f() -> Outer { let Outer o; o.inner.v = 1; return o; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'o' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_destructor_struct_not_assigned_member_by_member() {
+        let diagnostics = check("struct Guard { int id; ~{ } } fn f() -> Guard { let Guard g; g.id = 1; return g; }");
+
+        assert_eq!(messages(&diagnostics), vec!["variable 'g' used before being initialized"]);
+    }
+
+    #[test]
+    fn test_initialized_declaration() {
+        let diagnostics = check("fn f() -> int { let int x = 1; return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_reference_bound_at_declaration() {
+        let diagnostics = check("fn f() -> int { let int x = 1; let int& r = x; r = 2; return x; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_unbound_reference() {
+        let diagnostics = check("fn f() -> int { let int x = 1; let int& r; return x; }");
+
+        assert_eq!(messages(&diagnostics), vec!["reference 'r' must be bound when it is declared"]);
+    }
+}
@@ -9,10 +9,18 @@ pub struct LocalResolver<'ctx, 'ast> {
     log_target: &'ctx dyn LogTarget,
     compiler: &'ctx Compiler<'ast>,
     symbol_table: &'ctx SymbolTable<'ast>,
-    scopes: VecDeque<HashMap<&'ast String, AstId>>,
+    scopes: VecDeque<HashMap<&'ast str, AstId>>,
+    lambdas: Vec<LambdaCaptures>,
     unknown_variables: Vec<&'ast Path>
 }
 
+// A lambda currently being resolved. Any variable found in a scope below
+// `scope_depth` lives outside the lambda and has to be captured.
+struct LambdaCaptures {
+    scope_depth: usize,
+    captures: Vec<AstId>,
+}
+
 impl<'ctx, 'ast> LocalResolver<'ctx, 'ast> {
     pub fn new(log_target: &'ctx dyn LogTarget, compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>) -> Self {
         Self {
@@ -20,7 +28,20 @@ impl<'ctx, 'ast> LocalResolver<'ctx, 'ast> {
             compiler,
             symbol_table,
             scopes: VecDeque::new(),
+            lambdas: Vec::new(),
             unknown_variables: Vec::new(),
         }
     }
+
+    fn capture_variable(&mut self, decl_id: AstId, scope_depth: usize) {
+        for lambda in self.lambdas.iter_mut().rev() {
+            if lambda.scope_depth <= scope_depth {
+                break;
+            }
+
+            if !lambda.captures.contains(&decl_id) {
+                lambda.captures.push(decl_id);
+            }
+        }
+    }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::{ast::ASTNode, compiler::{local_resolver::{LocalResolver, witness::{LocalResolverResult, LocalResolverWitness}}, visit::{ASTVisitor, AcceptsASTVisitor, Parsed}}};
+use crate::{ast::ASTNode, compiler::{local_resolver::{LambdaCaptures, LocalResolver, witness::{LocalResolverResult, LocalResolverWitness}}, visit::{ASTVisitor, AcceptsASTVisitor, Parsed}}};
+
+const SELF: &str = "self";
 
 impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolver<'ctx, 'ast> {
     fn visit_array_access(&mut self, node: &'ast crate::ast::ArrayAccess<Parsed>) -> LocalResolverResult {
@@ -10,6 +12,14 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
         self.witness_array_access(node, &expr, &index)
     }
 
+    fn visit_array_literal(&mut self, node: &'ast crate::ast::ArrayLiteral<Parsed>) -> LocalResolverResult {
+        let elements: Vec<_> = node.elements.iter()
+            .map(|element| element.accept_visitor(self))
+            .collect();
+
+        self.witness_array_literal(node, elements.iter())
+    }
+
     fn visit_assignment(&mut self, node: &'ast crate::ast::AssignmentExpr<Parsed>) -> LocalResolverResult {
         let assignee = node.assignee.accept_visitor(self);
         let expr = node.expr.accept_visitor(self);
@@ -43,7 +53,8 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
     }
 
     fn visit_constructor(&mut self, node: &'ast crate::ast::ConstructorItem<Parsed>) -> LocalResolverResult {
-        self.scopes.push_back(HashMap::new());
+        // `self` is the instance under construction, declared by the constructor itself
+        self.scopes.push_back(HashMap::from([(SELF, node.get_id())]));
 
         let parameters: Vec<_> = node.parameters.iter()
             .map(|param| param.accept_visitor(self))
@@ -68,7 +79,7 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
         let expr = node.expr.as_ref().map(|e| e.accept_visitor(self));
 
         let scope = self.scopes.back_mut().unwrap();
-        scope.insert(node.identifier.as_ref(), node.get_id());
+        scope.insert(node.identifier.as_str(), node.get_id());
         
         self.witness_declaration(node, expr.as_ref())
     }
@@ -108,6 +119,12 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
         self.witness_function_access(node, &expr, arguments.iter())
     }
 
+    fn visit_global(&mut self, node: &'ast crate::ast::GlobalItem<Parsed>) -> LocalResolverResult {
+        let expr = node.expr.accept_visitor(self);
+
+        self.witness_global(node, &expr)
+    }
+
     fn visit_if(&mut self, node: &'ast crate::ast::IfExpr<Parsed>) -> LocalResolverResult {
         let condition = node.condition.accept_visitor(self);
         let success = node.success.accept_visitor(self);
@@ -124,6 +141,25 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
         self.witness_impl(node, functions.iter())
     }
 
+    fn visit_lambda(&mut self, node: &'ast crate::ast::LambdaExpr<Parsed>) -> LocalResolverResult {
+        self.lambdas.push(LambdaCaptures {
+            scope_depth: self.scopes.len(),
+            captures: Vec::new(),
+        });
+        self.scopes.push_back(HashMap::new());
+
+        let parameters: Vec<_> = node.parameters.iter()
+            .map(|param| param.accept_visitor(self))
+            .collect();
+
+        let body = node.body.accept_visitor(self);
+
+        self.scopes.pop_back();
+        let lambda = self.lambdas.pop().unwrap();
+
+        self.witness_lambda(node, parameters.iter(), &body, lambda.captures)
+    }
+
     fn visit_literal(&mut self, node: &'ast crate::ast::LiteralExpr<Parsed>) -> LocalResolverResult {
         self.witness_literal(node)
     }
@@ -158,11 +194,16 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
     }
 
     fn visit_struct(&mut self, node: &'ast crate::ast::StructItem<Parsed>) -> LocalResolverResult {
-        let constructors: Vec<_> = node.constructors.iter()
+        let default_values: Vec<_> = node.members.iter()
+            .filter_map(|(_, _, _, default_value)| default_value.as_ref())
+            .map(|default_value| default_value.accept_visitor(self))
+            .collect();
+
+        let constructors: Vec<_> = node.constructors.iter().chain(&node.destructor)
             .map(|ctor| ctor.accept_visitor(self))
             .collect();
         
-        self.witness_struct(node, constructors.iter())
+        self.witness_struct(node, default_values.iter(), constructors.iter())
     }
 
     fn visit_struct_access(&mut self, node: &'ast crate::ast::StructAccess<Parsed>) -> LocalResolverResult {
@@ -171,6 +212,14 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
         self.witness_struct_access(node, &expr)
     }
 
+    fn visit_struct_literal(&mut self, node: &'ast crate::ast::StructLiteral<Parsed>) -> LocalResolverResult {
+        let fields: Vec<_> = node.fields.iter()
+            .map(|(_, value)| value.accept_visitor(self))
+            .collect();
+
+        self.witness_struct_literal(node, fields.iter())
+    }
+
     fn visit_unary(&mut self, node: &'ast crate::ast::UnaryExpr<Parsed>) -> LocalResolverResult {
         let expr = node.expr.accept_visitor(self);
         
@@ -179,14 +228,19 @@ impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, LocalResolverWitness> for LocalResolve
 
     fn visit_var(&mut self, node: &'ast crate::ast::VarExpr<Parsed>) -> LocalResolverResult {
         if node.path.segments.len() == 1 {
-            for scope in self.scopes.iter().rev() {
-                let identifier = node.path.segments.first().unwrap();
-                if let Some(decl_id) = scope.get(identifier) {
-                    return self.witness_var(node, Some(*decl_id));
-                }
+            let identifier = node.path.segments.first().unwrap();
+            let declaration = self.scopes.iter().enumerate().rev()
+                .find_map(|(depth, scope)| scope.get(identifier.as_str()).map(|decl_id| (depth, *decl_id)));
+
+            if let Some((depth, decl_id)) = declaration {
+                self.capture_variable(decl_id, depth);
+                return self.witness_var(node, Some(decl_id));
             }
         }
 
-        self.witness_var(node, None)
+        // Locals shadow globals, so a global is only tried once no declaration matched
+        let global = self.compiler.resolve_global(self.symbol_table, &node.path).map(|(_, decl_id)| decl_id);
+
+        self.witness_var(node, global)
     }
 }
\ No newline at end of file
@@ -22,6 +22,14 @@ impl<'ctx, 'ast> LocalResolver<'ctx, 'ast> {
         VisitResult::new(node.get_id(), LocalResolverWitness::new())
     }
 
+    pub fn witness_array_literal<'itm>(&mut self, node: &ArrayLiteral<Parsed>, elements: impl Iterator<Item = &'itm LocalResolverResult>) -> LocalResolverResult {
+        for (element, confirmation) in node.elements.iter().zip(elements) {
+            assert!(confirmation.verify(element));
+        }
+
+        VisitResult::new(node.get_id(), LocalResolverWitness::new())
+    }
+
     pub fn witness_assignment(&mut self, node: &AssignmentExpr<Parsed>, assignee: &LocalResolverResult, expr: &LocalResolverResult) -> LocalResolverResult {
         assert!(assignee.verify(&node.assignee));
         assert!(expr.verify(&node.expr));
@@ -118,6 +126,12 @@ impl<'ctx, 'ast> LocalResolver<'ctx, 'ast> {
         VisitResult::new(node.get_id(), LocalResolverWitness::new())
     }
 
+    pub fn witness_global(&mut self, node: &GlobalItem<Parsed>, expr: &LocalResolverResult) -> LocalResolverResult {
+        assert!(expr.verify(&node.expr));
+
+        VisitResult::new(node.get_id(), LocalResolverWitness::new())
+    }
+
     pub fn witness_if(&mut self, node: &IfExpr<Parsed>, condition: &LocalResolverResult, success: &LocalResolverResult, fail: Option<&LocalResolverResult>) -> LocalResolverResult {
         assert!(condition.verify(&node.condition));
         assert!(success.verify(&node.success));
@@ -138,6 +152,17 @@ impl<'ctx, 'ast> LocalResolver<'ctx, 'ast> {
         VisitResult::new(node.get_id(), LocalResolverWitness::new())
     }
 
+    pub fn witness_lambda<'itm>(&mut self, node: &LambdaExpr<Parsed>, parameters: impl Iterator<Item = &'itm LocalResolverResult>, body: &LocalResolverResult, captures: Vec<AstId>) -> LocalResolverResult {
+        for (param, confirmation) in node.parameters.iter().zip(parameters) {
+            assert!(confirmation.verify(param));
+        }
+        assert!(body.verify(&node.body));
+
+        self.symbol_table.captures.insert(node.get_id(), captures);
+
+        VisitResult::new(node.get_id(), LocalResolverWitness::new())
+    }
+
     pub fn witness_literal(&mut self, node: &LiteralExpr<Parsed>) -> LocalResolverResult {
         VisitResult::new(node.get_id(), LocalResolverWitness::new())
     }
@@ -188,14 +213,27 @@ impl<'ctx, 'ast> LocalResolver<'ctx, 'ast> {
         VisitResult::new(node.get_id(), LocalResolverWitness::new())
     }
 
-    pub fn witness_struct<'itm>(&mut self, node: &StructItem<Parsed>, constructors: impl Iterator<Item = &'itm LocalResolverResult>) -> LocalResolverResult {
-        for (constructor, confirmation) in node.constructors.iter().zip(constructors) {
+    pub fn witness_struct<'itm>(&mut self, node: &StructItem<Parsed>, default_values: impl Iterator<Item = &'itm LocalResolverResult>, constructors: impl Iterator<Item = &'itm LocalResolverResult>) -> LocalResolverResult {
+        let member_defaults = node.members.iter().filter_map(|(_, _, _, default_value)| default_value.as_ref());
+
+        for (default_value, confirmation) in member_defaults.zip(default_values) {
+            assert!(confirmation.verify(default_value));
+        }
+        for (constructor, confirmation) in node.constructors.iter().chain(&node.destructor).zip(constructors) {
             assert!(confirmation.verify(constructor));
         }
 
         VisitResult::new(node.get_id(), LocalResolverWitness::new())
     }
 
+    pub fn witness_struct_literal<'itm>(&mut self, node: &StructLiteral<Parsed>, fields: impl Iterator<Item = &'itm LocalResolverResult>) -> LocalResolverResult {
+        for ((_, value), confirmation) in node.fields.iter().zip(fields) {
+            assert!(confirmation.verify(value));
+        }
+
+        VisitResult::new(node.get_id(), LocalResolverWitness::new())
+    }
+
     pub fn witness_unary(&mut self, node: &UnaryExpr<Parsed>, expr: &LocalResolverResult) -> LocalResolverResult {
         assert!(expr.verify(&node.expr));
 
@@ -0,0 +1,405 @@
+use crate::{ast::*, compiler::{Compiler, SymbolTable, visit::{ASTVisitor, AcceptsASTVisitor, LocalResolved, PhaseWitness, VisitResult}}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::Positioned};
+
+impl PhaseWitness<LocalResolved> for LvalueWitness {}
+
+pub type LvalueResult = VisitResult<LocalResolved, LvalueWitness>;
+
+pub struct LvalueWitness {
+    _private: (),
+}
+
+// Checks that assignments, `&` and references are only applied to lvalues, expressions
+// that name a place in memory. Literals, operators, calls and other temporaries are rvalues.
+pub struct LvalueChecker<'ctx, 'ast> {
+    compiler: &'ctx Compiler<'ast>,
+    symbol_table: &'ctx SymbolTable<'ast>,
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+}
+
+impl<'ctx, 'ast> LvalueChecker<'ctx, 'ast> {
+    pub fn new(compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>, diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            compiler,
+            symbol_table,
+            diagnostics,
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<LocalResolved>) -> LvalueResult {
+        VisitResult::new(node.get_id(), LvalueWitness { _private: () })
+    }
+
+    fn is_constant(&self, node: &VarExpr<LocalResolved>, decl_id: AstId) -> bool {
+        self.compiler.resolve_global(self.symbol_table, &node.path)
+            .is_some_and(|(owner, global_id)| global_id == decl_id && owner.constants.contains_key(&global_id))
+    }
+
+    // The function a name refers to when no variable goes by it
+    fn resolve_function(&self, node: &VarExpr<LocalResolved>) -> Option<&'ast FunctionItem> {
+        if self.symbol_table.variables.contains_key(&node.get_id()) {
+            return None;
+        }
+
+        let (owner, decl_id) = self.compiler.resolve_item(self.symbol_table, &node.path)?;
+        owner.functions.get(&decl_id).map(|function| *function)
+    }
+
+    // Locals, parameters and statics are places. Constants are folded into their uses and
+    // functions are code, so neither is. Names that resolve to nothing are reported elsewhere.
+    fn is_variable(&self, node: &VarExpr<LocalResolved>) -> bool {
+        match self.symbol_table.variables.get(&node.get_id()) {
+            Some(decl_id) => !self.is_constant(node, *decl_id),
+            None => self.resolve_function(node).is_none(),
+        }
+    }
+
+    // Indexing and `->` go through a pointer, so they name a place even when their operand doesn't.
+    // A member reached with `.` is only a place if the struct it belongs to is one.
+    fn is_lvalue(&self, expr: &ASTEnum<LocalResolved>) -> bool {
+        match expr {
+            ASTEnum::Var(var) => self.is_variable(var),
+            ASTEnum::ArrayAccess(_) => true,
+            ASTEnum::Unary(unary) => matches!(unary.operator, UnaryOperator::Deref),
+            ASTEnum::StructAccess(struct_access) => !struct_access.is_direct || self.is_lvalue(&struct_access.expr),
+            _ => false,
+        }
+    }
+
+    fn check_assignee(&mut self, assignee: &ASTEnum<LocalResolved>) {
+        let err_msg = match assignee {
+            ASTEnum::Var(var) if self.resolve_function(var).is_some() => ErrMsg::AssignToFunction(var.path.to_string()),
+            ASTEnum::Var(var) if !self.is_variable(var) => ErrMsg::AssignToConst(var.path.to_string()),
+            _ if !self.is_lvalue(assignee) => ErrMsg::AssignToRvalue,
+            _ => return,
+        };
+
+        self.diagnostics.push(err_msg.make_diagnostic(*assignee.get_position()));
+    }
+
+    // A reference is bound to the place it is initialized with, there is no temporary to outlive
+    fn check_reference_binding(&mut self, parsed_type: &ParsedType, expr: &ASTEnum<LocalResolved>) {
+        if matches!(parsed_type.parsed_type, ParsedTypeEnum::Reference(_)) && !self.is_lvalue(expr) {
+            self.diagnostics.push(ErrMsg::ReferenceToRvalue.make_diagnostic(*expr.get_position()));
+        }
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, LocalResolved, LvalueWitness> for LvalueChecker<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<LocalResolved>) -> LvalueResult {
+        node.expr.accept_visitor(self);
+        node.index.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<LocalResolved>) -> LvalueResult {
+        for element in &node.elements {
+            element.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<LocalResolved>) -> LvalueResult {
+        self.check_assignee(&node.assignee);
+
+        node.assignee.accept_visitor(self);
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<LocalResolved>) -> LvalueResult {
+        node.left.accept_visitor(self);
+        node.right.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<LocalResolved>) -> LvalueResult {
+        for expr in &node.exprs {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<LocalResolved>) -> LvalueResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<LocalResolved>) -> LvalueResult {
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<LocalResolved>) -> LvalueResult {
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<LocalResolved>) -> LvalueResult {
+        if let Some(expr) = &node.expr {
+            self.check_reference_binding(&node.declaration_type, expr);
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<LocalResolved>) -> LvalueResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<LocalResolved>) -> LvalueResult {
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<LocalResolved>) -> LvalueResult {
+        if let Some(body) = &node.body {
+            body.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<LocalResolved>) -> LvalueResult {
+        // Only calls of named functions are checked here, the parameters of anything else are known once it is typed
+        if let ASTEnum::Var(var) = &node.expr
+            && let Some(function) = self.resolve_function(var)
+        {
+            for (parameter, argument) in function.parameters.iter().zip(&node.arguments) {
+                self.check_reference_binding(&parameter.declaration_type, argument);
+            }
+        }
+
+        node.expr.accept_visitor(self);
+
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<LocalResolved>) -> LvalueResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<LocalResolved>) -> LvalueResult {
+        node.condition.accept_visitor(self);
+        node.success.accept_visitor(self);
+
+        if let Some(fail) = &node.fail {
+            fail.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<LocalResolved>) -> LvalueResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<LocalResolved>) -> LvalueResult {
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<LocalResolved>) -> LvalueResult {
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<LocalResolved>) -> LvalueResult {
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        if let Some(condition) = &node.condition {
+            condition.accept_visitor(self);
+        }
+
+        if let Some(increment) = &node.increment {
+            increment.accept_visitor(self);
+        }
+
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<LocalResolved>) -> LvalueResult {
+        for size in &node.sizes {
+            size.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<LocalResolved>) -> LvalueResult {
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<LocalResolved>) -> LvalueResult {
+        for (_, _, _, default_value) in &node.members {
+            if let Some(default_value) = default_value {
+                default_value.accept_visitor(self);
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<LocalResolved>) -> LvalueResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<LocalResolved>) -> LvalueResult {
+        for (_, value) in &node.fields {
+            value.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<LocalResolved>) -> LvalueResult {
+        if matches!(node.operator, UnaryOperator::AddressOf) && !self.is_lvalue(&node.expr) {
+            self.diagnostics.push(ErrMsg::AddressOfRvalue.make_diagnostic(*node.expr.get_position()));
+        }
+
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<LocalResolved>) -> LvalueResult {
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilerOptions;
+    use crate::compiler::test_util::{messages, parse_items, resolve_locals};
+
+    fn check(items: &str) -> Vec<Diagnostic> {
+        let scope = parse_items(items);
+        let compiler = Compiler::new(std::iter::once(&scope), CompilerOptions::default());
+        let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+        let local_resolved = resolve_locals(&compiler, &scope);
+
+        let mut diagnostics = Vec::new();
+        LvalueChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+
+        diagnostics
+    }
+
+    #[test]
+    fn test_assign_to_places() {
+        let diagnostics = check("struct P { int x; } fn g() -> P* { return null; } fn f(int *p, int[] a, P s) { let int x = 0; x = 1; *p = 2; a[0] = 3; s.x = 4; g()->x = 5; *&x = 6; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_assign_to_rvalues() {
+        let diagnostics = check("struct P { int x; } fn g() -> int { return 0; } fn h() -> P { return P { x: 0 }; } fn f(int a, int b) { a + b = 1; g() = 2; 3 = 4; h().x = 5; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "cannot assign to a temporary value",
+            "cannot assign to a temporary value",
+            "cannot assign to a temporary value",
+            "cannot assign to a temporary value",
+        ]);
+    }
+
+    #[test]
+    fn test_address_of_rvalue() {
+        let diagnostics = check("fn g() -> int { return 0; } fn f(int a) { let int *p = &a; let int *q = &(a + 1); let int *r = &g(); }");
+
+        assert_eq!(messages(&diagnostics), vec!["cannot take the address of a temporary value", "cannot take the address of a temporary value"]);
+    }
+
+    #[test]
+    fn test_complex_lvalues() {
+        // The sample predates scopes, so it is wrapped in one with `main` as a plain function
+        let source = include_str!("../tests/complex_lvalues.mar").replacen("main {", "fn main() {", 1);
+        let diagnostics = check(&source);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_assign_to_constants_and_functions() {
+        let diagnostics = check("const LIMIT: int = 4; static count: int = 0; fn g() {} fn f() { count = 1; LIMIT = 2; g = f; }");
+
+        assert_eq!(messages(&diagnostics), vec!["cannot assign to constant 'LIMIT'", "cannot assign to function 'g'"]);
+    }
+
+    #[test]
+    fn test_locals_shadow_constants() {
+        let diagnostics = check("const LIMIT: int = 4; fn f() { let int LIMIT = 0; LIMIT = 1; let int *p = &LIMIT; }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_address_of_constants_and_functions() {
+        let diagnostics = check("const LIMIT: int = 4; static count: int = 0; fn g() {} fn f() { let int *p = &count; let int *q = &LIMIT; let int *r = &g; }");
+
+        assert_eq!(messages(&diagnostics), vec!["cannot take the address of a temporary value", "cannot take the address of a temporary value"]);
+    }
+
+    #[test]
+    fn test_bind_references_to_places() {
+        let diagnostics = check("struct P { int x; } fn bump(int& counter) { counter = counter + 1; } fn f(P *p, int[] a) { let int x = 0; let int& r = x; let int& s = p->x; bump(x); bump(a[0]); bump(r); }");
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_bind_references_to_rvalues() {
+        let diagnostics = check("const LIMIT: int = 4; fn bump(int& counter) { counter = counter + 1; } fn g() -> int { return 0; } fn f(int a) { let int& r = a + 1; let int& s = LIMIT; bump(g()); bump(2); }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "cannot bind a reference to a temporary value",
+            "cannot bind a reference to a temporary value",
+            "cannot bind a reference to a temporary value",
+            "cannot bind a reference to a temporary value",
+        ]);
+    }
+}
@@ -1,80 +1,672 @@
+pub mod const_eval;
+pub mod control_flow;
+pub mod definite_assignment;
 pub mod local_resolver;
+pub mod lvalue;
+pub mod structs;
+#[cfg(test)]
+pub mod test_util;
+pub mod type_checker;
+pub mod visibility;
 pub mod visit;
 
-use std::{collections::{HashMap, HashSet}, sync::{MappedRwLockReadGuard, RwLock, RwLockReadGuard}};
+use std::{collections::{HashMap, HashSet}, path::PathBuf, sync::{MappedRwLockReadGuard, RwLock, RwLockReadGuard}};
 
 use dashmap::{DashMap, DashSet};
 use serde::Serialize;
 
-use crate::{ast::{AstId, ParsedType, ParsedTypeEnum, Scope}, diagnostic::Diagnostic};
+use crate::{ast::{ASTEnum, ASTNode, AstId, Attribute, DeclarationExpr, FunctionItem, GlobalItem, GlobalKind, ParsedType, ParsedTypeEnum, Path, Scope, StructItem, Visibility, is_entry, section}, compiler::const_eval::{ConstValue, numeric_type_name}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::{Located, NumericType, Positioned}};
+
+pub struct CompilerOptions {
+    // Emit a runtime check (reporting file and line) before every slice or fixed-size array index
+    pub bounds_checks: bool,
+    // Directories searched for the files of required scopes, the working directory when empty
+    pub source_roots: Vec<PathBuf>,
+    // Where `new` gets its memory from and where `delete` gives it back
+    pub allocator: Allocator,
+    // Size in bytes of the static arena the bump allocator hands memory out of
+    pub heap_size: usize,
+    // Instrument heap accesses to catch use after free, double delete, out of bounds and null pointers
+    pub sanitize_memory: bool,
+    pub opt_level: OptLevel,
+    // What the compiled module is written out as
+    pub emit: Emit,
+    // LLVM target triple, CPU and feature string code is generated for, the host when unset
+    pub target_triple: Option<String>,
+    pub target_cpu: Option<String>,
+    pub target_features: Option<String>,
+    // Program that links the emitted object into an executable, followed by its extra arguments
+    pub linker: String,
+    pub link_args: Vec<String>,
+    // Bare-metal build: nothing from the C library is called or linked
+    pub freestanding: bool,
+    // Where the emitted file is written, a default name for the emit kind when unset
+    pub output_file: Option<PathBuf>,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        Self {
+            bounds_checks: true,
+            source_roots: Vec::new(),
+            allocator: Allocator::Libc,
+            heap_size: 64 * 1024,
+            sanitize_memory: false,
+            opt_level: OptLevel::O0,
+            emit: Emit::Executable,
+            target_triple: None,
+            target_cpu: None,
+            target_features: None,
+            linker: String::from("cc"),
+            link_args: Vec::new(),
+            freestanding: false,
+            output_file: None,
+        }
+    }
+}
+
+impl CompilerOptions {
+    // C library functions the generated code calls by their external names. User items keep
+    // their names too, so declaring one of these would take the place of the runtime's.
+    pub fn runtime_symbols(&self) -> Vec<&'static str> {
+        let mut symbols = Vec::new();
+
+        if self.allocator == Allocator::Libc {
+            symbols.extend(["malloc", "free"]);
+        }
+
+        // Failed bounds and sanitizer checks report through it
+        if !self.freestanding {
+            symbols.push("dprintf");
+        }
+
+        symbols
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Allocator {
+    // `malloc` and `free` from the C library
+    Libc,
+    // Carves allocations out of a static arena and never reuses them, for targets without a C library
+    Bump,
+}
+
+impl Allocator {
+    pub fn from_name(name: &str) -> Option<Allocator> {
+        match name {
+            "libc" => Some(Allocator::Libc),
+            "bump" => Some(Allocator::Bump),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OptLevel {
+    O0,
+    O1,
+    O2,
+    O3,
+    // Like O2, but leaves out optimizations that grow the code
+    Os,
+}
+
+impl OptLevel {
+    pub fn from_flag(flag: &str) -> Option<OptLevel> {
+        match flag {
+            "-O0" => Some(OptLevel::O0),
+            "-O1" => Some(OptLevel::O1),
+            "-O2" => Some(OptLevel::O2),
+            "-O3" => Some(OptLevel::O3),
+            "-Os" => Some(OptLevel::Os),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Emit {
+    // An object file handed to the linker
+    Executable,
+    // A relocatable object file for the target
+    Object,
+    // Target assembly as text
+    Assembly,
+    // The optimized LLVM IR as text
+    LlvmIr,
+}
+
+impl Emit {
+    pub fn from_name(name: &str) -> Option<Emit> {
+        match name {
+            "exe" => Some(Emit::Executable),
+            "obj" => Some(Emit::Object),
+            "asm" => Some(Emit::Assembly),
+            "llvm-ir" => Some(Emit::LlvmIr),
+            _ => None,
+        }
+    }
+
+    pub fn default_output(&self) -> &'static str {
+        match self {
+            Emit::Executable => "a.out",
+            Emit::Object => "output.o",
+            Emit::Assembly => "output.s",
+            Emit::LlvmIr => "output.ll",
+        }
+    }
+}
 
 pub struct Compiler<'ast> {
     pub type_arena: TypeArena,
     pub symbol_tables: HashMap<&'ast[String], SymbolTable<'ast>>,
     pub diagnostics: Vec<Diagnostic>,
+    pub options: CompilerOptions,
 }
 
 impl<'ast> Compiler<'ast> {
-    pub fn new(scopes: impl Iterator<Item = &'ast Scope>) -> Self {
+    pub fn new(scopes: impl Iterator<Item = &'ast Scope>, options: CompilerOptions) -> Self {
+        let scopes = scopes.collect::<Vec<_>>();
         let mut symbol_tables = HashMap::new();
+        let mut diagnostics = Vec::new();
+        // The startup code calls a single `#[entry]` function, whichever scope declares it
+        let mut entry: Option<&Located<String>> = None;
+        let runtime_symbols = options.runtime_symbols();
+
+        for &scope in &scopes {
+            let symbol_table = SymbolTable::new(scope.path.segments.as_slice());
 
-        for scope in scopes {
-            symbol_tables.insert(scope.path.segments.as_slice(), SymbolTable::new());
+            // Items are registered up front so that any scope can resolve them by path
+            for item in &scope.items {
+                let visibility = match item {
+                    ASTEnum::Global(global) => {
+                        for attribute in global.attributes.iter().filter(|attribute| ***attribute == Attribute::Entry) {
+                            diagnostics.push(ErrMsg::MisplacedAttribute(attribute.name().to_string()).make_diagnostic(*attribute.get_position()));
+                        }
+
+                        if runtime_symbols.contains(&global.name.as_str()) {
+                            diagnostics.push(ErrMsg::ReservedRuntimeName(global.name.to_string()).make_diagnostic(*global.name.get_position()));
+                        }
+
+                        if symbol_table.globals.insert(global.name.to_string(), global.get_id()).is_some() {
+                            diagnostics.push(ErrMsg::DuplicateVariable(global.name.to_string()).make_diagnostic(*global.name.get_position()));
+                        }
+
+                        if global.kind == GlobalKind::Const {
+                            symbol_table.constants.insert(global.get_id(), global);
+                        }
+
+                        global.visibility
+                    },
+                    ASTEnum::Function(function) => {
+                        if runtime_symbols.contains(&function.name.as_str()) {
+                            diagnostics.push(ErrMsg::ReservedRuntimeName(function.name.to_string()).make_diagnostic(*function.name.get_position()));
+                        }
+
+                        if is_entry(&function.attributes) {
+                            if entry.is_some() {
+                                diagnostics.push(ErrMsg::DuplicateEntry(function.name.to_string()).make_diagnostic(*function.name.get_position()));
+                            } else {
+                                entry = Some(&function.name);
+                            }
+
+                            if !function.parameters.is_empty() {
+                                diagnostics.push(ErrMsg::EntryWithParameters(function.name.to_string()).make_diagnostic(*function.name.get_position()));
+                            }
+                        }
+
+                        // An extern function is defined elsewhere, there is nothing here to place in a section
+                        if function.body.is_none() && section(&function.attributes).is_some() {
+                            diagnostics.push(ErrMsg::MisplacedAttribute(String::from("section")).make_diagnostic(*function.name.get_position()));
+                        }
+
+                        symbol_table.items.insert(function.name.to_string(), function.get_id());
+                        symbol_table.functions.insert(function.get_id(), function);
+                        function.visibility
+                    },
+                    ASTEnum::Struct(struct_item) => {
+                        symbol_table.items.insert(struct_item.name.to_string(), struct_item.get_id());
+                        symbol_table.structs.insert(struct_item.get_id(), struct_item);
+                        struct_item.visibility
+                    },
+                    _ => continue,
+                };
+
+                if visibility == Visibility::Public {
+                    symbol_table.public_items.insert(item.get_id());
+                }
+            }
+
+            symbol_tables.insert(scope.path.segments.as_slice(), symbol_table);
+        }
+
+        // Requires can name items of any scope, so they are only resolved once every item is registered
+        for &scope in &scopes {
+            let symbol_table = &symbol_tables[scope.path.segments.as_slice()];
+            Self::register_requires(&symbol_tables, scope, symbol_table, &mut diagnostics);
         }
 
         Self {
             type_arena: TypeArena::new(),
             symbol_tables,
-            diagnostics: Vec::new(),
+            diagnostics,
+            options,
+        }
+    }
+
+    fn register_requires(symbol_tables: &HashMap<&'ast [String], SymbolTable<'ast>>, scope: &Scope, symbol_table: &SymbolTable<'ast>, diagnostics: &mut Vec<Diagnostic>) {
+        let is_declared = |symbol_table: &SymbolTable, name: &str| {
+            symbol_table.globals.contains_key(name) || symbol_table.items.contains_key(name)
+        };
+
+        // Explicit requires go first so that they take precedence over the names a glob brings in
+        let (globs, requires): (Vec<_>, Vec<_>) = scope.requires.iter().enumerate().partition(|(_, require)| require.glob);
+
+        for (index, require) in requires {
+            let position = *require.path.get_position();
+            let (name, scope_path) = require.path.segments.split_last().unwrap();
+            let alias = require.alias.as_ref().map_or(name, |alias| &**alias);
+
+            if let Some(owner) = symbol_tables.get(scope_path) && is_declared(owner, name) {
+                let import = Import { owner: owner.path, name: name.clone(), require: index, glob: false };
+
+                if is_declared(symbol_table, alias) {
+                    diagnostics.push(ErrMsg::ConflictingImport(alias.clone()).make_diagnostic(position));
+                } else if let Some(existing) = symbol_table.imports.get(alias) && !existing.is_same_item(&import) {
+                    diagnostics.push(ErrMsg::ConflictingImport(alias.clone()).make_diagnostic(position));
+                } else {
+                    symbol_table.imports.insert(alias.clone(), import);
+                }
+            } else if let Some(required_scope) = symbol_tables.get(require.path.segments.as_slice()) {
+                if let Some(existing) = symbol_table.scope_imports.get(alias) && existing.0 != required_scope.path {
+                    diagnostics.push(ErrMsg::ConflictingImport(alias.clone()).make_diagnostic(position));
+                } else {
+                    symbol_table.scope_imports.insert(alias.clone(), (required_scope.path, index));
+                }
+            } else {
+                diagnostics.push(ErrMsg::UnresolvedImport(require.path.to_string()).make_diagnostic(position));
+            }
+        }
+
+        for (index, require) in globs {
+            let position = *require.path.get_position();
+
+            let Some(owner) = symbol_tables.get(require.path.segments.as_slice()) else {
+                diagnostics.push(ErrMsg::UnresolvedImport(require.path.to_string()).make_diagnostic(position));
+                continue;
+            };
+
+            let mut names = owner.globals.iter()
+                .chain(owner.items.iter())
+                .filter(|entry| owner.public_items.contains(entry.value()))
+                .map(|entry| entry.key().clone())
+                .collect::<Vec<_>>();
+            names.sort();
+
+            for name in names {
+                // Items of the scope itself shadow anything a glob brings in
+                if is_declared(symbol_table, &name) {
+                    continue;
+                }
+
+                let import = Import { owner: owner.path, name: name.clone(), require: index, glob: true };
+
+                if let Some(existing) = symbol_table.imports.get(&name) {
+                    if existing.glob && !existing.is_same_item(&import) {
+                        diagnostics.push(ErrMsg::ConflictingImport(name).make_diagnostic(position));
+                    }
+                } else {
+                    symbol_table.imports.insert(name, import);
+                }
+            }
         }
     }
+
+    // A bare name refers to a global of the current scope, `Math::PI` to one in scope `Math`.
+    // Returns the symbol table of the scope that owns the global along with its id.
+    pub fn resolve_global<'a>(&'a self, symbol_table: &'a SymbolTable<'ast>, path: &Path) -> Option<(&'a SymbolTable<'ast>, AstId)> {
+        self.resolve_path(symbol_table, path, |symbol_table, name| {
+            symbol_table.globals.get(name).map(|decl_id| *decl_id)
+        })
+    }
+
+    // Like `resolve_global`, but also finds the functions and structs of a scope
+    pub fn resolve_item<'a>(&'a self, symbol_table: &'a SymbolTable<'ast>, path: &Path) -> Option<(&'a SymbolTable<'ast>, AstId)> {
+        self.resolve_path(symbol_table, path, |symbol_table, name| {
+            symbol_table.globals.get(name)
+                .or_else(|| symbol_table.items.get(name))
+                .map(|decl_id| *decl_id)
+        })
+    }
+
+    // Finds the scope owning the last segment of `path` and looks the name up in it, going
+    // through the requires of the current scope when nothing it declares matches.
+    // Every require that leads somewhere is marked as used.
+    fn resolve_path<'a>(&'a self, symbol_table: &'a SymbolTable<'ast>, path: &Path, lookup: impl Fn(&SymbolTable<'ast>, &str) -> Option<AstId>) -> Option<(&'a SymbolTable<'ast>, AstId)> {
+        let (name, scope_path) = path.segments.split_last()?;
+
+        if scope_path.is_empty() {
+            if let Some(decl_id) = lookup(symbol_table, name) {
+                return Some((symbol_table, decl_id));
+            }
+
+            let import = symbol_table.imports.get(name)?;
+            let owner = self.symbol_tables.get(import.owner)?;
+            let decl_id = lookup(owner, &import.name)?;
+
+            symbol_table.used_requires.insert(import.require);
+
+            return Some((owner, decl_id));
+        }
+
+        if let Some(owner) = self.symbol_tables.get(scope_path) {
+            let decl_id = lookup(owner, name)?;
+
+            // `Foo::Bar::get` needs no alias, but the require of `Foo::Bar` is what loads its file
+            if let Some(require) = Self::require_of_scope(symbol_table, scope_path) {
+                symbol_table.used_requires.insert(require);
+            }
+
+            return Some((owner, decl_id));
+        }
+
+        // `Lib::helper` after `require Main::Lib;`
+        let (first, rest) = scope_path.split_first()?;
+        let scope_import = symbol_table.scope_imports.get(first)?;
+        let (required_path, require) = *scope_import;
+        let owner = self.symbol_tables.get([required_path, rest].concat().as_slice())?;
+        let decl_id = lookup(owner, name)?;
+
+        symbol_table.used_requires.insert(require);
+
+        Some((owner, decl_id))
+    }
+
+    // The require that brings in the scope at `scope_path` or one enclosing it, preferring a
+    // require of the scope itself over one of an item or glob
+    fn require_of_scope(symbol_table: &SymbolTable<'ast>, scope_path: &[String]) -> Option<usize> {
+        let scope_require = symbol_table.scope_imports.iter()
+            .filter(|entry| scope_path.starts_with(entry.value().0))
+            .map(|entry| entry.value().1)
+            .min();
+
+        scope_require.or_else(|| {
+            symbol_table.imports.iter()
+                .filter(|entry| scope_path.starts_with(entry.value().owner))
+                .map(|entry| entry.value().require)
+                .min()
+        })
+    }
+
+    // Private items can only be used by the scope that declares them and the scopes nested inside it
+    pub fn is_visible(&self, symbol_table: &SymbolTable<'ast>, owner: &SymbolTable<'ast>, decl_id: AstId) -> bool {
+        owner.public_items.contains(&decl_id) || symbol_table.path.starts_with(owner.path)
+    }
+
+    // Private struct members follow the same rule as private items of the scope declaring the struct
+    pub fn is_member_visible(&self, symbol_table: &SymbolTable<'ast>, owner: &SymbolTable<'ast>, visibility: Visibility) -> bool {
+        visibility == Visibility::Public || symbol_table.path.starts_with(owner.path)
+    }
+
+    // Gives every struct, function and global its type. Runs once all scopes passed the parsed
+    // checks, since member and parameter types may name structs of any scope or fold constants.
+    pub fn register_types(&self, scopes: impl Iterator<Item = &'ast Scope>) -> Vec<Diagnostic> {
+        let scopes = scopes.collect::<Vec<_>>();
+        let mut diagnostics = Vec::new();
+
+        // Struct types are reserved up front so that members and signatures can refer to any of them
+        for &scope in &scopes {
+            let symbol_table = &self.symbol_tables[scope.path.segments.as_slice()];
+
+            for item in &scope.items {
+                if let ASTEnum::Struct(struct_item) = item {
+                    symbol_table.types.insert(struct_item.name.to_string(), self.type_arena.reserve());
+                }
+            }
+        }
+
+        for &scope in &scopes {
+            let symbol_table = &self.symbol_tables[scope.path.segments.as_slice()];
+
+            for item in &scope.items {
+                match item {
+                    ASTEnum::Struct(struct_item) => self.register_struct(symbol_table, struct_item, &mut diagnostics),
+                    ASTEnum::Function(function) => self.register_function(symbol_table, function, &mut diagnostics),
+                    ASTEnum::Impl(impl_item) => {
+                        for function in &impl_item.functions {
+                            self.register_function(symbol_table, function, &mut diagnostics);
+                        }
+                    },
+                    ASTEnum::Global(global) => {
+                        if let Some(type_id) = self.resolve_type(symbol_table, &global.declaration_type, &mut diagnostics) {
+                            symbol_table.declaration_types.insert(global.get_id(), type_id);
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    fn register_struct(&self, symbol_table: &SymbolTable<'ast>, struct_item: &StructItem, diagnostics: &mut Vec<Diagnostic>) {
+        let struct_type_id = *symbol_table.types.get(&*struct_item.name).unwrap();
+        let mut members = HashMap::new();
+        let mut private_members = HashSet::new();
+        let mut constructors = HashSet::new();
+
+        for (visibility, member_type, member_name, _) in &struct_item.members {
+            // A member has no declaration to bind it at, so it could be left referring to nothing
+            if matches!(member_type.parsed_type, ParsedTypeEnum::Reference(_)) {
+                diagnostics.push(ErrMsg::MisplacedReference(String::from("struct member")).make_diagnostic(*member_type.get_position()));
+            } else if let Some(type_id) = self.resolve_type(symbol_table, member_type, diagnostics) {
+                members.insert(member_name.to_string(), type_id);
+            }
+
+            if *visibility == Visibility::Private {
+                private_members.insert(member_name.to_string());
+            }
+        }
+
+        // A constructor has the type of a function returning the struct, and `self` inside it is the struct
+        for constructor in &struct_item.constructors {
+            if let Some(param_types) = self.register_parameters(symbol_table, &constructor.parameters, diagnostics) {
+                constructors.insert(self.type_arena.make_function(FunctionType { param_types, return_type: struct_type_id }));
+            }
+
+            symbol_table.declaration_types.insert(constructor.get_id(), struct_type_id);
+        }
+
+        if let Some(destructor) = &struct_item.destructor {
+            symbol_table.declaration_types.insert(destructor.get_id(), struct_type_id);
+        }
+
+        self.type_arena.set_type(&struct_type_id, ResolvedType::Struct(StructType {
+            name: struct_item.name.to_string(),
+            scope: symbol_table.path.to_vec(),
+            members,
+            private_members,
+            constructors,
+        }));
+    }
+
+    fn register_function(&self, symbol_table: &SymbolTable<'ast>, function: &FunctionItem, diagnostics: &mut Vec<Diagnostic>) {
+        let param_types = self.register_parameters(symbol_table, &function.parameters, diagnostics);
+        let return_type = self.resolve_return_type(symbol_table, &function.return_type, diagnostics);
+
+        if let (Some(param_types), Some(return_type)) = (param_types, return_type) {
+            symbol_table.declaration_types.insert(function.get_id(), self.type_arena.make_function(FunctionType { param_types, return_type }));
+        }
+    }
+
+    // Like `resolve_type`, but for what a function or lambda returns. Whatever a returned reference
+    // would refer to may be a local of the function that just returned, so returns are values only.
+    pub fn resolve_return_type(&self, symbol_table: &SymbolTable<'ast>, parsed_type: &ParsedType, diagnostics: &mut Vec<Diagnostic>) -> Option<TypeId> {
+        if matches!(parsed_type.parsed_type, ParsedTypeEnum::Reference(_)) {
+            diagnostics.push(ErrMsg::MisplacedReference(String::from("return type")).make_diagnostic(*parsed_type.get_position()));
+            return None;
+        }
+
+        self.resolve_type(symbol_table, parsed_type, diagnostics)
+    }
+
+    // Every parameter is resolved so that each unknown type name gets reported
+    fn register_parameters(&self, symbol_table: &SymbolTable<'ast>, parameters: &[DeclarationExpr], diagnostics: &mut Vec<Diagnostic>) -> Option<Vec<TypeId>> {
+        let param_types = parameters.iter()
+            .map(|parameter| {
+                let type_id = self.resolve_type(symbol_table, &parameter.declaration_type, diagnostics)?;
+                symbol_table.declaration_types.insert(parameter.get_id(), type_id);
+                Some(type_id)
+            })
+            .collect::<Vec<_>>();
+
+        param_types.into_iter().collect()
+    }
+
+    // Resolves a type written in the scope of `symbol_table`. Struct names are looked up like any other
+    // item, through the requires of the scope. Array lengths naming constants were folded by the
+    // constant evaluator, which also reported the ones that could not be.
+    pub fn resolve_type(&self, symbol_table: &SymbolTable<'ast>, parsed_type: &ParsedType, diagnostics: &mut Vec<Diagnostic>) -> Option<TypeId> {
+        let type_arena = &self.type_arena;
+
+        Some(match &parsed_type.parsed_type {
+            ParsedTypeEnum::Void => type_arena.void(),
+            ParsedTypeEnum::Boolean => type_arena.bool(),
+            ParsedTypeEnum::Char => type_arena.char(),
+            ParsedTypeEnum::Str => type_arena.str(),
+            ParsedTypeEnum::Integer => type_arena.int(),
+            ParsedTypeEnum::Double => type_arena.double(),
+            ParsedTypeEnum::Numeric(numeric_type) => type_arena.numeric(*numeric_type),
+            ParsedTypeEnum::Array(array_type) => {
+                let array_type_id = self.resolve_type(symbol_table, array_type, diagnostics)?;
+                type_arena.make_array(array_type_id)
+            },
+            ParsedTypeEnum::FixedArray(array_type, length) => {
+                let array_type_id = self.resolve_type(symbol_table, array_type, diagnostics)?;
+                type_arena.make_fixed_array(array_type_id, *length)
+            },
+            ParsedTypeEnum::ConstArray(array_type, length) => {
+                let array_type_id = self.resolve_type(symbol_table, array_type, diagnostics)?;
+                type_arena.make_fixed_array(array_type_id, *symbol_table.array_lengths.get(length)?)
+            },
+            ParsedTypeEnum::Pointer(ptr_type) => {
+                let ptr_type_id = self.resolve_type(symbol_table, ptr_type, diagnostics)?;
+                type_arena.make_ptr(ptr_type_id)
+            },
+            ParsedTypeEnum::Reference(ref_type) => {
+                let ref_type_id = self.resolve_type(symbol_table, ref_type, diagnostics)?;
+                type_arena.make_ref(ref_type_id)
+            },
+            ParsedTypeEnum::Function(param_types, return_type) => {
+                let param_types = param_types.iter()
+                    .map(|param_type| self.resolve_type(symbol_table, param_type, diagnostics))
+                    .collect::<Vec<_>>();
+                let return_type = self.resolve_return_type(symbol_table, return_type, diagnostics)?;
+
+                type_arena.make_function(FunctionType { param_types: param_types.into_iter().collect::<Option<_>>()?, return_type })
+            },
+            ParsedTypeEnum::TypeName(type_name) => {
+                let path = Path::new(vec![Located::new(type_name.clone(), *parsed_type.get_position())]);
+
+                let struct_type_id = self.resolve_item(symbol_table, &path)
+                    .and_then(|(owner, decl_id)| {
+                        let struct_item = owner.structs.get(&decl_id)?;
+                        owner.types.get(&*struct_item.name).map(|type_id| *type_id)
+                    });
+
+                match struct_type_id {
+                    Some(type_id) => type_id,
+                    None => {
+                        diagnostics.push(ErrMsg::UnknownTypeName(type_name.clone()).make_diagnostic(*parsed_type.get_position()));
+                        return None;
+                    },
+                }
+            },
+        })
+    }
+
+    // Only meaningful once every pass that resolves paths has run over the scope
+    pub fn unused_requires(&self, scope: &Scope, symbol_table: &SymbolTable<'ast>) -> Vec<Diagnostic> {
+        scope.requires.iter().enumerate()
+            .filter(|(index, _)| !symbol_table.used_requires.contains(index))
+            .map(|(_, require)| ErrMsg::UnusedImport(require.path.to_string()).make_warning(*require.path.get_position()))
+            .collect()
+    }
+}
+
+// An item of another scope brought in by a `require`
+pub struct Import<'ast> {
+    // Path of the scope that declares the item
+    pub owner: &'ast [String],
+    pub name: String,
+    // Index of the require in its scope
+    pub require: usize,
+    pub glob: bool,
+}
+
+impl<'ast> Import<'ast> {
+    fn is_same_item(&self, other: &Import<'ast>) -> bool {
+        self.owner == other.owner && self.name == other.name
+    }
 }
 
 pub struct SymbolTable<'ast> {
+    // Path of the scope this table belongs to
+    pub path: &'ast [String],
     pub types: DashMap<String, TypeId>,
     pub function_names: DashSet<&'ast String>,
-    pub functions: DashMap<String, TypeId>,
     pub ast_types: DashMap<AstId, TypeId>,
     pub declaration_types: DashMap<AstId, TypeId>,
     pub variables: DashMap<AstId, AstId>,
+    pub captures: DashMap<AstId, Vec<AstId>>,
+    pub globals: DashMap<String, AstId>,
+    // Functions and structs declared in this scope
+    pub items: DashMap<String, AstId>,
+    // Items marked `pub`, which any scope may use
+    pub public_items: DashSet<AstId>,
+    // Items brought in by requires, keyed by the name they are used under
+    pub imports: DashMap<String, Import<'ast>>,
+    // Scopes brought in by requires, along with the index of that require
+    pub scope_imports: DashMap<String, (&'ast [String], usize)>,
+    // Indices of the requires that some path resolved through
+    pub used_requires: DashSet<usize>,
+    pub constants: DashMap<AstId, &'ast GlobalItem>,
+    pub structs: DashMap<AstId, &'ast StructItem>,
+    pub functions: DashMap<AstId, &'ast FunctionItem>,
+    // Folded values of constants, filled in lazily by the constant evaluator
+    pub const_values: DashMap<AstId, ConstValue>,
+    // Lengths of the `T[NAME]` array types written in this scope
+    pub array_lengths: DashMap<Path, usize>,
+    // Constructor each constructor call resolved to, filled in by the struct checker
+    pub constructor_calls: DashMap<AstId, AstId>,
 }
 
-impl SymbolTable<'_> {
-    pub fn new() -> Self {        
+impl<'ast> SymbolTable<'ast> {
+    pub fn new(path: &'ast [String]) -> Self {
         Self {
+            path,
             types: DashMap::new(),
             function_names: DashSet::new(),
-            functions: DashMap::new(),
             ast_types: DashMap::new(),
             declaration_types: DashMap::new(),
             variables: DashMap::new(),
+            captures: DashMap::new(),
+            globals: DashMap::new(),
+            items: DashMap::new(),
+            public_items: DashSet::new(),
+            imports: DashMap::new(),
+            scope_imports: DashMap::new(),
+            used_requires: DashSet::new(),
+            constants: DashMap::new(),
+            structs: DashMap::new(),
+            functions: DashMap::new(),
+            const_values: DashMap::new(),
+            array_lengths: DashMap::new(),
+            constructor_calls: DashMap::new(),
         }
     }
-
-    pub fn resolve_type(&self, type_arena: &TypeArena, parsed_type: &ParsedType) -> Option<TypeId> {
-        Some(match parsed_type.parsed_type {
-            ParsedTypeEnum::Void =>type_arena.void(),
-            ParsedTypeEnum::Boolean => type_arena.bool(),
-            ParsedTypeEnum::Char => type_arena.char(),
-            ParsedTypeEnum::Integer => type_arena.int(),
-            ParsedTypeEnum::Double => type_arena.double(),
-            ParsedTypeEnum::Array(ref array_type) => {
-                let array_type_id = self.resolve_type(type_arena, array_type)?;
-                type_arena.make_array(array_type_id)
-            },
-            ParsedTypeEnum::Pointer(ref ptr_type) => {
-                let ptr_type_id = self.resolve_type(type_arena, ptr_type)?;
-                type_arena.make_ptr(ptr_type_id)
-            },
-            ParsedTypeEnum::Reference(ref ref_type) => {
-                let ref_type_id = self.resolve_type(type_arena, ref_type)?;
-                type_arena.make_ref(ref_type_id)
-            },
-            ParsedTypeEnum::TypeName(ref type_name) => {
-                *self.types.get(type_name)?
-            }
-        })
-    }
 }
 
 pub struct TypeArena {
@@ -83,13 +675,16 @@ pub struct TypeArena {
     ref_ids: DashMap<TypeId, TypeId>,
     ptr_ids: DashMap<TypeId, TypeId>,
     array_ids: DashMap<TypeId, TypeId>,
+    fixed_array_ids: DashMap<(TypeId, usize), TypeId>,
     function_ids: DashMap<FunctionType, TypeId>,
+    numeric_ids: HashMap<NumericType, TypeId>,
 
     int_type_id: TypeId,
     double_type_id: TypeId,
     bool_type_id: TypeId,
     char_type_id: TypeId,
     void_type_id: TypeId,
+    str_type_id: TypeId,
 }
 
 impl TypeArena {
@@ -101,79 +696,87 @@ impl TypeArena {
         types.push(Some(ResolvedType::Boolean));
         types.push(Some(ResolvedType::Char));
         types.push(Some(ResolvedType::Void));
+        types.push(Some(ResolvedType::Str));
+
+        // int and double are the i32 and f64 types under their original names
+        let mut numeric_ids = HashMap::new();
+        numeric_ids.insert(NumericType::I32, TypeId(0));
+        numeric_ids.insert(NumericType::F64, TypeId(1));
+
+        for numeric_type in [
+            NumericType::I8, NumericType::I16, NumericType::I64,
+            NumericType::U8, NumericType::U16, NumericType::U32, NumericType::U64,
+            NumericType::F32,
+        ] {
+            numeric_ids.insert(numeric_type, TypeId(types.len()));
+            types.push(Some(ResolvedType::Numeric(numeric_type)));
+        }
 
         Self {
             types: RwLock::new(types),
             ref_ids: DashMap::new(),
             ptr_ids: DashMap::new(),
             array_ids: DashMap::new(),
+            fixed_array_ids: DashMap::new(),
             function_ids: DashMap::new(),
+            numeric_ids,
             int_type_id: TypeId(0),
             double_type_id: TypeId(1),
             bool_type_id: TypeId(2),
             char_type_id: TypeId(3),
             void_type_id: TypeId(4),
+            str_type_id: TypeId(5),
         }
     }
 
     pub fn int(&self) -> TypeId {
-        return self.int_type_id;
+        self.int_type_id
     }
 
     pub fn double(&self) -> TypeId {
-        return self.double_type_id;
+        self.double_type_id
     }
 
     pub fn bool(&self) -> TypeId {
-        return self.bool_type_id;
+        self.bool_type_id
     }
 
     pub fn char(&self) -> TypeId {
-        return self.char_type_id;
+        self.char_type_id
     }
 
     pub fn void(&self) -> TypeId {
-        return self.void_type_id;
+        self.void_type_id
     }
 
-    pub fn make_ref(&self, type_id: TypeId) -> TypeId {
-        if let Some(ref_id) = self.ref_ids.get(&type_id) {
-            return *ref_id;
-        }
+    pub fn str(&self) -> TypeId {
+        self.str_type_id
+    }
 
-        let ref_id = self.insert(ResolvedType::Reference(type_id));
-        self.ref_ids.insert(type_id, ref_id);
-        ref_id
+    pub fn numeric(&self, numeric_type: NumericType) -> TypeId {
+        self.numeric_ids[&numeric_type]
     }
 
-    pub fn make_ptr(&self, type_id: TypeId) -> TypeId {
-        if let Some(ptr_id) = self.ptr_ids.get(&type_id) {
-            return *ptr_id;
-        }
+    // Scopes are checked in parallel, so a derived type is looked up and inserted under the
+    // same map entry lock. Otherwise two scopes could intern the same type under different ids.
+    pub fn make_ref(&self, type_id: TypeId) -> TypeId {
+        *self.ref_ids.entry(type_id).or_insert_with(|| self.insert(ResolvedType::Reference(type_id)))
+    }
 
-        let ptr_id = self.insert(ResolvedType::Pointer(type_id));
-        self.ptr_ids.insert(type_id, ptr_id);
-        ptr_id
+    pub fn make_ptr(&self, type_id: TypeId) -> TypeId {
+        *self.ptr_ids.entry(type_id).or_insert_with(|| self.insert(ResolvedType::Pointer(type_id)))
     }
 
     pub fn make_array(&self, type_id: TypeId) -> TypeId {
-        if let Some(array_id) = self.array_ids.get(&type_id) {
-            return *array_id;
-        }
+        *self.array_ids.entry(type_id).or_insert_with(|| self.insert(ResolvedType::Array(type_id)))
+    }
 
-        let array_id = self.insert(ResolvedType::Array(type_id));
-        self.array_ids.insert(type_id, array_id);
-        array_id
+    pub fn make_fixed_array(&self, type_id: TypeId, length: usize) -> TypeId {
+        *self.fixed_array_ids.entry((type_id, length)).or_insert_with(|| self.insert(ResolvedType::FixedArray(type_id, length)))
     }
 
     pub fn make_function(&self, function_type: FunctionType) -> TypeId {
-        if let Some(function_id) = self.function_ids.get(&function_type) {
-            return *function_id;
-        }
-
-        let function_id = self.insert(ResolvedType::Function(function_type.clone()));
-        self.function_ids.insert(function_type, function_id);
-        function_id
+        *self.function_ids.entry(function_type.clone()).or_insert_with(|| self.insert(ResolvedType::Function(function_type)))
     }
 
     pub fn reserve(&self) -> TypeId {
@@ -200,18 +803,57 @@ impl TypeArena {
             types[type_id.0].as_ref().unwrap()
         })
     }
+
+    // The type as it is written in source, for diagnostics
+    pub fn type_name(&self, type_id: TypeId) -> String {
+        // Cloned so that the lock is released before naming the types it refers to
+        let resolved_type = self.get(type_id).clone();
+
+        match resolved_type {
+            ResolvedType::Integer => numeric_type_name(NumericType::I32),
+            ResolvedType::Double => numeric_type_name(NumericType::F64),
+            ResolvedType::Boolean => "bool".to_string(),
+            ResolvedType::Char => "char".to_string(),
+            ResolvedType::Void => "void".to_string(),
+            ResolvedType::Str => "str".to_string(),
+            ResolvedType::Numeric(numeric_type) => numeric_type_name(numeric_type),
+            ResolvedType::Struct(struct_type) => struct_type.name,
+            ResolvedType::Pointer(inner) => format!("{}*", self.type_name(inner)),
+            ResolvedType::Reference(inner) => format!("{}&", self.type_name(inner)),
+            ResolvedType::Array(inner) => format!("{}[]", self.type_name(inner)),
+            ResolvedType::FixedArray(inner, length) => format!("{}[{}]", self.type_name(inner), length),
+            ResolvedType::Function(function_type) => {
+                let param_types = function_type.param_types.iter()
+                    .map(|param_type| self.type_name(*param_type))
+                    .collect::<Vec<_>>();
+
+                if function_type.return_type == self.void() {
+                    format!("fn({})", param_types.join(", "))
+                } else {
+                    format!("fn({}) -> {}", param_types.join(", "), self.type_name(function_type.return_type))
+                }
+            },
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct TypeId(usize);
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub enum ResolvedType {
     Integer, Double, Boolean, Char, Void,
+    // Length-carrying string slice: { char*, int }
+    Str,
+    // Sized integer and float types other than int (i32) and double (f64)
+    Numeric(NumericType),
     Struct(StructType),
     Pointer(TypeId),
     Reference(TypeId),
+    // Slice carrying its length: { T*, int }
     Array(TypeId),
+    // Stack-allocated array with a length known at compile time
+    FixedArray(TypeId, usize),
     Function(FunctionType),
 }
 
@@ -223,10 +865,13 @@ impl PartialEq for ResolvedType {
             (ResolvedType::Boolean, ResolvedType::Boolean) => true,
             (ResolvedType::Char, ResolvedType::Char) => true,
             (ResolvedType::Void, ResolvedType::Void) => true,
+            (ResolvedType::Str, ResolvedType::Str) => true,
+            (ResolvedType::Numeric(a), ResolvedType::Numeric(b)) => a == b,
             (ResolvedType::Struct(a), ResolvedType::Struct(b)) => a == b,
             (ResolvedType::Pointer(a), ResolvedType::Pointer(b)) => a == b,
             (ResolvedType::Reference(a), ResolvedType::Reference(b)) => a == b,
             (ResolvedType::Array(a), ResolvedType::Array(b)) => a == b,
+            (ResolvedType::FixedArray(a, a_len), ResolvedType::FixedArray(b, b_len)) => a == b && a_len == b_len,
             (ResolvedType::Function(a), ResolvedType::Function(b)) => a == b,
             _ => false,
         }
@@ -235,10 +880,59 @@ impl PartialEq for ResolvedType {
 
 impl Eq for ResolvedType {}
 
+impl ResolvedType {
+    pub fn numeric_type(&self) -> Option<NumericType> {
+        match self {
+            ResolvedType::Integer => Some(NumericType::I32),
+            ResolvedType::Double => Some(NumericType::F64),
+            ResolvedType::Numeric(numeric_type) => Some(*numeric_type),
+            _ => None,
+        }
+    }
+}
+
+// How a value is converted by an `as` cast between two numeric types
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NumericCast {
+    Identity,
+    Truncate,
+    SignExtend,
+    ZeroExtend,
+    FloatTruncate,
+    FloatExtend,
+    SignedToFloat,
+    UnsignedToFloat,
+    FloatToSigned,
+    FloatToUnsigned,
+}
+
+impl NumericCast {
+    // Integer widening extends according to the signedness of the source, so
+    // `-1i8 as u32` is 0xFFFFFFFF and `255u8 as i32` is 255. Narrowing always truncates.
+    pub fn between(from: NumericType, to: NumericType) -> NumericCast {
+        match (from.is_float(), to.is_float()) {
+            (false, false) if from.bit_width() > to.bit_width() => NumericCast::Truncate,
+            (false, false) if from.bit_width() == to.bit_width() => NumericCast::Identity,
+            (false, false) if from.is_signed() => NumericCast::SignExtend,
+            (false, false) => NumericCast::ZeroExtend,
+            (true, true) if from.bit_width() > to.bit_width() => NumericCast::FloatTruncate,
+            (true, true) if from.bit_width() < to.bit_width() => NumericCast::FloatExtend,
+            (true, true) => NumericCast::Identity,
+            (false, true) if from.is_signed() => NumericCast::SignedToFloat,
+            (false, true) => NumericCast::UnsignedToFloat,
+            (true, false) if to.is_signed() => NumericCast::FloatToSigned,
+            (true, false) => NumericCast::FloatToUnsigned,
+        }
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct StructType {
     pub name: String,
+    // Path of the scope declaring the struct, which its private members are visible in
+    pub scope: Vec<String>,
     pub members: HashMap<String, TypeId>,
+    pub private_members: HashSet<String>,
     pub constructors: HashSet<TypeId>,
 }
 
@@ -247,4 +941,276 @@ pub struct StructType {
 pub struct FunctionType {
     pub param_types: Vec<TypeId>,
     pub return_type: TypeId,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::test_util::{messages, parse_scopes};
+
+    #[test]
+    fn test_integer_widening_follows_source_signedness() {
+        assert_eq!(NumericCast::between(NumericType::I8, NumericType::U32), NumericCast::SignExtend);
+        assert_eq!(NumericCast::between(NumericType::U8, NumericType::I32), NumericCast::ZeroExtend);
+        assert_eq!(NumericCast::between(NumericType::U16, NumericType::U64), NumericCast::ZeroExtend);
+    }
+
+    #[test]
+    fn test_integer_narrowing_truncates() {
+        assert_eq!(NumericCast::between(NumericType::I64, NumericType::U8), NumericCast::Truncate);
+        assert_eq!(NumericCast::between(NumericType::U32, NumericType::I16), NumericCast::Truncate);
+    }
+
+    #[test]
+    fn test_same_width_integer_cast_is_identity() {
+        assert_eq!(NumericCast::between(NumericType::I32, NumericType::U32), NumericCast::Identity);
+        assert_eq!(NumericCast::between(NumericType::U64, NumericType::U64), NumericCast::Identity);
+    }
+
+    #[test]
+    fn test_float_casts() {
+        assert_eq!(NumericCast::between(NumericType::F32, NumericType::F64), NumericCast::FloatExtend);
+        assert_eq!(NumericCast::between(NumericType::F64, NumericType::F32), NumericCast::FloatTruncate);
+        assert_eq!(NumericCast::between(NumericType::I16, NumericType::F32), NumericCast::SignedToFloat);
+        assert_eq!(NumericCast::between(NumericType::U32, NumericType::F64), NumericCast::UnsignedToFloat);
+        assert_eq!(NumericCast::between(NumericType::F64, NumericType::I8), NumericCast::FloatToSigned);
+        assert_eq!(NumericCast::between(NumericType::F32, NumericType::U16), NumericCast::FloatToUnsigned);
+    }
+
+    #[test]
+    fn test_int_and_double_share_sized_type_ids() {
+        let type_arena = TypeArena::new();
+
+        assert_eq!(type_arena.numeric(NumericType::I32), type_arena.int());
+        assert_eq!(type_arena.numeric(NumericType::F64), type_arena.double());
+        assert_ne!(type_arena.numeric(NumericType::U32), type_arena.int());
+    }
+
+    fn global_id(scope: &Scope, name: &str) -> AstId {
+        scope.items.iter()
+            .find_map(|item| match item {
+                ASTEnum::Global(global) if *global.name == name => Some(global.get_id()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_globals_registered_per_scope() {
+        let scopes = parse_scopes("scope Main { scope Math { const PI: double = 3.14; static count: int = 0; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        let math = compiler.symbol_tables.get(scopes[0].path.segments.as_slice()).unwrap();
+        let pi = global_id(&scopes[0], "PI");
+        let count = global_id(&scopes[0], "count");
+
+        assert_eq!(*math.globals.get("PI").unwrap(), pi);
+        assert_eq!(*math.globals.get("count").unwrap(), count);
+        assert!(math.constants.contains_key(&pi));
+        assert!(!math.constants.contains_key(&count));
+        assert!(compiler.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_global() {
+        let scopes = parse_scopes("scope Main { const A: int = 1; static A: int = 2; }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert_eq!(compiler.diagnostics.len(), 1);
+        assert_eq!(compiler.diagnostics[0].message, "duplicate variable declaration: 'A'");
+    }
+
+    #[test]
+    fn test_globals_resolve_through_paths() {
+        use crate::compiler::{local_resolver::LocalResolver, visit::ASTVisitor};
+
+        let scopes = parse_scopes("scope Main { scope Math { const PI: double = 3.14; } const TAU: double = Main::Math::PI * 2.0; fn f() -> double { return TAU; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let main = compiler.symbol_tables.get(scopes[1].path.segments.as_slice()).unwrap();
+
+        LocalResolver::new(&crate::logger::CONSOLE_LOGGER, &compiler, main).visit_scope(&scopes[1]);
+
+        let resolved = main.variables.iter().map(|entry| *entry.value()).collect::<Vec<_>>();
+
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.contains(&global_id(&scopes[0], "PI")));
+        assert!(resolved.contains(&global_id(&scopes[1], "TAU")));
+    }
+
+    #[test]
+    fn test_locals_shadow_globals() {
+        use crate::compiler::{local_resolver::LocalResolver, visit::ASTVisitor};
+
+        let scopes = parse_scopes("scope Main { const X: int = 1; fn f() -> int { let int X = 2; return X; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let main = compiler.symbol_tables.get(scopes[0].path.segments.as_slice()).unwrap();
+
+        LocalResolver::new(&crate::logger::CONSOLE_LOGGER, &compiler, main).visit_scope(&scopes[0]);
+
+        let resolved = main.variables.iter().map(|entry| *entry.value()).collect::<Vec<_>>();
+
+        assert_eq!(resolved.len(), 1);
+        assert_ne!(resolved[0], global_id(&scopes[0], "X"));
+    }
+
+    #[test]
+    fn test_constructor_binds_self() {
+        let scopes = parse_scopes("scope Main { struct Foo { int a; $(int a) { self.a = a; } } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        let constructor_id = match &scopes[0].items[0] {
+            ASTEnum::Struct(struct_item) => struct_item.constructors[0].get_id(),
+            _ => unreachable!(),
+        };
+
+        let resolved = resolve_scope(&compiler, &scopes[0]);
+
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.contains(&constructor_id));
+    }
+
+    #[test]
+    fn test_destructor_binds_self() {
+        let scopes = parse_scopes("scope Main { struct Foo { int *a; ~ { delete self.a; } } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        let destructor_id = match &scopes[0].items[0] {
+            ASTEnum::Struct(struct_item) => struct_item.destructor.as_ref().unwrap().get_id(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(resolve_scope(&compiler, &scopes[0]), vec![destructor_id]);
+    }
+
+    #[test]
+    fn test_entry_attributes() {
+        let scopes = parse_scopes("scope Main { #[entry] #[section(\".text.start\")] fn start() { } #[section(\".isr_vector\")] static VECTORS: int = 0; }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert!(compiler.diagnostics.is_empty());
+
+        match (&scopes[0].items[0], &scopes[0].items[1]) {
+            (ASTEnum::Function(function), ASTEnum::Global(global)) => {
+                assert!(is_entry(&function.attributes));
+                assert_eq!(section(&function.attributes), Some(".text.start"));
+                assert_eq!(section(&global.attributes), Some(".isr_vector"));
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_invalid_entry_attributes() {
+        let scopes = parse_scopes("scope Main { #[entry] fn start() { } #[entry] fn reset(int code) { } #[entry] static X: int = 0; #[section(\".text\")] extern fn putchar(char c); }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert_eq!(messages(&compiler.diagnostics), vec![
+            "'reset' is marked as entry, but the program already has an entry function",
+            "entry function 'reset' cannot take parameters",
+            "attribute 'entry' is not allowed here",
+            "attribute 'section' is not allowed here",
+        ]);
+    }
+
+    #[test]
+    fn test_runtime_names_are_reserved() {
+        let code = "scope Main { fn free(int* p) { } extern fn malloc(int size) -> int*; static dprintf: int = 0; }";
+        let scopes = parse_scopes(code);
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert_eq!(messages(&compiler.diagnostics), vec![
+            "'free' is the name of a runtime function and cannot be declared",
+            "'malloc' is the name of a runtime function and cannot be declared",
+            "'dprintf' is the name of a runtime function and cannot be declared",
+        ]);
+
+        // A freestanding build with the bump allocator calls none of them
+        let options = CompilerOptions { freestanding: true, allocator: Allocator::Bump, ..CompilerOptions::default() };
+        let compiler = Compiler::new(scopes.iter(), options);
+
+        assert!(compiler.diagnostics.is_empty());
+    }
+
+    fn resolve_scope<'ast>(compiler: &Compiler<'ast>, scope: &'ast Scope) -> Vec<AstId> {
+        use crate::compiler::{local_resolver::LocalResolver, visit::ASTVisitor};
+
+        let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+        LocalResolver::new(&crate::logger::CONSOLE_LOGGER, compiler, symbol_table).visit_scope(scope);
+
+        symbol_table.variables.iter().map(|entry| *entry.value()).collect()
+    }
+
+    #[test]
+    fn test_glob_require_imports_public_items() {
+        let scopes = parse_scopes("scope Main { from Main::CharUtil require *; const A: int = UPPER + 1; scope CharUtil { pub const UPPER: int = 65; const HIDDEN: int = 1; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let main = compiler.symbol_tables.get(scopes[1].path.segments.as_slice()).unwrap();
+
+        assert!(compiler.diagnostics.is_empty());
+        assert!(main.imports.contains_key("UPPER"));
+        assert!(!main.imports.contains_key("HIDDEN"));
+        assert_eq!(resolve_scope(&compiler, &scopes[1]), vec![global_id(&scopes[0], "UPPER")]);
+        assert!(compiler.unused_requires(&scopes[1], main).is_empty());
+    }
+
+    #[test]
+    fn test_grouped_requires_with_alias() {
+        let scopes = parse_scopes("scope Main { from Main::Lib require { X, Y as Z }; fn f() -> int { return X + Z; } scope Lib { pub const X: int = 1; pub const Y: int = 2; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let resolved = resolve_scope(&compiler, &scopes[1]);
+
+        assert!(compiler.diagnostics.is_empty());
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.contains(&global_id(&scopes[0], "X")));
+        assert!(resolved.contains(&global_id(&scopes[0], "Y")));
+    }
+
+    #[test]
+    fn test_explicit_require_takes_precedence_over_glob() {
+        let scopes = parse_scopes("scope Main { from Main::A require *; require Main::B::X; fn f() -> int { return X; } scope A { pub const X: int = 1; } scope B { pub const X: int = 2; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert!(compiler.diagnostics.is_empty());
+        assert_eq!(resolve_scope(&compiler, &scopes[2]), vec![global_id(&scopes[1], "X")]);
+    }
+
+    #[test]
+    fn test_conflicting_imports() {
+        let scopes = parse_scopes("scope Main { require Main::A::X, Main::B::X; require Main::A::Y; from Main::A require *; from Main::B require *; const Y: int = 0; scope A { pub const X: int = 1; pub const Y: int = 1; pub const Z: int = 1; } scope B { pub const X: int = 2; pub const Z: int = 2; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert_eq!(messages(&compiler.diagnostics), vec![
+            "import of 'X' conflicts with another item of the same name",
+            "import of 'Y' conflicts with another item of the same name",
+            "import of 'Z' conflicts with another item of the same name",
+        ]);
+    }
+
+    #[test]
+    fn test_unresolved_imports() {
+        let scopes = parse_scopes("scope Main { require Main::Lib::MISSING; from Main::Nowhere require *; scope Lib {} }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+
+        assert_eq!(messages(&compiler.diagnostics), vec![
+            "unresolved import: 'Main::Lib::MISSING'",
+            "unresolved import: 'Main::Nowhere'",
+        ]);
+    }
+
+    #[test]
+    fn test_unused_requires() {
+        let scopes = parse_scopes("scope Main { require Main::Lib; from Main::Lib require Y; from Main::Other require *; require Main::Full; fn f() -> int { return Lib::X + Main::Full::Z; } scope Lib { pub const X: int = 1; pub const Y: int = 2; } scope Other { pub const W: int = 3; } scope Full { pub const Z: int = 4; } }");
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let main = compiler.symbol_tables.get(scopes[3].path.segments.as_slice()).unwrap();
+
+        let resolved = resolve_scope(&compiler, &scopes[3]);
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.contains(&global_id(&scopes[0], "X")) && resolved.contains(&global_id(&scopes[2], "Z")));
+
+        // The full path `Main::Full::Z` still goes through the scope `require Main::Full` loads
+        let unused = compiler.unused_requires(&scopes[3], main);
+
+        assert_eq!(messages(&unused), vec!["unused import: 'Main::Lib::Y'", "unused import: 'Main::Other'"]);
+        assert!(unused.iter().all(|diagnostic| matches!(diagnostic.severity, crate::diagnostic::DiagnosticSeverity::Warning)));
+    }
+}
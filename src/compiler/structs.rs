@@ -0,0 +1,543 @@
+use std::collections::HashSet;
+
+use crate::{ast::*, compiler::{Compiler, ResolvedType, SymbolTable, TypeId, visit::{ASTVisitor, AcceptsASTVisitor, LocalResolved, PhaseWitness, VisitResult}}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::{Located, Positioned}};
+
+impl PhaseWitness<LocalResolved> for StructWitness {}
+
+pub type StructResult = VisitResult<LocalResolved, StructWitness>;
+
+pub struct StructWitness {
+    _private: (),
+}
+
+// Checks struct literals and constructor calls against the struct they build.
+// Every member has to be given exactly once unless it declares a default value,
+// and a constructor call picks the constructor whose parameters have the types of
+// its arguments. Runs after type checking, which gives the arguments their types.
+// A struct with a destructor is also checked to only be moved out of a place that
+// gives up its value, so that the destructor never runs twice on the same value.
+pub struct StructChecker<'ctx, 'ast> {
+    compiler: &'ctx Compiler<'ast>,
+    symbol_table: &'ctx SymbolTable<'ast>,
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+    // Variables declared in the body being checked, set aside while a lambda body is checked
+    locals: HashSet<AstId>,
+}
+
+impl<'ctx, 'ast> StructChecker<'ctx, 'ast> {
+    pub fn new(compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>, diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            compiler,
+            symbol_table,
+            diagnostics,
+            locals: HashSet::new(),
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<LocalResolved>) -> StructResult {
+        VisitResult::new(node.get_id(), StructWitness { _private: () })
+    }
+
+    // Returns the struct along with the symbol table of the scope declaring it
+    fn resolve_struct(&self, type_name: &Located<String>) -> Option<(&'ctx SymbolTable<'ast>, &'ast StructItem)> {
+        let path = Path::new(vec![type_name.clone()]);
+        let (owner, decl_id) = self.compiler.resolve_item(self.symbol_table, &path)?;
+
+        owner.structs.get(&decl_id).map(|struct_item| (owner, *struct_item))
+    }
+
+    fn check_fields(&mut self, node: &StructLiteral<LocalResolved>) {
+        let Some((owner, struct_item)) = self.resolve_struct(&node.type_name) else {
+            self.diagnostics.push(ErrMsg::UnknownTypeName(node.type_name.to_string()).make_diagnostic(*node.type_name.get_position()));
+            return;
+        };
+
+        let mut initialized = HashSet::new();
+
+        for (field_name, _) in &node.fields {
+            let Some((visibility, _, _, _)) = struct_item.members.iter().find(|(_, _, member_name, _)| **member_name == **field_name) else {
+                self.diagnostics.push(ErrMsg::FieldNotFound(field_name.to_string()).make_diagnostic(*field_name.get_position()));
+                continue;
+            };
+
+            if !initialized.insert(&**field_name) {
+                self.diagnostics.push(ErrMsg::DuplicateField(field_name.to_string()).make_diagnostic(*field_name.get_position()));
+            } else if !self.compiler.is_member_visible(self.symbol_table, owner, *visibility) {
+                self.diagnostics.push(ErrMsg::PrivateField(field_name.to_string(), struct_item.name.to_string()).make_diagnostic(*field_name.get_position()));
+            }
+        }
+
+        for (_, _, member_name, default_value) in &struct_item.members {
+            if default_value.is_none() && !initialized.contains(&**member_name) {
+                self.diagnostics.push(ErrMsg::MissingField(member_name.to_string(), struct_item.name.to_string()).make_diagnostic(*node.get_position()));
+            }
+        }
+    }
+
+    fn has_destructor(&self, type_id: TypeId) -> bool {
+        let ResolvedType::Struct(struct_type) = self.compiler.type_arena.get(type_id).clone() else {
+            return false;
+        };
+
+        self.compiler.symbol_tables.get(struct_type.scope.as_slice())
+            .and_then(|owner| owner.items.get(&struct_type.name).and_then(|decl_id| owner.structs.get(&*decl_id).map(|struct_item| struct_item.destructor.is_some())))
+            .unwrap_or(false)
+    }
+
+    // Going into a variable, member, element or exit value moves a value. A temporary has no other owner and a
+    // local variable gives its value up, but the destructor of any other place would run on the value again.
+    fn check_move(&mut self, expr: &ASTEnum<LocalResolved>) {
+        let Some(type_id) = self.symbol_table.ast_types.get(&expr.get_id()).map(|type_id| *type_id) else {
+            return;
+        };
+
+        let is_local = match expr {
+            ASTEnum::Var(var) => self.symbol_table.variables.get(&var.get_id()).is_some_and(|decl_id| self.locals.contains(&*decl_id)),
+            _ => false,
+        };
+
+        if !expr.is_temporary() && !is_local && self.has_destructor(type_id) {
+            self.diagnostics.push(ErrMsg::MoveOutOfPlace(self.compiler.type_arena.type_name(type_id)).make_diagnostic(*expr.get_position()));
+        }
+    }
+
+    // Types a constructor takes its arguments as, where reference parameters take a place of the referenced type
+    fn parameter_types(&self, owner: &SymbolTable<'ast>, constructor: &ConstructorItem) -> Option<Vec<TypeId>> {
+        constructor.parameters.iter()
+            .map(|parameter| {
+                let type_id = *owner.declaration_types.get(&parameter.get_id())?;
+
+                match *self.compiler.type_arena.get(type_id) {
+                    ResolvedType::Reference(referenced_type_id) => Some(referenced_type_id),
+                    _ => Some(type_id),
+                }
+            })
+            .collect()
+    }
+
+    fn check_constructor_call(&mut self, node: &ConstructorCallExpr<LocalResolved>) {
+        let Some((owner, struct_item)) = self.resolve_struct(&node.type_name) else {
+            self.diagnostics.push(ErrMsg::UnknownTypeName(node.type_name.to_string()).make_diagnostic(*node.type_name.get_position()));
+            return;
+        };
+
+        // Arguments without a type have errors the type checker reported already
+        let Some(argument_types) = node.arguments.iter()
+            .map(|argument| self.symbol_table.ast_types.get(&argument.get_id()).map(|type_id| *type_id))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+
+        let constructor = struct_item.constructors.iter()
+            .find(|constructor| self.parameter_types(owner, constructor).is_some_and(|parameter_types| parameter_types == argument_types));
+
+        match constructor {
+            Some(constructor) => {
+                self.symbol_table.constructor_calls.insert(node.get_id(), constructor.get_id());
+            },
+            None => {
+                self.diagnostics.push(ErrMsg::ConstructorNotFound(struct_item.name.to_string()).make_diagnostic(*node.get_position()));
+            },
+        }
+    }
+}
+
+impl<'ctx, 'ast> StructChecker<'ctx, 'ast> {
+    // Parameters are borrowed from the caller, so only the variables declared in the body own their value
+    fn visit_body(&mut self, body: &'ast BlockExpr<LocalResolved>) {
+        let outer_locals = std::mem::take(&mut self.locals);
+        body.accept_visitor(self);
+        self.locals = outer_locals;
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, LocalResolved, StructWitness> for StructChecker<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+        node.index.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<LocalResolved>) -> StructResult {
+        for element in &node.elements {
+            element.accept_visitor(self);
+            self.check_move(element);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<LocalResolved>) -> StructResult {
+        node.assignee.accept_visitor(self);
+        node.expr.accept_visitor(self);
+        self.check_move(&node.expr);
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<LocalResolved>) -> StructResult {
+        node.left.accept_visitor(self);
+        node.right.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<LocalResolved>) -> StructResult {
+        for expr in &node.exprs {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<LocalResolved>) -> StructResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        self.visit_body(&node.body);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<LocalResolved>) -> StructResult {
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.check_constructor_call(node);
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<LocalResolved>) -> StructResult {
+        let is_reference = self.symbol_table.declaration_types.get(&node.get_id())
+            .is_some_and(|type_id| matches!(*self.compiler.type_arena.get(*type_id), ResolvedType::Reference(_)));
+
+        // A reference is bound to its initializer, which keeps its value
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+
+            if !is_reference {
+                self.check_move(expr);
+            }
+        }
+
+        self.locals.insert(node.get_id());
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<LocalResolved>) -> StructResult {
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+            self.check_move(expr);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<LocalResolved>) -> StructResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        if let Some(body) = &node.body {
+            self.visit_body(body);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<LocalResolved>) -> StructResult {
+        node.condition.accept_visitor(self);
+        node.success.accept_visitor(self);
+        self.check_move(&node.success);
+
+        if let Some(fail) = &node.fail {
+            fail.accept_visitor(self);
+            self.check_move(fail);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<LocalResolved>) -> StructResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<LocalResolved>) -> StructResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        self.visit_body(&node.body);
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<LocalResolved>) -> StructResult {
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<LocalResolved>) -> StructResult {
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        if let Some(condition) = &node.condition {
+            condition.accept_visitor(self);
+        }
+
+        if let Some(increment) = &node.increment {
+            increment.accept_visitor(self);
+        }
+
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<LocalResolved>) -> StructResult {
+        for size in &node.sizes {
+            size.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<LocalResolved>) -> StructResult {
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<LocalResolved>) -> StructResult {
+        for (_, _, _, default_value) in &node.members {
+            if let Some(default_value) = default_value {
+                default_value.accept_visitor(self);
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<LocalResolved>) -> StructResult {
+        self.check_fields(node);
+
+        for (_, value) in &node.fields {
+            value.accept_visitor(self);
+            self.check_move(value);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<LocalResolved>) -> StructResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<LocalResolved>) -> StructResult {
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilerOptions;
+    use crate::compiler::test_util::{parse_scopes, resolve_locals};
+    use crate::compiler::type_checker::TypeChecker;
+
+    // Only the diagnostics of the struct checker are returned, those of type checking are left out
+    fn check(code: &str) -> Vec<String> {
+        let scopes = parse_scopes(code);
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let mut type_diagnostics = compiler.register_types(scopes.iter());
+        let mut diagnostics = Vec::new();
+
+        for scope in &scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            let local_resolved = resolve_locals(&compiler, scope);
+
+            TypeChecker::new(&compiler, symbol_table, &mut type_diagnostics).visit_scope(local_resolved);
+            StructChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+        }
+
+        diagnostics.into_iter().map(|diagnostic| diagnostic.message).collect()
+    }
+
+    #[test]
+    fn test_all_fields_given() {
+        let messages = check("scope Main { struct Foo { char a; char b; } fn f() { let Foo foo = Foo { b: 'b', a: 'a' }; } }");
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_default_values_fill_omitted_fields() {
+        let messages = check("scope Main { struct Config { int retries = 3; bool verbose = false; char mode; } fn f() { let Config config = Config { mode: 'r' }; } }");
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let messages = check("scope Main { struct Foo { char a; char b; int c = 0; } fn f() { let Foo foo = Foo { a: 'a' }; } }");
+
+        assert_eq!(messages, vec!["missing field 'b' in literal of struct 'Foo'"]);
+    }
+
+    #[test]
+    fn test_unknown_and_duplicate_fields() {
+        let messages = check("scope Main { struct Foo { char a; } fn f() { let Foo foo = Foo { a: 'a', z: 1, a: 'b' }; } }");
+
+        assert_eq!(messages, vec!["field 'z' not found in struct", "field 'a' is initialized more than once"]);
+    }
+
+    #[test]
+    fn test_unknown_struct() {
+        let messages = check("scope Main { fn helper() {} fn f() { let int x = Bar { a: 1 }; let int y = helper { a: 1 }; } }");
+
+        assert_eq!(messages, vec!["unknown type name: 'Bar'", "unknown type name: 'helper'"]);
+    }
+
+    #[test]
+    fn test_nested_and_required_literals() {
+        let messages = check("scope Main { from Main::Geo require Point; struct Line { Point start; Point end; } fn f() { let Line line = Line { start: Point { x: 1 }, end: Point { y: 2 } }; } scope Geo { pub struct Point { pub int x; pub int y = 0; } } }");
+
+        assert_eq!(messages, vec!["missing field 'x' in literal of struct 'Point'"]);
+    }
+
+    #[test]
+    fn test_private_fields_outside_their_scope() {
+        let messages = check("scope Main { from Main::Geo require Point; fn f() { let Point p = Point { x: 1, y: 2 }; } scope Geo { pub struct Point { pub int x; int y; } fn origin() -> Point { return Point { x: 0, y: 0 }; } } }");
+
+        assert_eq!(messages, vec!["field 'y' of struct 'Point' is private to its scope"]);
+    }
+
+    #[test]
+    fn test_constructor_calls() {
+        let messages = check("scope Main { struct Foo { int a; $(int a) { self.a = a; } $(int a, int b) { self.a = a + b; } } struct Bar { u8 b; $(u8 b) { self.b = b; } } fn f() { let Foo x = $Foo(1); let Foo *y = new $Foo(1, 2); let Bar z = $Bar(200); } }");
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_constructor_overloads_by_argument_type() {
+        let code = "scope Main { struct Foo { int a; $(int a) { self.a = a; } $(bool b) { self.a = 0; } $(char& c) { self.a = 1; } } fn f(char c) { let Foo x = $Foo(1); let Foo *y = new $Foo(true); let Foo z = $Foo(c); let Foo w = $Foo(1.5); } }";
+        let scopes = parse_scopes(code);
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let mut diagnostics = compiler.register_types(scopes.iter());
+
+        let symbol_table = compiler.symbol_tables.get(scopes[0].path.segments.as_slice()).unwrap();
+        let local_resolved = resolve_locals(&compiler, &scopes[0]);
+        TypeChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+        StructChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+
+        let messages = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, vec!["constructor not found for type 'Foo'"]);
+
+        // Each call is bound to the constructor taking its argument types
+        let ASTEnum::Struct(foo) = &scopes[0].items[0] else { panic!("Expected a struct") };
+        let ASTEnum::Function(f) = &scopes[0].items[1] else { panic!("Expected a function") };
+
+        let calls = f.body.as_ref().unwrap().exprs.iter()
+            .map(|expr| {
+                let ASTEnum::Declaration(declaration) = expr else { panic!("Expected a declaration") };
+                symbol_table.constructor_calls.get(&declaration.expr.as_ref().unwrap().get_id()).map(|decl_id| *decl_id)
+            })
+            .collect::<Vec<_>>();
+
+        let constructors = foo.constructors.iter().map(|constructor| Some(constructor.get_id())).collect::<Vec<_>>();
+        assert_eq!(calls, vec![constructors[0], constructors[1], constructors[2], None]);
+    }
+
+    #[test]
+    fn test_constructor_not_found() {
+        let messages = check("scope Main { struct Foo { int a; $(int a) { self.a = a; } } struct Bar { int b; } fn f() { let Foo x = $Foo(); let Bar y = $Bar(1); let Baz z = $Baz(); } }");
+
+        assert_eq!(messages, vec!["constructor not found for type 'Foo'", "constructor not found for type 'Bar'", "unknown type name: 'Baz'"]);
+    }
+
+    #[test]
+    fn test_moves_of_values_with_destructors() {
+        let messages = check("
+            scope Main {
+                struct Guard { char tag; ~ {} }
+                struct Holder { Guard guard; }
+
+                fn take(Guard param, Guard *ptr, Holder holder) -> Guard {
+                    let Guard local = Guard { tag: 'a' };
+                    let Guard moved = local;
+                    let Guard& borrowed = param;
+                    let Guard copied = param;
+                    let Guard pointee = *ptr;
+                    let Holder nested = Holder { guard: holder.guard };
+                    let fn() -> char capture = fn() -> char { let Guard inner = moved; return inner.tag; };
+
+                    return moved;
+                }
+            }
+        ");
+
+        assert_eq!(messages, vec![
+            "value of type 'Guard' has a destructor and can only be moved out of a local variable",
+            "value of type 'Guard' has a destructor and can only be moved out of a local variable",
+            "value of type 'Guard' has a destructor and can only be moved out of a local variable",
+            "value of type 'Guard' has a destructor and can only be moved out of a local variable",
+        ]);
+    }
+}
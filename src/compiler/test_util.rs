@@ -0,0 +1,40 @@
+use crate::ast::Scope;
+use crate::compiler::Compiler;
+use crate::compiler::local_resolver::LocalResolver;
+use crate::compiler::visit::{ASTVisitor, LocalResolved};
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Lexer;
+use crate::logger::CONSOLE_LOGGER;
+use crate::parser::ExprParser;
+
+// Lexes and parses a whole file, which every test expects to be free of syntax errors
+pub fn parse(code: &str) -> Scope {
+    let mut diagnostics = Vec::new();
+    let tokens = Lexer::new(&CONSOLE_LOGGER, code, &mut diagnostics).parse();
+    let scope = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics).parse();
+
+    assert!(diagnostics.is_empty(), "Expected code to parse without diagnostics");
+
+    scope.unwrap()
+}
+
+// Parses items as the contents of a scope named `test`
+pub fn parse_items(items: &str) -> Scope {
+    parse(&format!("scope test {{ {} }}", items))
+}
+
+pub fn parse_scopes(code: &str) -> Vec<Scope> {
+    parse(code).flatten()
+}
+
+// Runs the local resolver over a scope registered with the compiler
+pub fn resolve_locals<'ast>(compiler: &Compiler<'ast>, scope: &'ast Scope) -> &'ast Scope<LocalResolved> {
+    let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+    let resolve_confirmation = LocalResolver::new(&CONSOLE_LOGGER, compiler, symbol_table).visit_scope(scope);
+
+    resolve_confirmation.transmute_scope(scope)
+}
+
+pub fn messages(diagnostics: &[Diagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect()
+}
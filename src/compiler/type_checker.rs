@@ -0,0 +1,1100 @@
+use crate::{ast::*, compiler::{Compiler, FunctionType, ResolvedType, SymbolTable, TypeId, const_eval::{int_range, is_comparison, takes_context_type}, visit::{ASTVisitor, AcceptsASTVisitor, LocalResolved, PhaseWitness, VisitResult}}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::{Located, Positioned}};
+
+impl PhaseWitness<LocalResolved> for TypeWitness {}
+
+pub type TypeResult = VisitResult<LocalResolved, TypeWitness>;
+
+pub struct TypeWitness {
+    _private: (),
+}
+
+// A loop or block expression enclosing the node being checked, along with the
+// type of the values it exits with, which is known after the first one
+struct ExitFrame<'ast> {
+    label: Option<&'ast String>,
+    expected: Option<TypeId>,
+    value_type: Option<TypeId>,
+}
+
+// Gives every expression a type, recorded in `ast_types`, and checks that operators, calls,
+// assignments and returns are applied to values of the right types. The types of items were
+// registered by the compiler beforehand, local declarations get theirs here.
+pub struct TypeChecker<'ctx, 'ast> {
+    compiler: &'ctx Compiler<'ast>,
+    symbol_table: &'ctx SymbolTable<'ast>,
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+    // Type the context of the expression about to be visited expects, which unsuffixed literals take
+    expected: Option<TypeId>,
+    // Return type of the enclosing function, lambda or constructor, if its signature could be resolved
+    return_type: Option<TypeId>,
+    // Enclosing loops and block expressions, innermost last. Reset at function and lambda boundaries.
+    loops: Vec<ExitFrame<'ast>>,
+    blocks: Vec<ExitFrame<'ast>>,
+}
+
+impl<'ctx, 'ast> TypeChecker<'ctx, 'ast> {
+    pub fn new(compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>, diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            compiler,
+            symbol_table,
+            diagnostics,
+            expected: None,
+            return_type: None,
+            loops: Vec::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<LocalResolved>) -> TypeResult {
+        VisitResult::new(node.get_id(), TypeWitness { _private: () })
+    }
+
+    fn resolved_type(&self, type_id: TypeId) -> ResolvedType {
+        self.compiler.type_arena.get(type_id).clone()
+    }
+
+    fn type_name(&self, type_id: TypeId) -> String {
+        self.compiler.type_arena.type_name(type_id)
+    }
+
+    fn set_type(&self, node: &impl ASTNode<LocalResolved>, type_id: TypeId) {
+        self.symbol_table.ast_types.insert(node.get_id(), type_id);
+    }
+
+    // Visits an expression and returns its type, or nothing if it has an error that was already reported
+    fn check_expr(&mut self, expr: &'ast ASTEnum<LocalResolved>, expected: Option<TypeId>) -> Option<TypeId> {
+        self.expected = expected;
+        expr.accept_visitor(self);
+        self.expected = None;
+
+        self.symbol_table.ast_types.get(&expr.get_id()).map(|type_id| *type_id)
+    }
+
+    fn resolve_type(&mut self, parsed_type: &ParsedType) -> Option<TypeId> {
+        self.compiler.resolve_type(self.symbol_table, parsed_type, self.diagnostics)
+    }
+
+    // References are used as the value they refer to
+    fn dereferenced(&self, type_id: TypeId) -> TypeId {
+        match self.resolved_type(type_id) {
+            ResolvedType::Reference(referenced_type_id) => referenced_type_id,
+            _ => type_id,
+        }
+    }
+
+    fn is_integer(&self, type_id: TypeId) -> bool {
+        self.resolved_type(type_id).numeric_type().is_some_and(|numeric_type| !numeric_type.is_float())
+    }
+
+    // Visits `expr` where a value of type `expected` is needed, returning the type it has if it is another one
+    fn check_assignable(&mut self, expected: TypeId, expr: &'ast ASTEnum<LocalResolved>) -> Option<TypeId> {
+        self.check_expr(expr, Some(expected)).filter(|type_id| *type_id != expected)
+    }
+
+    // Visits an array index or size, which can be of any integer type. Returns the type it has if it is not one.
+    fn check_integer(&mut self, expr: &'ast ASTEnum<LocalResolved>) -> Option<TypeId> {
+        let int_type_id = self.compiler.type_arena.int();
+
+        self.check_expr(expr, Some(int_type_id)).filter(|type_id| !self.is_integer(*type_id))
+    }
+
+    fn check_condition(&mut self, condition: &'ast ASTEnum<LocalResolved>) {
+        let bool_type_id = self.compiler.type_arena.bool();
+
+        if let Some(condition_type_id) = self.check_assignable(bool_type_id, condition) {
+            self.diagnostics.push(ErrMsg::ConditionNotBoolean(self.type_name(condition_type_id)).make_diagnostic(*condition.get_position()));
+        }
+    }
+
+    // Function, lambda and constructor bodies return to their own caller, and
+    // loops and blocks outside of them cannot be exited from within
+    fn visit_body(&mut self, body: &'ast BlockExpr<LocalResolved>, return_type: Option<TypeId>) {
+        let outer_return_type = std::mem::replace(&mut self.return_type, return_type);
+        let loops = std::mem::take(&mut self.loops);
+        let blocks = std::mem::take(&mut self.blocks);
+
+        for expr in &body.exprs {
+            self.check_expr(expr, None);
+        }
+
+        self.return_type = outer_return_type;
+        self.loops = loops;
+        self.blocks = blocks;
+    }
+
+    // Checks the value a `break` or `result` exits with against the ones before it, the first one decides the type
+    // of the loop or block. Exits without a target were reported by the control-flow checker.
+    fn check_exit_value(&mut self, node: &'ast ExitExpr<LocalResolved>) {
+        let frames = match node.exit_type {
+            ExitType::Result => &self.blocks,
+            _ => &self.loops,
+        };
+
+        let target = match &node.label {
+            Some(label) => frames.iter().rposition(|frame| frame.label == Some(label.as_ref())),
+            None => frames.len().checked_sub(1),
+        };
+
+        let expected = target.and_then(|index| frames[index].value_type.or(frames[index].expected));
+        let value_type_id = match &node.expr {
+            Some(expr) => self.check_expr(expr, expected),
+            None => Some(self.compiler.type_arena.void()),
+        };
+
+        let (Some(index), Some(value_type_id)) = (target, value_type_id) else {
+            return;
+        };
+
+        let frame = match node.exit_type {
+            ExitType::Result => &mut self.blocks[index],
+            _ => &mut self.loops[index],
+        };
+
+        match frame.value_type {
+            None => frame.value_type = Some(value_type_id),
+            Some(frame_type_id) if frame_type_id != value_type_id => {
+                let position = node.expr.as_ref().map_or(*node.get_position(), |expr| *expr.get_position());
+                let (frame_type, value_type) = (self.type_name(frame_type_id), self.type_name(value_type_id));
+
+                let message = match node.exit_type {
+                    ExitType::Result => ErrMsg::MismatchedResultTypes(frame_type, value_type),
+                    _ => ErrMsg::MismatchedBreakTypes(frame_type, value_type),
+                };
+
+                self.diagnostics.push(message.make_diagnostic(position));
+            },
+            Some(_) => {},
+        }
+    }
+
+    // Unsuffixed integer literals have the integer type their context expects, and are ints otherwise.
+    // A negated literal may be one larger in magnitude than the maximum, as in `-128` for an i8.
+    fn check_literal(&mut self, node: &LiteralExpr<LocalResolved>, expected: Option<TypeId>, negated: bool) {
+        let type_arena = &self.compiler.type_arena;
+
+        let type_id = match node.value {
+            Literal::Int(value) => {
+                let numeric_type = expected
+                    .and_then(|type_id| self.resolved_type(type_id).numeric_type())
+                    .filter(|numeric_type| !numeric_type.is_float())
+                    .unwrap_or(crate::lexer::token::NumericType::I32);
+
+                let (min, max) = int_range(numeric_type);
+                let limit = if negated && numeric_type.is_signed() { -min } else { max };
+
+                if value as u64 as i128 > limit {
+                    self.diagnostics.push(ErrMsg::LiteralOutOfRange(value as u64, numeric_type).make_diagnostic(*node.get_position()));
+                }
+
+                type_arena.numeric(numeric_type)
+            },
+            Literal::Double(_) => type_arena.double(),
+            Literal::SizedInt(_, numeric_type) | Literal::SizedFloat(_, numeric_type) => type_arena.numeric(numeric_type),
+            Literal::Bool(_) => type_arena.bool(),
+            Literal::Char(_) => type_arena.char(),
+            Literal::String(_) => type_arena.str(),
+        };
+
+        self.set_type(node, type_id);
+    }
+
+    // The struct a literal or constructor call names, looked up like any other item
+    fn resolve_struct(&self, type_name: &Located<String>) -> Option<TypeId> {
+        let path = Path::new(vec![type_name.clone()]);
+        let (owner, decl_id) = self.compiler.resolve_item(self.symbol_table, &path)?;
+        let struct_item = owner.structs.get(&decl_id)?;
+
+        owner.types.get(&*struct_item.name).map(|type_id| *type_id)
+    }
+
+    fn member_type(&self, struct_type_id: TypeId, member_name: &str) -> Option<TypeId> {
+        match self.resolved_type(struct_type_id) {
+            ResolvedType::Struct(struct_type) => struct_type.members.get(member_name).copied(),
+            _ => None,
+        }
+    }
+
+    fn constructor_parameters(&self, struct_type_id: TypeId, arity: usize) -> Option<Vec<TypeId>> {
+        let ResolvedType::Struct(struct_type) = self.resolved_type(struct_type_id) else {
+            return None;
+        };
+
+        let mut candidates = struct_type.constructors.iter().filter_map(|&constructor_type_id| match self.resolved_type(constructor_type_id) {
+            ResolvedType::Function(function_type) if function_type.param_types.len() == arity => Some(function_type.param_types),
+            _ => None,
+        });
+
+        let parameter_types = candidates.next()?;
+        candidates.next().is_none().then_some(parameter_types)
+    }
+
+    // Private members can only be accessed from the scope declaring the struct and the scopes nested inside it
+    fn check_member_visible(&mut self, struct_type_id: TypeId, member_name: &Located<String>) {
+        let ResolvedType::Struct(struct_type) = self.resolved_type(struct_type_id) else {
+            return;
+        };
+
+        if !struct_type.private_members.contains(&**member_name) {
+            return;
+        }
+
+        let Some(owner) = self.compiler.symbol_tables.get(struct_type.scope.as_slice()) else {
+            return;
+        };
+
+        if !self.compiler.is_member_visible(self.symbol_table, owner, Visibility::Private) {
+            self.diagnostics.push(ErrMsg::PrivateField(member_name.to_string(), struct_type.name).make_diagnostic(*member_name.get_position()));
+        }
+    }
+
+    // A local, parameter or global, which are declared in this scope, or a global of another one
+    fn variable_type(&self, node: &VarExpr<LocalResolved>, decl_id: AstId) -> Option<TypeId> {
+        if let Some(type_id) = self.symbol_table.declaration_types.get(&decl_id) {
+            return Some(*type_id);
+        }
+
+        let (owner, _) = self.compiler.resolve_global(self.symbol_table, &node.path)?;
+        owner.declaration_types.get(&decl_id).map(|type_id| *type_id)
+    }
+
+    fn function_type(&self, node: &VarExpr<LocalResolved>) -> Option<Option<TypeId>> {
+        let (owner, decl_id) = self.compiler.resolve_item(self.symbol_table, &node.path)?;
+
+        if !owner.functions.contains_key(&decl_id) {
+            return None;
+        }
+
+        // A function whose signature names an unknown type has no type, which was reported already
+        Some(owner.declaration_types.get(&decl_id).map(|type_id| *type_id))
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, LocalResolved, TypeWitness> for TypeChecker<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<LocalResolved>) -> TypeResult {
+        let expr_type_id = self.check_expr(&node.expr, None);
+
+        if let Some(index_type_id) = self.check_integer(&node.index) {
+            self.diagnostics.push(ErrMsg::ArrayIndexNotInteger(self.type_name(index_type_id)).make_diagnostic(*node.index.get_position()));
+        }
+
+        let Some(expr_type_id) = expr_type_id else {
+            return self.witness(node);
+        };
+
+        match self.resolved_type(expr_type_id) {
+            ResolvedType::Array(element_type_id) | ResolvedType::FixedArray(element_type_id, _) | ResolvedType::Pointer(element_type_id) => {
+                self.set_type(node, element_type_id);
+            },
+            ResolvedType::Str => self.set_type(node, self.compiler.type_arena.char()),
+            _ => {
+                self.diagnostics.push(ErrMsg::IncompatibleMemberAccessType(self.type_name(expr_type_id)).make_diagnostic(*node.get_position()));
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<LocalResolved>) -> TypeResult {
+        // The elements of an array literal initializing an array take its element type
+        let expected = self.expected.take().and_then(|type_id| match self.resolved_type(type_id) {
+            ResolvedType::Array(element_type_id) | ResolvedType::FixedArray(element_type_id, _) => Some(element_type_id),
+            _ => None,
+        });
+
+        let Some((first, rest)) = node.elements.split_first() else {
+            self.diagnostics.push(ErrMsg::EmptyArrayLiteral.make_diagnostic(*node.get_position()));
+            return self.witness(node);
+        };
+
+        let element_type_id = self.check_expr(first, expected);
+        let mut valid = element_type_id.is_some();
+
+        for element in rest {
+            match (element_type_id, self.check_expr(element, element_type_id.or(expected))) {
+                (Some(element_type_id), Some(other_type_id)) if other_type_id != element_type_id => {
+                    self.diagnostics.push(ErrMsg::MismatchedArrayElements(self.type_name(element_type_id), self.type_name(other_type_id)).make_diagnostic(*element.get_position()));
+                    valid = false;
+                },
+                (_, None) => valid = false,
+                _ => {},
+            }
+        }
+
+        if let Some(element_type_id) = element_type_id && valid {
+            self.set_type(node, self.compiler.type_arena.make_fixed_array(element_type_id, node.elements.len()));
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<LocalResolved>) -> TypeResult {
+        let Some(assignee_type_id) = self.check_expr(&node.assignee, None) else {
+            self.check_expr(&node.expr, None);
+            return self.witness(node);
+        };
+
+        if let Some(expr_type_id) = self.check_assignable(assignee_type_id, &node.expr) {
+            self.diagnostics.push(ErrMsg::IncompatibleAssignment(self.type_name(assignee_type_id), self.type_name(expr_type_id)).make_diagnostic(*node.get_position()));
+        }
+
+        self.set_type(node, assignee_type_id);
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<LocalResolved>) -> TypeResult {
+        let operator = node.operator;
+        // Comparisons give a bool, so what their context expects says nothing about their operands
+        let expected = self.expected.take().filter(|_| !is_comparison(operator));
+
+        // Unsuffixed literals take the type of the operand next to them, like in constants
+        let (left, right) = match operator {
+            BinaryOperator::LeftShift | BinaryOperator::RightShift => {
+                (self.check_expr(&node.left, expected), self.check_expr(&node.right, None))
+            },
+            _ if takes_context_type(&node.left) && !takes_context_type(&node.right) => {
+                let right = self.check_expr(&node.right, expected);
+                (self.check_expr(&node.left, right), right)
+            },
+            _ => {
+                let left = self.check_expr(&node.left, expected);
+                (left, self.check_expr(&node.right, left.or(expected)))
+            },
+        };
+
+        let (Some(left), Some(right)) = (left, right) else {
+            return self.witness(node);
+        };
+
+        let left_type = self.resolved_type(left);
+        let bool_type_id = self.compiler.type_arena.bool();
+
+        let type_id = match operator {
+            BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Times | BinaryOperator::Divide if left == right => {
+                matches!(left_type, ResolvedType::Integer | ResolvedType::Double | ResolvedType::Numeric(_) | ResolvedType::Char).then_some(left)
+            },
+            BinaryOperator::Greater | BinaryOperator::GreaterEqual | BinaryOperator::Less | BinaryOperator::LessEqual if left == right => {
+                matches!(left_type, ResolvedType::Integer | ResolvedType::Double | ResolvedType::Numeric(_) | ResolvedType::Char).then_some(bool_type_id)
+            },
+            BinaryOperator::Equal | BinaryOperator::NotEqual if left == right => Some(bool_type_id),
+            BinaryOperator::And | BinaryOperator::Or if left == right => (left == bool_type_id).then_some(left),
+            BinaryOperator::BitwiseAnd | BinaryOperator::BitwiseOr | BinaryOperator::BitwiseXor | BinaryOperator::Modulo if left == right => {
+                self.is_integer(left).then_some(left)
+            },
+            // The shift amount may be any integer type, the result has the type of the shifted value
+            BinaryOperator::LeftShift | BinaryOperator::RightShift => {
+                (self.is_integer(left) && self.is_integer(right)).then_some(left)
+            },
+            _ => None,
+        };
+
+        match type_id {
+            Some(type_id) => self.set_type(node, type_id),
+            None => {
+                self.diagnostics.push(ErrMsg::IncompatibleBinaryTypes(self.type_name(left), self.type_name(right), operator).make_diagnostic(*node.get_position()));
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<LocalResolved>) -> TypeResult {
+        let expected = self.expected.take();
+        self.blocks.push(ExitFrame { label: None, expected, value_type: None });
+
+        for expr in &node.exprs {
+            self.check_expr(expr, None);
+        }
+
+        // A block has the type of the values it results in, and is void without any
+        let frame = self.blocks.pop().unwrap();
+        self.set_type(node, frame.value_type.unwrap_or_else(|| self.compiler.type_arena.void()));
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<LocalResolved>) -> TypeResult {
+        self.check_expr(&node.expr, None);
+
+        if let Some(type_id) = self.resolve_type(&node.cast_type) {
+            self.set_type(node, type_id);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<LocalResolved>) -> TypeResult {
+        self.visit_body(&node.body, Some(self.compiler.type_arena.void()));
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<LocalResolved>) -> TypeResult {
+        let struct_type_id = self.resolve_struct(&node.type_name);
+
+        // Literal arguments take the parameter types of the only constructor with that many parameters,
+        // the struct checker picks among overloads by the argument types
+        let parameter_types = struct_type_id.and_then(|struct_type_id| self.constructor_parameters(struct_type_id, node.arguments.len()));
+
+        for (index, argument) in node.arguments.iter().enumerate() {
+            let expected = parameter_types.as_ref().map(|parameter_types| self.dereferenced(parameter_types[index]));
+            self.check_expr(argument, expected);
+        }
+
+        // Unknown structs were reported by the struct checker
+        if let Some(struct_type_id) = struct_type_id {
+            // `new $Foo(...)` allocates on the heap and gives a pointer to the instance
+            let type_id = if node.is_heap {
+                self.compiler.type_arena.make_ptr(struct_type_id)
+            } else {
+                struct_type_id
+            };
+
+            self.set_type(node, type_id);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<LocalResolved>) -> TypeResult {
+        let declaration_type_id = self.resolve_type(&node.declaration_type);
+
+        if let Some(declaration_type_id) = declaration_type_id {
+            self.symbol_table.declaration_types.insert(node.get_id(), declaration_type_id);
+        }
+
+        if let Some(expr) = &node.expr {
+            match declaration_type_id {
+                // A reference is initialized with the place it is bound to
+                Some(declaration_type_id) => {
+                    let target_type_id = self.dereferenced(declaration_type_id);
+
+                    if let Some(init_type_id) = self.check_assignable(target_type_id, expr) {
+                        self.diagnostics.push(ErrMsg::IncompatibleAssignment(self.type_name(target_type_id), self.type_name(init_type_id)).make_diagnostic(*node.get_position()));
+                    }
+                },
+                None => {
+                    self.check_expr(expr, None);
+                },
+            }
+        }
+
+        self.set_type(node, self.compiler.type_arena.void());
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<LocalResolved>) -> TypeResult {
+        // Only what `new` handed out can be deleted, and `new` always gives a pointer
+        if let Some(expr_type_id) = self.check_expr(&node.expr, None)
+            && !matches!(self.resolved_type(expr_type_id), ResolvedType::Pointer(_))
+        {
+            self.diagnostics.push(ErrMsg::DeleteNonPointer(self.type_name(expr_type_id)).make_diagnostic(*node.expr.get_position()));
+        }
+
+        self.set_type(node, self.compiler.type_arena.void());
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<LocalResolved>) -> TypeResult {
+        let void_type_id = self.compiler.type_arena.void();
+
+        match (&node.exit_type, self.return_type) {
+            (ExitType::Return, Some(return_type_id)) => {
+                let expr_type_id = match &node.expr {
+                    Some(expr) => self.check_expr(expr, Some(return_type_id)),
+                    None => Some(void_type_id),
+                };
+
+                if let Some(expr_type_id) = expr_type_id && expr_type_id != return_type_id {
+                    let position = node.expr.as_ref().map_or(*node.get_position(), |expr| *expr.get_position());
+                    self.diagnostics.push(ErrMsg::MismatchedReturnType(self.type_name(return_type_id), self.type_name(expr_type_id)).make_diagnostic(position));
+                }
+            },
+            (ExitType::Return, None) | (ExitType::Continue, _) => {
+                if let Some(expr) = &node.expr {
+                    self.check_expr(expr, None);
+                }
+            },
+            (ExitType::Break | ExitType::Result, _) => self.check_exit_value(node),
+        }
+
+        self.set_type(node, void_type_id);
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<LocalResolved>) -> TypeResult {
+        let return_type_id = self.symbol_table.declaration_types.get(&node.get_id())
+            .and_then(|type_id| match self.resolved_type(*type_id) {
+                ResolvedType::Function(function_type) => Some(function_type.return_type),
+                _ => None,
+            });
+
+        if let Some(body) = &node.body {
+            self.visit_body(body, return_type_id);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<LocalResolved>) -> TypeResult {
+        let function_type = match self.check_expr(&node.expr, None) {
+            Some(callee_type_id) => match self.resolved_type(callee_type_id) {
+                ResolvedType::Function(function_type) => Some(function_type),
+                _ => {
+                    self.diagnostics.push(ErrMsg::CallOnNonFunctionType(self.type_name(callee_type_id)).make_diagnostic(*node.get_position()));
+                    None
+                },
+            },
+            None => None,
+        };
+
+        let Some(function_type) = function_type else {
+            for argument in &node.arguments {
+                self.check_expr(argument, None);
+            }
+
+            return self.witness(node);
+        };
+
+        if function_type.param_types.len() != node.arguments.len() {
+            self.diagnostics.push(ErrMsg::FunctionArgumentCountMismatch(function_type.param_types.len(), node.arguments.len()).make_diagnostic(*node.get_position()));
+        }
+
+        for (index, argument) in node.arguments.iter().enumerate() {
+            let Some(&param_type_id) = function_type.param_types.get(index) else {
+                self.check_expr(argument, None);
+                continue;
+            };
+
+            // A reference parameter takes a place of the referenced type
+            let param_type_id = self.dereferenced(param_type_id);
+
+            if let Some(argument_type_id) = self.check_assignable(param_type_id, argument) {
+                self.diagnostics.push(ErrMsg::FunctionArgumentTypeMismatch(index, self.type_name(param_type_id), self.type_name(argument_type_id)).make_diagnostic(*argument.get_position()));
+            }
+        }
+
+        self.set_type(node, function_type.return_type);
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<LocalResolved>) -> TypeResult {
+        // Initializers are folded and checked against the declared type by the constant evaluator
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<LocalResolved>) -> TypeResult {
+        let expected = self.expected.take();
+
+        self.check_condition(&node.condition);
+
+        let success_type_id = self.check_expr(&node.success, expected);
+
+        // Without an else branch there is no value when the condition fails
+        let Some(fail) = &node.fail else {
+            self.set_type(node, self.compiler.type_arena.void());
+            return self.witness(node);
+        };
+
+        let fail_type_id = self.check_expr(fail, expected);
+
+        if let (Some(success_type_id), Some(fail_type_id)) = (success_type_id, fail_type_id) {
+            if success_type_id == fail_type_id {
+                self.set_type(node, success_type_id);
+            } else {
+                self.diagnostics.push(ErrMsg::MismatchedIfBranches(self.type_name(success_type_id), self.type_name(fail_type_id)).make_diagnostic(*node.get_position()));
+            }
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<LocalResolved>) -> TypeResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<LocalResolved>) -> TypeResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        let param_types = node.parameters.iter()
+            .map(|parameter| self.symbol_table.declaration_types.get(&parameter.get_id()).map(|type_id| *type_id))
+            .collect::<Option<Vec<_>>>();
+        let return_type = self.compiler.resolve_return_type(self.symbol_table, &node.return_type, self.diagnostics);
+
+        self.visit_body(&node.body, return_type);
+
+        if let (Some(param_types), Some(return_type)) = (param_types, return_type) {
+            self.set_type(node, self.compiler.type_arena.make_function(FunctionType { param_types, return_type }));
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<LocalResolved>) -> TypeResult {
+        let expected = self.expected.take();
+        self.check_literal(node, expected, false);
+
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<LocalResolved>) -> TypeResult {
+        let expected = self.expected.take();
+
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        if let Some(condition) = &node.condition {
+            self.check_condition(condition);
+        }
+
+        if let Some(increment) = &node.increment {
+            self.check_expr(increment, None);
+        }
+
+        self.loops.push(ExitFrame {
+            label: node.label.as_ref().map(|label| label.as_ref()),
+            expected,
+            value_type: None,
+        });
+
+        node.body.accept_visitor(self);
+
+        // A loop has the type of the values it breaks with, and is void without any
+        let frame = self.loops.pop().unwrap();
+        self.set_type(node, frame.value_type.unwrap_or_else(|| self.compiler.type_arena.void()));
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<LocalResolved>) -> TypeResult {
+        for size in &node.sizes {
+            if let Some(size_type_id) = self.check_integer(size) {
+                self.diagnostics.push(ErrMsg::ArraySizeNotInteger(self.type_name(size_type_id)).make_diagnostic(*size.get_position()));
+            }
+        }
+
+        // Every dimension is reached through a pointer to the rows of the next one
+        if let Some(mut type_id) = self.resolve_type(&node.array_type) {
+            for _ in 0..node.dimension {
+                type_id = self.compiler.type_arena.make_ptr(type_id);
+            }
+
+            self.set_type(node, type_id);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<LocalResolved>) -> TypeResult {
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<LocalResolved>) -> TypeResult {
+        let struct_type_id = self.symbol_table.types.get(&*node.name).map(|type_id| *type_id);
+
+        for (_, _, member_name, default_value) in &node.members {
+            let Some(default_value) = default_value else {
+                continue;
+            };
+
+            match struct_type_id.and_then(|struct_type_id| self.member_type(struct_type_id, member_name)) {
+                Some(member_type_id) => {
+                    if let Some(value_type_id) = self.check_assignable(member_type_id, default_value) {
+                        self.diagnostics.push(ErrMsg::IncompatibleAssignment(self.type_name(member_type_id), self.type_name(value_type_id)).make_diagnostic(*default_value.get_position()));
+                    }
+                },
+                None => {
+                    self.check_expr(default_value, None);
+                },
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<LocalResolved>) -> TypeResult {
+        let Some(expr_type_id) = self.check_expr(&node.expr, None) else {
+            return self.witness(node);
+        };
+
+        let member_name = &node.member_name;
+
+        // `.` reaches into a struct value and gives the length of strings and arrays, `->` goes through a pointer to a struct
+        let struct_type_id = match (node.is_direct, self.resolved_type(expr_type_id)) {
+            (true, ResolvedType::Str | ResolvedType::Array(_) | ResolvedType::FixedArray(_, _)) if **member_name == "len" => {
+                self.set_type(node, self.compiler.type_arena.int());
+                return self.witness(node);
+            },
+            (true, ResolvedType::Str | ResolvedType::Array(_) | ResolvedType::FixedArray(_, _)) => {
+                self.diagnostics.push(ErrMsg::FieldNotFound(member_name.to_string()).make_diagnostic(*member_name.get_position()));
+                return self.witness(node);
+            },
+            (true, ResolvedType::Struct(_)) => Some(expr_type_id),
+            (false, ResolvedType::Pointer(inner_type_id)) if matches!(self.resolved_type(inner_type_id), ResolvedType::Struct(_)) => Some(inner_type_id),
+            _ => None,
+        };
+
+        let Some(struct_type_id) = struct_type_id else {
+            self.diagnostics.push(ErrMsg::IncompatibleMemberAccessType(self.type_name(expr_type_id)).make_diagnostic(*node.get_position()));
+            return self.witness(node);
+        };
+
+        match self.member_type(struct_type_id, member_name) {
+            Some(member_type_id) => {
+                self.check_member_visible(struct_type_id, member_name);
+                self.set_type(node, member_type_id);
+            },
+            None => {
+                self.diagnostics.push(ErrMsg::FieldNotFound(member_name.to_string()).make_diagnostic(*member_name.get_position()));
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<LocalResolved>) -> TypeResult {
+        // Unknown structs and fields were reported by the struct checker
+        let struct_type_id = self.resolve_struct(&node.type_name);
+
+        for (field_name, value) in &node.fields {
+            match struct_type_id.and_then(|struct_type_id| self.member_type(struct_type_id, field_name)) {
+                Some(member_type_id) => {
+                    if let Some(value_type_id) = self.check_assignable(member_type_id, value) {
+                        self.diagnostics.push(ErrMsg::IncompatibleAssignment(self.type_name(member_type_id), self.type_name(value_type_id)).make_diagnostic(*value.get_position()));
+                    }
+                },
+                None => {
+                    self.check_expr(value, None);
+                },
+            }
+        }
+
+        if let Some(struct_type_id) = struct_type_id {
+            self.set_type(node, struct_type_id);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<LocalResolved>) -> TypeResult {
+        let expected = self.expected.take();
+
+        let expr_type_id = match (node.operator, &node.expr) {
+            (UnaryOperator::Negative, ASTEnum::Literal(literal)) => {
+                self.check_literal(literal, expected, true);
+                self.symbol_table.ast_types.get(&literal.get_id()).map(|type_id| *type_id)
+            },
+            (UnaryOperator::Negative | UnaryOperator::BitwiseNot, expr) => self.check_expr(expr, expected),
+            (_, expr) => self.check_expr(expr, None),
+        };
+
+        let Some(expr_type_id) = expr_type_id else {
+            return self.witness(node);
+        };
+
+        let expr_type = self.resolved_type(expr_type_id);
+
+        let type_id = match node.operator {
+            UnaryOperator::Negative => expr_type.numeric_type()
+                .filter(|numeric_type| numeric_type.is_float() || numeric_type.is_signed())
+                .map(|_| expr_type_id),
+            UnaryOperator::Not => (expr_type == ResolvedType::Boolean).then_some(expr_type_id),
+            UnaryOperator::BitwiseNot => self.is_integer(expr_type_id).then_some(expr_type_id),
+            UnaryOperator::AddressOf => Some(self.compiler.type_arena.make_ptr(expr_type_id)),
+            UnaryOperator::Deref => match expr_type {
+                ResolvedType::Pointer(inner_type_id) => Some(inner_type_id),
+                _ => None,
+            },
+        };
+
+        match type_id {
+            Some(type_id) => self.set_type(node, type_id),
+            None => {
+                self.diagnostics.push(ErrMsg::IncompatibleUnaryType(self.type_name(expr_type_id), node.operator).make_diagnostic(*node.get_position()));
+            },
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<LocalResolved>) -> TypeResult {
+        let decl_id = self.symbol_table.variables.get(&node.get_id()).map(|decl_id| *decl_id);
+
+        let type_id = match decl_id {
+            Some(decl_id) => self.variable_type(node, decl_id).map(|type_id| self.dereferenced(type_id)),
+            None => match self.function_type(node) {
+                Some(function_type_id) => function_type_id,
+                None => {
+                    self.diagnostics.push(ErrMsg::UnknownVariable(node.path.to_string()).make_diagnostic(*node.get_position()));
+                    None
+                },
+            },
+        };
+
+        if let Some(type_id) = type_id {
+            self.set_type(node, type_id);
+        }
+
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilerOptions;
+    use crate::compiler::const_eval::ConstEvaluator;
+    use crate::compiler::test_util::{messages, parse_items, parse_scopes, resolve_locals};
+
+    fn check_scopes(scopes: &[Scope]) -> Vec<Diagnostic> {
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let mut diagnostics = Vec::new();
+
+        // Array lengths are folded before the types using them are resolved
+        for scope in scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            ConstEvaluator::new(&compiler, symbol_table, &mut diagnostics).visit_scope(scope);
+        }
+
+        diagnostics.extend(compiler.register_types(scopes.iter()));
+
+        for scope in scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            let local_resolved = resolve_locals(&compiler, scope);
+            TypeChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+        }
+
+        diagnostics
+    }
+
+    fn check(items: &str) -> Vec<Diagnostic> {
+        check_scopes(std::slice::from_ref(&parse_items(items)))
+    }
+
+    #[test]
+    fn test_well_typed_program() {
+        let diagnostics = check("
+            struct Point { int x; int y = 2; $(int x) { self.x = x; } }
+            fn length(Point *p) -> int { return p->x * p->x + p->y * p->y; }
+            fn main() {
+                let Point p = $Point(1);
+                let int *values = new int[4];
+                values[0] = length(&p);
+                let bool positive = values[0] > 0 and !(p.x == 0);
+                let str name = \"marlin\";
+                let char first = name[0];
+                let int size = name.len;
+                let fn(int) -> int twice = fn(int n) -> int { return n * 2; };
+                let u8 byte = 255;
+                let i64 shifted = 1 << byte;
+                delete values;
+            }
+        ");
+
+        assert_eq!(messages(&diagnostics), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_incompatible_binary_operands() {
+        let diagnostics = check("fn f(int a, double b, bool c, u8 d) { a + b; c * c; a & b; !a; -d; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "incompatible types for operator '+': left is 'int', right is 'double'",
+            "incompatible types for operator '*': left is 'bool', right is 'bool'",
+            "incompatible types for operator '&': left is 'int', right is 'double'",
+            "incompatible type for operator '!': expression is of type 'int'",
+            "incompatible type for operator '-': expression is of type 'u8'",
+        ]);
+    }
+
+    #[test]
+    fn test_comparisons_are_bool() {
+        let diagnostics = check("fn f(u16 a, u16 b) { let bool less = a < b; let bool same = a == 3; let u16 c = a < b; }");
+
+        assert_eq!(messages(&diagnostics), vec!["cannot assign expression of type 'bool' to variable of type 'u16'"]);
+    }
+
+    #[test]
+    fn test_literals_take_context_type() {
+        let diagnostics = check("fn f(u8 a) { let u8 b = a + 1; let i8 c = -128; let i8 d = 128; let u8 e = 256 - a; let int g = 1 + a; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "literal 128 out of range for 'i8'",
+            "literal 256 out of range for 'u8'",
+            "cannot assign expression of type 'u8' to variable of type 'int'",
+        ]);
+    }
+
+    #[test]
+    fn test_function_calls() {
+        let diagnostics = check("fn g(int a, bool b) -> int { return a; } fn f(int x) { g(1); g(1, 2); x(1); let bool b = g(1, true); }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "function expected 2 arguments, but 1 were provided",
+            "function argument 1 expected type 'bool', but found type 'int'",
+            "cannot call expression of non-function type 'int'",
+            "cannot assign expression of type 'int' to variable of type 'bool'",
+        ]);
+    }
+
+    #[test]
+    fn test_misplaced_references() {
+        let diagnostics = check("struct Holder { int& value; } fn pick(int& a) -> int& { return a; } fn f(int x) { let fn() -> int& g = fn() -> int& { return x; }; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "a reference cannot be used as a struct member",
+            "a reference cannot be used as a return type",
+            "a reference cannot be used as a return type",
+            "a reference cannot be used as a return type",
+        ]);
+    }
+
+    #[test]
+    fn test_member_access() {
+        let diagnostics = check("struct P { int x; } fn f(P p, P *q, int i) { p.y; q.x; i.x; p->x; q->x; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "field 'y' not found in struct",
+            "cannot access member of type 'P*'",
+            "cannot access member of type 'int'",
+            "cannot access member of type 'P'",
+        ]);
+    }
+
+    #[test]
+    fn test_array_index_and_literal() {
+        let diagnostics = check("const N: int = 3; fn f(int[N] a, bool b) { a[b]; let int[N] c = [1, 2, 3]; let int[] d = [1, true]; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "array index must be of integer type, found 'bool'",
+            "mismatched types in array literal: expected 'int', found 'bool'",
+        ]);
+    }
+
+    #[test]
+    fn test_sized_integer_index() {
+        let diagnostics = check("fn f(int[] a, u8 i, i64 j, double d) -> int** { a[i]; a[j]; a[d]; return new int[i][j]; }");
+
+        assert_eq!(messages(&diagnostics), vec!["array index must be of integer type, found 'double'"]);
+    }
+
+    #[test]
+    fn test_return_types() {
+        let diagnostics = check("fn f() -> int { return true; } fn g() { return 1; } fn h() -> u8 { let fn() -> bool l = fn() -> bool { return 1; }; return 200; }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "mismatched return type: expected 'int', found 'bool'",
+            "mismatched return type: expected 'void', found 'int'",
+            "mismatched return type: expected 'bool', found 'int'",
+        ]);
+    }
+
+    #[test]
+    fn test_conditions() {
+        let diagnostics = check("fn f(int a) { if a + 1 { } while a - 1 { } for (let int i = 0; i; i = i + 1) { } if a > 0 { } }");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "condition must be of type 'bool', found 'int'",
+            "condition must be of type 'bool', found 'int'",
+            "condition must be of type 'bool', found 'int'",
+        ]);
+    }
+
+    #[test]
+    fn test_block_results_and_if_branches() {
+        let diagnostics = check("
+            fn f(bool c) -> u8 {
+                let u8 a = { result 1; };
+                let int b = { result 1; result true; };
+                let int d = if c { result 1; } else { result true; };
+                return if c { result 2; } else { result a; };
+            }
+        ");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "mismatched result types in block: expected 'int', found 'bool'",
+            "mismatched types in if branches: 'then' is 'int', 'else' is 'bool'",
+        ]);
+    }
+
+    #[test]
+    fn test_break_values() {
+        let diagnostics = check("
+            fn f(bool c) {
+                let u8 a = loop { if c { break 1; } break 2; };
+                let int b = loop { break 1; break true; };
+                let int d = loop:outer { loop { break:outer 1; break 'x'; } break:outer 2; };
+                let int e = loop { break; };
+            }
+        ");
+
+        assert_eq!(messages(&diagnostics), vec![
+            "mismatched break types in loop: expected 'int', found 'bool'",
+            "cannot assign expression of type 'void' to variable of type 'int'",
+        ]);
+    }
+
+    #[test]
+    fn test_private_members_outside_their_scope() {
+        let scopes = parse_scopes("
+            scope Main {
+                from Main::Geo require Point, origin;
+                fn f(Point *p) -> int { let Point o = origin(); return o.x + o.y + p->y; }
+                scope Geo {
+                    pub struct Point { pub int x; int y; $(int y) { self.y = y; } }
+                    pub fn origin() -> Point { let Point o = $Point(0); return $Point(o.y); }
+                    scope Inner { from Main::Geo require Point; fn g(Point p) -> int { return p.y; } }
+                }
+            }
+        ");
+        let diagnostics = check_scopes(&scopes);
+
+        assert_eq!(messages(&diagnostics), vec![
+            "field 'y' of struct 'Point' is private to its scope",
+            "field 'y' of struct 'Point' is private to its scope",
+        ]);
+    }
+
+    #[test]
+    fn test_unknown_names() {
+        let diagnostics = check("fn f(Missing m) { missing; } fn g() { let Other o; }");
+
+        assert_eq!(messages(&diagnostics), vec!["unknown type name: 'Missing'", "unknown variable: 'missing'", "unknown type name: 'Other'"]);
+    }
+
+    #[test]
+    fn test_items_of_other_scopes() {
+        let scopes = parse_scopes("
+            scope Main {
+                scope Lib { pub struct V { pub int x; } pub static COUNT: u32 = 0; pub fn make(int x) -> V { return V { x: x }; } }
+                fn main() { let int x = Main::Lib::make(1).x; let u32 count = Main::Lib::COUNT + x; }
+            }
+        ");
+        let diagnostics = check_scopes(&scopes);
+
+        assert_eq!(messages(&diagnostics), vec!["incompatible types for operator '+': left is 'u32', right is 'int'"]);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{ast::*, compiler::{Compiler, SymbolTable, visit::{ASTVisitor, AcceptsASTVisitor, Parsed, PhaseWitness, VisitResult}}, diagnostic::{Diagnostic, ErrMsg}, lexer::token::{Located, PositionRange, Positioned}};
+
+impl PhaseWitness<Parsed> for VisibilityWitness {}
+
+pub type VisibilityResult = VisitResult<Parsed, VisibilityWitness>;
+
+pub struct VisibilityWitness {
+    _private: (),
+}
+
+// Reports private items that are used from outside their scope, either
+// through a `require` or through a qualified path.
+pub struct VisibilityChecker<'ctx, 'ast> {
+    compiler: &'ctx Compiler<'ast>,
+    symbol_table: &'ctx SymbolTable<'ast>,
+    diagnostics: &'ctx mut Vec<Diagnostic>,
+}
+
+impl<'ctx, 'ast> VisibilityChecker<'ctx, 'ast> {
+    pub fn new(compiler: &'ctx Compiler<'ast>, symbol_table: &'ctx SymbolTable<'ast>, diagnostics: &'ctx mut Vec<Diagnostic>) -> Self {
+        Self {
+            compiler,
+            symbol_table,
+            diagnostics,
+        }
+    }
+
+    fn witness(&mut self, node: &impl ASTNode<Parsed>) -> VisibilityResult {
+        VisitResult::new(node.get_id(), VisibilityWitness { _private: () })
+    }
+
+    // Paths that lead nowhere are left to the resolvers to report. A bare name that
+    // resolves elsewhere came in through a require, which is checked on its own.
+    fn check_path(&mut self, path: &Path, position: PositionRange) {
+        if let Some((owner, decl_id)) = self.compiler.resolve_item(self.symbol_table, path)
+            && path.segments.len() > 1
+            && !self.compiler.is_visible(self.symbol_table, owner, decl_id)
+        {
+            self.diagnostics.push(ErrMsg::PrivateItem(path.to_string()).make_diagnostic(position));
+        }
+    }
+
+    // Struct names are resolved too so that the requires bringing them in count as used
+    fn check_type_name(&mut self, type_name: &str, position: PositionRange) {
+        let path = Path::new(vec![Located::new(type_name.to_string(), position)]);
+        self.check_path(&path, position);
+    }
+
+    fn visit_type(&mut self, parsed_type: &ParsedType) {
+        for length in parsed_type.array_lengths() {
+            let position = PositionRange::concat(length.locations.first().unwrap(), length.locations.last().unwrap());
+            self.check_path(length, position);
+        }
+
+        for type_name in parsed_type.type_names() {
+            self.check_type_name(type_name, *parsed_type.get_position());
+        }
+    }
+}
+
+impl<'ctx, 'ast> ASTVisitor<'ast, Parsed, VisibilityWitness> for VisibilityChecker<'ctx, 'ast> {
+    fn visit_array_access(&mut self, node: &'ast ArrayAccess<Parsed>) -> VisibilityResult {
+        node.expr.accept_visitor(self);
+        node.index.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<Parsed>) -> VisibilityResult {
+        for element in &node.elements {
+            element.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_assignment(&mut self, node: &'ast AssignmentExpr<Parsed>) -> VisibilityResult {
+        node.assignee.accept_visitor(self);
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_binary(&mut self, node: &'ast BinaryExpr<Parsed>) -> VisibilityResult {
+        node.left.accept_visitor(self);
+        node.right.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_block(&mut self, node: &'ast BlockExpr<Parsed>) -> VisibilityResult {
+        for expr in &node.exprs {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_cast(&mut self, node: &'ast CastExpr<Parsed>) -> VisibilityResult {
+        node.expr.accept_visitor(self);
+        self.visit_type(&node.cast_type);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor(&mut self, node: &'ast ConstructorItem<Parsed>) -> VisibilityResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_constructor_call(&mut self, node: &'ast ConstructorCallExpr<Parsed>) -> VisibilityResult {
+        self.check_type_name(&node.type_name, *node.type_name.get_position());
+
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_declaration(&mut self, node: &'ast DeclarationExpr<Parsed>) -> VisibilityResult {
+        self.visit_type(&node.declaration_type);
+
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_delete(&mut self, node: &'ast DeleteExpr<Parsed>) -> VisibilityResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_exit(&mut self, node: &'ast ExitExpr<Parsed>) -> VisibilityResult {
+        if let Some(expr) = &node.expr {
+            expr.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function(&mut self, node: &'ast FunctionItem<Parsed>) -> VisibilityResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        self.visit_type(&node.return_type);
+
+        if let Some(body) = &node.body {
+            body.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_function_access(&mut self, node: &'ast FunctionAccess<Parsed>) -> VisibilityResult {
+        node.expr.accept_visitor(self);
+
+        for argument in &node.arguments {
+            argument.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_global(&mut self, node: &'ast GlobalItem<Parsed>) -> VisibilityResult {
+        self.visit_type(&node.declaration_type);
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_if(&mut self, node: &'ast IfExpr<Parsed>) -> VisibilityResult {
+        node.condition.accept_visitor(self);
+        node.success.accept_visitor(self);
+
+        if let Some(fail) = &node.fail {
+            fail.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_impl(&mut self, node: &'ast ImplItem<Parsed>) -> VisibilityResult {
+        for function in &node.functions {
+            function.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<Parsed>) -> VisibilityResult {
+        for parameter in &node.parameters {
+            parameter.accept_visitor(self);
+        }
+
+        self.visit_type(&node.return_type);
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_literal(&mut self, node: &'ast LiteralExpr<Parsed>) -> VisibilityResult {
+        self.witness(node)
+    }
+
+    fn visit_loop(&mut self, node: &'ast LoopExpr<Parsed>) -> VisibilityResult {
+        if let Some(initial) = &node.initial {
+            initial.accept_visitor(self);
+        }
+
+        if let Some(condition) = &node.condition {
+            condition.accept_visitor(self);
+        }
+
+        if let Some(increment) = &node.increment {
+            increment.accept_visitor(self);
+        }
+
+        node.body.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_new_array(&mut self, node: &'ast NewArrayExpr<Parsed>) -> VisibilityResult {
+        for size in &node.sizes {
+            size.accept_visitor(self);
+        }
+
+        self.visit_type(&node.array_type);
+
+        self.witness(node)
+    }
+
+    fn visit_scope(&mut self, node: &'ast Scope<Parsed>) -> VisibilityResult {
+        for require in &node.requires {
+            self.check_path(&require.path, *require.path.get_position());
+        }
+
+        for scope in &node.child_scopes {
+            scope.accept_visitor(self);
+        }
+
+        for item in &node.items {
+            item.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct(&mut self, node: &'ast StructItem<Parsed>) -> VisibilityResult {
+        for (_, member_type, _, default_value) in &node.members {
+            self.visit_type(member_type);
+
+            if let Some(default_value) = default_value {
+                default_value.accept_visitor(self);
+            }
+        }
+
+        for constructor in node.constructors.iter().chain(&node.destructor) {
+            constructor.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_struct_access(&mut self, node: &'ast StructAccess<Parsed>) -> VisibilityResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<Parsed>) -> VisibilityResult {
+        self.check_type_name(&node.type_name, *node.type_name.get_position());
+
+        for (_, value) in &node.fields {
+            value.accept_visitor(self);
+        }
+
+        self.witness(node)
+    }
+
+    fn visit_unary(&mut self, node: &'ast UnaryExpr<Parsed>) -> VisibilityResult {
+        node.expr.accept_visitor(self);
+
+        self.witness(node)
+    }
+
+    fn visit_var(&mut self, node: &'ast VarExpr<Parsed>) -> VisibilityResult {
+        self.check_path(&node.path, *node.get_position());
+
+        self.witness(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilerOptions;
+    use crate::compiler::test_util::parse_scopes;
+
+    fn check(code: &str) -> Vec<String> {
+        let scopes = parse_scopes(code);
+        let compiler = Compiler::new(scopes.iter(), CompilerOptions::default());
+        let mut diagnostics = Vec::new();
+
+        for scope in &scopes {
+            let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+            VisibilityChecker::new(&compiler, symbol_table, &mut diagnostics).visit_scope(scope);
+        }
+
+        diagnostics.into_iter().map(|diagnostic| diagnostic.message).collect()
+    }
+
+    #[test]
+    fn test_private_function_through_path() {
+        let messages = check("scope Main { scope Lib { fn helper() {} pub fn api() { helper(); } } fn f() { Main::Lib::api(); Main::Lib::helper(); } }");
+
+        assert_eq!(messages, vec!["'Main::Lib::helper' is private to its scope"]);
+    }
+
+    #[test]
+    fn test_private_items_through_requires() {
+        let messages = check("scope Main { from Main::Lib require SECRET, SIZE, Point; scope Lib { const SECRET: int = 1; pub const SIZE: int = 2; struct Point { int x; } } }");
+
+        assert_eq!(messages, vec!["'Main::Lib::SECRET' is private to its scope", "'Main::Lib::Point' is private to its scope"]);
+    }
+
+    #[test]
+    fn test_private_import_reported_once() {
+        let messages = check("scope Main { from Main::Lib require SECRET; fn f() -> int { return SECRET + SECRET; } scope Lib { const SECRET: int = 1; } }");
+
+        assert_eq!(messages, vec!["'Main::Lib::SECRET' is private to its scope"]);
+    }
+
+    #[test]
+    fn test_requiring_a_scope() {
+        let messages = check("scope Main { require Main::Lib; scope Lib { fn helper() {} } }");
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_nested_scopes_see_private_items() {
+        let messages = check("scope Main { const SECRET: int = 1; fn helper() {} scope Inner { fn f() -> int { Main::helper(); return Main::SECRET; } } }");
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_parent_cannot_see_child_private_items() {
+        let messages = check("scope Main { scope Inner { static count: int = 0; } fn f() -> int { return Main::Inner::count; } }");
+
+        assert_eq!(messages, vec!["'Main::Inner::count' is private to its scope"]);
+    }
+
+    #[test]
+    fn test_private_array_length() {
+        let messages = check("scope Main { scope Lib { const N: int = 4; pub const M: int = 2; } fn f() { let int[Main::Lib::N] a; let int[Main::Lib::M] b; } }");
+
+        assert_eq!(messages, vec!["'Main::Lib::N' is private to its scope"]);
+    }
+}
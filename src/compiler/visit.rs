@@ -73,6 +73,11 @@ where
         unsafe { std::mem::transmute(node) }
     }
 
+    pub fn transmute_array_literal(self, node: &ArrayLiteral<P>) -> &ArrayLiteral<P::Next> {
+        assert!(VisitResult::verify(&self, node));
+        unsafe { std::mem::transmute(node) }
+    }
+
     pub fn transmute_assignment(self, node: &AssignmentExpr<P>) -> &AssignmentExpr<P::Next> {
         assert!(VisitResult::verify(&self, node));
         unsafe { std::mem::transmute(node) }
@@ -128,6 +133,11 @@ where
         unsafe { std::mem::transmute(node) }
     }
 
+    pub fn transmute_global(self, node: &GlobalItem<P>) -> &GlobalItem<P::Next> {
+        assert!(VisitResult::verify(&self, node));
+        unsafe { std::mem::transmute(node) }
+    }
+
     pub fn transmute_if(self, node: &IfExpr<P>) -> &IfExpr<P::Next> {
         assert!(VisitResult::verify(&self, node));
         unsafe { std::mem::transmute(node) }
@@ -138,6 +148,11 @@ where
         unsafe { std::mem::transmute(node) }
     }
 
+    pub fn transmute_lambda(self, node: &LambdaExpr<P>) -> &LambdaExpr<P::Next> {
+        assert!(VisitResult::verify(&self, node));
+        unsafe { std::mem::transmute(node) }
+    }
+
     pub fn transmute_literal(self, node: &LiteralExpr<P>) -> &LiteralExpr<P::Next> {
         assert!(VisitResult::verify(&self, node));
         unsafe { std::mem::transmute(node) }
@@ -168,6 +183,11 @@ where
         unsafe { std::mem::transmute(node) }
     }
 
+    pub fn transmute_struct_literal(self, node: &StructLiteral<P>) -> &StructLiteral<P::Next> {
+        assert!(VisitResult::verify(&self, node));
+        unsafe { std::mem::transmute(node) }
+    }
+
     pub fn transmute_unary(self, node: &UnaryExpr<P>) -> &UnaryExpr<P::Next> {
         assert!(VisitResult::verify(&self, node));
         unsafe { std::mem::transmute(node) }
@@ -185,6 +205,7 @@ where
     W: PhaseWitness<P>,
 {
     fn visit_array_access(&mut self, node: &'ast ArrayAccess<P>) -> VisitResult<P, W>;
+    fn visit_array_literal(&mut self, node: &'ast ArrayLiteral<P>) -> VisitResult<P, W>;
     fn visit_assignment(&mut self, node: &'ast AssignmentExpr<P>) -> VisitResult<P, W>;
     fn visit_binary(&mut self, node: &'ast BinaryExpr<P>) -> VisitResult<P, W>;
     fn visit_block(&mut self, node: &'ast BlockExpr<P>) -> VisitResult<P, W>;
@@ -196,14 +217,17 @@ where
     fn visit_exit(&mut self, node: &'ast ExitExpr<P>) -> VisitResult<P, W>;
     fn visit_function(&mut self, node: &'ast FunctionItem<P>) -> VisitResult<P, W>;
     fn visit_function_access(&mut self, node: &'ast FunctionAccess<P>) -> VisitResult<P, W>;
+    fn visit_global(&mut self, node: &'ast GlobalItem<P>) -> VisitResult<P, W>;
     fn visit_if(&mut self, node: &'ast IfExpr<P>) -> VisitResult<P, W>;
     fn visit_impl(&mut self, node: &'ast ImplItem<P>) -> VisitResult<P, W>;
+    fn visit_lambda(&mut self, node: &'ast LambdaExpr<P>) -> VisitResult<P, W>;
     fn visit_literal(&mut self, node: &'ast LiteralExpr<P>) -> VisitResult<P, W>;
     fn visit_loop(&mut self, node: &'ast LoopExpr<P>) -> VisitResult<P, W>;
     fn visit_new_array(&mut self, node: &'ast NewArrayExpr<P>) -> VisitResult<P, W>;
     fn visit_scope(&mut self, node: &'ast Scope<P>) -> VisitResult<P, W>;
     fn visit_struct(&mut self, node: &'ast StructItem<P>) -> VisitResult<P, W>;
     fn visit_struct_access(&mut self, node: &'ast StructAccess<P>) -> VisitResult<P, W>;
+    fn visit_struct_literal(&mut self, node: &'ast StructLiteral<P>) -> VisitResult<P, W>;
     fn visit_unary(&mut self, node: &'ast UnaryExpr<P>) -> VisitResult<P, W>;
     fn visit_var(&mut self, node: &'ast VarExpr<P>) -> VisitResult<P, W>;
 }
@@ -224,6 +248,7 @@ where
     fn accept_visitor(&'ast self, visitor: &mut impl ASTVisitor<'ast, P, W>) -> VisitResult<P, W> {
         match self {
             ASTEnum::ArrayAccess(node) => visitor.visit_array_access(node),
+            ASTEnum::ArrayLiteral(node) => visitor.visit_array_literal(node),
             ASTEnum::Assignment(node) => visitor.visit_assignment(node),
             ASTEnum::Binary(node) => visitor.visit_binary(node),
             ASTEnum::Block(node) => visitor.visit_block(node),
@@ -235,14 +260,17 @@ where
             ASTEnum::Exit(node) => visitor.visit_exit(node),
             ASTEnum::Function(node) => visitor.visit_function(node),
             ASTEnum::FunctionAccess(node) => visitor.visit_function_access(node),
+            ASTEnum::Global(node) => visitor.visit_global(node),
             ASTEnum::If(node) => visitor.visit_if(node),
             ASTEnum::Impl(node) => visitor.visit_impl(node),
+            ASTEnum::Lambda(node) => visitor.visit_lambda(node),
             ASTEnum::Literal(node) => visitor.visit_literal(node),
             ASTEnum::Loop(node) => visitor.visit_loop(node),
             ASTEnum::NewArray(node) => visitor.visit_new_array(node),
             ASTEnum::Scope(node) => visitor.visit_scope(node),
             ASTEnum::Struct(node) => visitor.visit_struct(node),
             ASTEnum::StructAccess(node) => visitor.visit_struct_access(node),
+            ASTEnum::StructLiteral(node) => visitor.visit_struct_literal(node),
             ASTEnum::Unary(node) => visitor.visit_unary(node),
             ASTEnum::Var(node) => visitor.visit_var(node),
         }
@@ -259,6 +287,16 @@ where
     }
 }
 
+impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for ArrayLiteral<P> 
+where
+    P: Phase,
+    W: PhaseWitness<P>,
+{
+    fn accept_visitor(&'ast self, visitor: &mut impl ASTVisitor<'ast, P, W>) -> VisitResult<P, W> {
+        visitor.visit_array_literal(self)
+    }
+}
+
 impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for AssignmentExpr<P> 
 where
     P: Phase,
@@ -369,6 +407,16 @@ where
     }
 }
 
+impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for GlobalItem<P> 
+where
+    P: Phase,
+    W: PhaseWitness<P>,
+{
+    fn accept_visitor(&'ast self, visitor: &mut impl ASTVisitor<'ast, P, W>) -> VisitResult<P, W> {
+        visitor.visit_global(self)
+    }
+}
+
 impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for IfExpr<P> 
 where
     P: Phase,
@@ -389,6 +437,16 @@ where
     }
 }
 
+impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for LambdaExpr<P> 
+where
+    P: Phase,
+    W: PhaseWitness<P>,
+{
+    fn accept_visitor(&'ast self, visitor: &mut impl ASTVisitor<'ast, P, W>) -> VisitResult<P, W> {
+        visitor.visit_lambda(self)
+    }
+}
+
 impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for LiteralExpr<P> 
 where
     P: Phase,
@@ -449,6 +507,16 @@ where
     }
 }
 
+impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for StructLiteral<P> 
+where
+    P: Phase,
+    W: PhaseWitness<P>,
+{
+    fn accept_visitor(&'ast self, visitor: &mut impl ASTVisitor<'ast, P, W>) -> VisitResult<P, W> {
+        visitor.visit_struct_literal(self)
+    }
+}
+
 impl<'ast, P, W> AcceptsASTVisitor<'ast, P, W> for UnaryExpr<P> 
 where
     P: Phase,
@@ -2,12 +2,11 @@ use core::fmt;
 
 use crate::ast::{BinaryOperator, UnaryOperator};
 use crate::logger::LogLevel;
-use crate::lexer::token::{PositionRange, TokenType};
+use crate::lexer::token::{NumericType, PositionRange, TokenType};
 
 #[derive(Clone, Copy)]
 pub enum DiagnosticSeverity {
     Error,
-    #[allow(dead_code)]
     Warning
 }
 
@@ -48,6 +47,8 @@ pub enum ErrMsg {
     UnknownSymbol(char),
     UnterminatedString,
     DecimalLiteralAsInt,
+    UnknownLiteralSuffix(String),
+    LiteralOutOfRange(u64, NumericType),
     UnterminatedChar,
     ExpectedStatement,
     ExpectedExpression,
@@ -61,18 +62,59 @@ pub enum ErrMsg {
     UnknownEscapeSequence(char),
     UnknownTypeName(String),
     UnknownVariable(String),
+    PrivateItem(String),
+    PrivateField(String, String),
+    UnresolvedImport(String),
+    ConflictingImport(String),
+    UnusedImport(String),
     DuplicateVariable(String),
+    UseBeforeInit(String),
+    ExitOutsideLoop(String),
+    UnknownLoopLabel(String),
+    UnknownBlockLabel(String),
+    ResultOutsideBlock,
+    MissingReturn(String),
+    MissingLambdaReturn,
     IncompatibleBinaryTypes(String, String, BinaryOperator),
     IncompatibleUnaryType(String, UnaryOperator),
     FieldNotFound(String),
+    MissingField(String, String),
+    DuplicateField(String),
     IncompatibleMemberAccessType(String),
     ArrayIndexNotInteger(String),
+    ArraySizeNotInteger(String),
     MismatchedIfBranches(String, String),
+    MismatchedArrayElements(String, String),
+    MismatchedBreakTypes(String, String),
+    MismatchedResultTypes(String, String),
+    MismatchedReturnType(String, String),
+    ConditionNotBoolean(String),
+    EmptyArrayLiteral,
     IncompatibleAssignment(String, String),
+    AssignToConst(String),
+    AssignToFunction(String),
+    NotConstant,
+    ConstOverflow(String),
+    DivisionByZero,
+    RecursiveConstant(String),
+    InvalidArrayLength(String),
     FunctionArgumentCountMismatch(usize, usize),
     FunctionArgumentTypeMismatch(usize, String, String),
     CallOnNonFunctionType(String),
-    ConstructorNotFound(String)
+    ConstructorNotFound(String),
+    DuplicateDestructor(String),
+    UninitializedReference(String),
+    ReferenceToRvalue,
+    MisplacedReference(String),
+    AssignToRvalue,
+    AddressOfRvalue,
+    UnknownAttribute(String),
+    MisplacedAttribute(String),
+    DuplicateEntry(String),
+    EntryWithParameters(String),
+    DeleteNonPointer(String),
+    ReservedRuntimeName(String),
+    MoveOutOfPlace(String)
 }
 
 impl ErrMsg {
@@ -83,6 +125,14 @@ impl ErrMsg {
             position
         }
     }
+
+    pub fn make_warning(self, position: PositionRange) -> Diagnostic {
+        Diagnostic {
+            severity: DiagnosticSeverity::Warning,
+            message: format!("{}", self),
+            position
+        }
+    }
 }
 
 impl fmt::Display for ErrMsg {
@@ -91,6 +141,8 @@ impl fmt::Display for ErrMsg {
             Self::UnknownSymbol(x) => &format!("unknown symbol {}", x),
             Self::UnterminatedString => "unterminated string",
             Self::DecimalLiteralAsInt => "decimal literal cannot be used as int",
+            Self::UnknownLiteralSuffix(suffix) => &format!("unknown literal suffix: '{}'", suffix),
+            Self::LiteralOutOfRange(value, numeric_type) => &format!("literal {} out of range for '{}'", value, numeric_type),
             Self::UnterminatedChar => "unterminated char literal",
             Self::ExpectedDeclaration => "expected declaration",
             Self::ExpectedParameters => "expected parameters",
@@ -104,7 +156,19 @@ impl fmt::Display for ErrMsg {
             Self::UnknownEscapeSequence(x) => &format!("unknown escape sequence: \\{}", x),
             Self::UnknownTypeName(name) => &format!("unknown type name: '{}'", name),
             Self::UnknownVariable(name) => &format!("unknown variable: '{}'", name),
+            Self::PrivateItem(path) => &format!("'{}' is private to its scope", path),
+            Self::PrivateField(field_name, struct_name) => &format!("field '{}' of struct '{}' is private to its scope", field_name, struct_name),
+            Self::UnresolvedImport(path) => &format!("unresolved import: '{}'", path),
+            Self::ConflictingImport(name) => &format!("import of '{}' conflicts with another item of the same name", name),
+            Self::UnusedImport(path) => &format!("unused import: '{}'", path),
             Self::DuplicateVariable(name) => &format!("duplicate variable declaration: '{}'", name),
+            Self::UseBeforeInit(name) => &format!("variable '{}' used before being initialized", name),
+            Self::ExitOutsideLoop(keyword) => &format!("'{}' outside of a loop", keyword),
+            Self::UnknownLoopLabel(label) => &format!("unknown loop label: '{}'", label),
+            Self::UnknownBlockLabel(label) => &format!("unknown block label: '{}'", label),
+            Self::ResultOutsideBlock => "'result' outside of a block expression",
+            Self::MissingReturn(name) => &format!("function '{}' does not return a value on every path", name),
+            Self::MissingLambdaReturn => "lambda does not return a value on every path",
             Self::IncompatibleBinaryTypes(left, right, operator) => {
                 &format!("incompatible types for operator '{}': left is '{}', right is '{}'", operator, left, right)
             },
@@ -114,18 +178,50 @@ impl fmt::Display for ErrMsg {
             Self::FieldNotFound(field_name) => {
                 &format!("field '{}' not found in struct", field_name)
             },
+            Self::MissingField(field_name, struct_name) => {
+                &format!("missing field '{}' in literal of struct '{}'", field_name, struct_name)
+            },
+            Self::DuplicateField(field_name) => {
+                &format!("field '{}' is initialized more than once", field_name)
+            },
             Self::IncompatibleMemberAccessType(ty) => {
                 &format!("cannot access member of type '{}'", ty)
             },
             Self::ArrayIndexNotInteger(ty) => {
                 &format!("array index must be of integer type, found '{}'", ty)
             },
+            Self::ArraySizeNotInteger(ty) => {
+                &format!("array size must be of integer type, found '{}'", ty)
+            },
             Self::MismatchedIfBranches(then_type, else_type) => {
                 &format!("mismatched types in if branches: 'then' is '{}', 'else' is '{}'", then_type, else_type)
             },
+            Self::MismatchedArrayElements(first_type, element_type) => {
+                &format!("mismatched types in array literal: expected '{}', found '{}'", first_type, element_type)
+            },
+            Self::MismatchedBreakTypes(loop_type, break_type) => {
+                &format!("mismatched break types in loop: expected '{}', found '{}'", loop_type, break_type)
+            },
+            Self::MismatchedResultTypes(block_type, result_type) => {
+                &format!("mismatched result types in block: expected '{}', found '{}'", block_type, result_type)
+            },
+            Self::MismatchedReturnType(return_type, expr_type) => {
+                &format!("mismatched return type: expected '{}', found '{}'", return_type, expr_type)
+            },
+            Self::ConditionNotBoolean(ty) => {
+                &format!("condition must be of type 'bool', found '{}'", ty)
+            },
+            Self::EmptyArrayLiteral => "cannot infer the element type of an empty array literal",
             Self::IncompatibleAssignment(var_type, expr_type) => {
                 &format!("cannot assign expression of type '{}' to variable of type '{}'", expr_type, var_type)
             },
+            Self::AssignToConst(name) => &format!("cannot assign to constant '{}'", name),
+            Self::AssignToFunction(name) => &format!("cannot assign to function '{}'", name),
+            Self::NotConstant => "expression cannot be evaluated at compile time",
+            Self::ConstOverflow(ty) => &format!("constant expression overflows type '{}'", ty),
+            Self::DivisionByZero => "division by zero in constant expression",
+            Self::RecursiveConstant(name) => &format!("constant '{}' depends on its own value", name),
+            Self::InvalidArrayLength(value) => &format!("array length must be a non-negative integer, found {}", value),
             Self::FunctionArgumentCountMismatch(expected, found) => {
                 &format!("function expected {} arguments, but {} were provided", expected, found)
             },
@@ -137,6 +233,33 @@ impl fmt::Display for ErrMsg {
             },
             Self::ConstructorNotFound(ty) => {
                 &format!("constructor not found for type '{}'", ty)
+            },
+            Self::DuplicateDestructor(ty) => {
+                &format!("duplicate destructor for type '{}'", ty)
+            },
+            Self::UninitializedReference(name) => {
+                &format!("reference '{}' must be bound when it is declared", name)
+            },
+            Self::ReferenceToRvalue => "cannot bind a reference to a temporary value",
+            Self::MisplacedReference(position) => &format!("a reference cannot be used as a {}", position),
+            Self::AssignToRvalue => "cannot assign to a temporary value",
+            Self::AddressOfRvalue => "cannot take the address of a temporary value",
+            Self::UnknownAttribute(name) => &format!("unknown attribute: '{}'", name),
+            Self::MisplacedAttribute(name) => &format!("attribute '{}' is not allowed here", name),
+            Self::DuplicateEntry(name) => {
+                &format!("'{}' is marked as entry, but the program already has an entry function", name)
+            },
+            Self::EntryWithParameters(name) => {
+                &format!("entry function '{}' cannot take parameters", name)
+            },
+            Self::DeleteNonPointer(ty) => {
+                &format!("cannot delete expression of non-pointer type '{}'", ty)
+            },
+            Self::ReservedRuntimeName(name) => {
+                &format!("'{}' is the name of a runtime function and cannot be declared", name)
+            },
+            Self::MoveOutOfPlace(ty) => {
+                &format!("value of type '{}' has a destructor and can only be moved out of a local variable", ty)
             }
         };
 
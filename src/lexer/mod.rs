@@ -8,7 +8,7 @@ use std::{iter::Peekable, str::Chars};
 use crate::logger::{Log, LogTarget};
 use crate::diagnostic::{Diagnostic, ErrMsg};
 use crate::lexer::token::{Position, PositionRange};
-use crate::lexer::token::{Token, TokenType};
+use crate::lexer::token::{NumericType, Token, TokenType};
 
 pub struct Lexer<'ctx> {
     log_target: &'ctx dyn LogTarget,
@@ -16,6 +16,7 @@ pub struct Lexer<'ctx> {
     chars: Peekable<Chars<'ctx>>,
     next_position: Position, // Position of the next character to be read
     token_range: PositionRange, // Range of the current token being parsed
+    negated: bool, // Whether the current token is the operand of a unary minus
 }
 
 impl Log for Lexer<'_> {
@@ -35,6 +36,7 @@ impl<'ctx> Lexer<'ctx> {
             chars,
             next_position,
             token_range: PositionRange::new(next_position),
+            negated: false,
         }
     }
 
@@ -44,6 +46,8 @@ impl<'ctx> Lexer<'ctx> {
         let mut tokens = Vec::new();
 
         while self.peek().is_some() {
+            self.negated = Self::ends_in_negation(&tokens);
+
             if let Some(token) = self.next_token() {
                 self.log_debug(self.log_target, format!("Parsed token: {:?}", token));
                 tokens.push(token);
@@ -61,6 +65,33 @@ impl<'ctx> Lexer<'ctx> {
         tokens
     }
 
+    // A minus is unary when it does not follow an operand, as in `-128i8` or `x * -1`
+    fn ends_in_negation(tokens: &[Token]) -> bool {
+        match tokens {
+            [.., previous, last] if matches!(last.value, TokenType::Minus) => !Self::ends_operand(&previous.value),
+            [last] => matches!(last.value, TokenType::Minus),
+            _ => false,
+        }
+    }
+
+    fn ends_operand(token_type: &TokenType) -> bool {
+        matches!(token_type,
+            TokenType::Identifier(_) | TokenType::RightParen | TokenType::RightSquare |
+            TokenType::IntLiteral(_) | TokenType::DoubleLiteral(_) | TokenType::BoolLiteral(_) |
+            TokenType::CharLiteral(_) | TokenType::StringLiteral(_) |
+            TokenType::SizedIntLiteral(_, _) | TokenType::SizedFloatLiteral(_, _)
+        )
+    }
+
+    // The largest magnitude a literal may have, which is one more for a negated signed type
+    fn max_magnitude(&self, numeric_type: NumericType) -> u64 {
+        if self.negated && numeric_type.is_signed() && !numeric_type.is_float() {
+            numeric_type.max_literal() + 1
+        } else {
+            numeric_type.max_literal()
+        }
+    }
+
     fn next_char(&mut self) -> Option<char> {
         self.next().map(|(c, _)| c)
     }
@@ -117,6 +148,7 @@ impl<'ctx> Lexer<'ctx> {
             '^' => Some(self.end_token_consume(TokenType::Carat)),
             '%' => Some(self.end_token_consume(TokenType::Percentage)),
             '~' => Some(self.end_token_consume(TokenType::Tilda)),
+            '#' => Some(self.end_token_consume(TokenType::Hash)),
             '|' => Some(self.end_token_consume(TokenType::Bar)),
             '$' => Some(self.end_token_consume(TokenType::DollarSign)),
             ';' => Some(self.end_token_consume(TokenType::Semicolon)),
@@ -326,28 +358,49 @@ impl<'ctx> Lexer<'ctx> {
             self.next(); // Consume suffix underscore
         }
 
-        let peek = self.peek().cloned();
+        let mut suffix = String::new();
 
-        match peek {
-            Some('d') => {
-                self.next();
-                Some(self.end_token(TokenType::DoubleLiteral(value)))
-            },
-            Some('i') => {
-                self.next();
-                if !is_decimal {
-                    Some(self.end_token(TokenType::IntLiteral(value as i64)))
+        while let Some(&peek) = self.peek() {
+            if !Self::is_alphanumeric(peek) {
+                break;
+            }
+
+            suffix.push(self.next_char().unwrap());
+        }
+
+        match suffix.as_str() {
+            "d" => Some(self.end_token(TokenType::DoubleLiteral(value))),
+            "i" | "" if is_decimal => {
+                if suffix.is_empty() {
+                    Some(self.end_token(TokenType::DoubleLiteral(value)))
                 } else {
                     self.diagnostics.push(ErrMsg::DecimalLiteralAsInt.make_diagnostic(self.token_range));
                     None
                 }
             },
-            _ => {
-                if !is_decimal {
-                    Some(self.end_token(TokenType::IntLiteral(value as i64)))
-                } else {
-                    Some(self.end_token(TokenType::DoubleLiteral(value)))
-                }
+            "i" | "" if whole_part > self.max_magnitude(NumericType::I64) => {
+                self.diagnostics.push(ErrMsg::LiteralOutOfRange(whole_part, NumericType::I64).make_diagnostic(self.token_range));
+                None
+            },
+            // The magnitude of i64::MIN wraps to itself, the negation in front of it restores the value
+            "i" | "" => Some(self.end_token(TokenType::IntLiteral(whole_part as i64))),
+            _ => match NumericType::from_name(&suffix) {
+                Some(numeric_type) if numeric_type.is_float() => {
+                    Some(self.end_token(TokenType::SizedFloatLiteral(value, numeric_type)))
+                },
+                Some(_) if is_decimal => {
+                    self.diagnostics.push(ErrMsg::DecimalLiteralAsInt.make_diagnostic(self.token_range));
+                    None
+                },
+                Some(numeric_type) if whole_part > self.max_magnitude(numeric_type) => {
+                    self.diagnostics.push(ErrMsg::LiteralOutOfRange(whole_part, numeric_type).make_diagnostic(self.token_range));
+                    None
+                },
+                Some(numeric_type) => Some(self.end_token(TokenType::SizedIntLiteral(whole_part, numeric_type))),
+                None => {
+                    self.diagnostics.push(ErrMsg::UnknownLiteralSuffix(suffix).make_diagnostic(self.token_range));
+                    None
+                },
             },
         }
     }
@@ -388,16 +441,24 @@ impl<'ctx> Lexer<'ctx> {
             "or" => self.end_token(TokenType::Or),
             "while" => self.end_token(TokenType::While),
             "break" => self.end_token(TokenType::Break),
+            "continue" => self.end_token(TokenType::Continue),
             "loop" => self.end_token(TokenType::Loop),
             "int" => self.end_token(TokenType::Int),
             "double" => self.end_token(TokenType::Double),
             "bool" => self.end_token(TokenType::Bool),
             "char" => self.end_token(TokenType::Char),
+            "str" => self.end_token(TokenType::Str),
             "let" => self.end_token(TokenType::Let),
+            "const" => self.end_token(TokenType::Const),
+            "static" => self.end_token(TokenType::Static),
+            "pub" => self.end_token(TokenType::Pub),
             "struct" => self.end_token(TokenType::Struct),
             "new" => self.end_token(TokenType::New),
             "as" => self.end_token(TokenType::As),
-            _ => self.end_token(TokenType::Identifier(word)),
+            _ => match NumericType::from_name(&word) {
+                Some(numeric_type) => self.end_token(TokenType::Numeric(numeric_type)),
+                None => self.end_token(TokenType::Identifier(word)),
+            },
         }
     }
     
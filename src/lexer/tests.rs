@@ -1,5 +1,5 @@
 use crate::diagnostic::Diagnostic;
-use crate::lexer::token::{Token, TokenType};
+use crate::lexer::token::{NumericType, Token, TokenType};
 use crate::lexer::token::Positioned;
 use crate::lexer::Lexer;
 use crate::logger::CONSOLE_LOGGER;
@@ -91,7 +91,7 @@ fn test_single_vs_paired_tokens() {
 
 #[test]
 fn test_keywords() {
-    let (tokens, _) = tokenize("delete result impl if else for return fn while break loop let struct new");
+    let (tokens, _) = tokenize("delete result impl if else for return fn while break continue loop let const static pub struct new");
     
     let expected = vec![
         TokenType::Delete,
@@ -104,8 +104,12 @@ fn test_keywords() {
         TokenType::Fn,
         TokenType::While,
         TokenType::Break,
+        TokenType::Continue,
         TokenType::Loop,
         TokenType::Let,
+        TokenType::Const,
+        TokenType::Static,
+        TokenType::Pub,
         TokenType::Struct,
         TokenType::New,
         TokenType::EOF,
@@ -119,13 +123,39 @@ fn test_keywords() {
 
 #[test]
 fn test_type_keywords() {
-    let (tokens, _) = tokenize("int double bool char");
+    let (tokens, _) = tokenize("int double bool char str");
     
     let expected = vec![
         TokenType::Int,
         TokenType::Double,
         TokenType::Bool,
         TokenType::Char,
+        TokenType::Str,
+        TokenType::EOF,
+    ];
+
+    assert_eq!(tokens.len(), expected.len());
+    for (token, expected_type) in tokens.iter().zip(expected.iter()) {
+        assert_eq!(token.value, *expected_type);
+    }
+}
+
+#[test]
+fn test_numeric_type_keywords() {
+    let (tokens, _) = tokenize("i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 i128");
+    
+    let expected = vec![
+        TokenType::Numeric(NumericType::I8),
+        TokenType::Numeric(NumericType::I16),
+        TokenType::Numeric(NumericType::I32),
+        TokenType::Numeric(NumericType::I64),
+        TokenType::Numeric(NumericType::U8),
+        TokenType::Numeric(NumericType::U16),
+        TokenType::Numeric(NumericType::U32),
+        TokenType::Numeric(NumericType::U64),
+        TokenType::Numeric(NumericType::F32),
+        TokenType::Numeric(NumericType::F64),
+        TokenType::Identifier("i128".to_string()),
         TokenType::EOF,
     ];
 
@@ -437,7 +467,7 @@ fn test_unknown_escape_sequence_error() {
 
 #[test]
 fn test_unknown_symbol_error() {
-    let (tokens, diagnostics) = tokenize("@#`");
+    let (tokens, diagnostics) = tokenize("@?`");
     
     assert_eq!(tokens.len(), 1); // Only EOF (unknown symbols prevented token creation)
     assert_eq!(diagnostics.len(), 3); // Three unknown symbols
@@ -446,7 +476,7 @@ fn test_unknown_symbol_error() {
     assert_eq!(diagnostics[0].message, "unknown symbol @");
     assert_eq!(format!("{}", diagnostics[0].position), "1:1-1:1");
     
-    assert_eq!(diagnostics[1].message, "unknown symbol #");
+    assert_eq!(diagnostics[1].message, "unknown symbol ?");
     assert_eq!(format!("{}", diagnostics[1].position), "1:2-1:2");
     
     assert_eq!(diagnostics[2].message, "unknown symbol `");
@@ -1022,6 +1052,28 @@ fn test_bitwise_operators() {
     }
 }
 
+#[test]
+fn test_attribute_tokens() {
+    let (tokens, diagnostics) = tokenize("#[section(\".isr_vector\")]");
+
+    let expected = vec![
+        TokenType::Hash,
+        TokenType::LeftSquare,
+        TokenType::Identifier("section".to_string()),
+        TokenType::LeftParen,
+        TokenType::StringLiteral(".isr_vector".to_string()),
+        TokenType::RightParen,
+        TokenType::RightSquare,
+        TokenType::EOF,
+    ];
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(tokens.len(), expected.len());
+    for (token, expected_type) in tokens.iter().zip(expected.iter()) {
+        assert_eq!(token.value, *expected_type);
+    }
+}
+
 #[test]
 fn test_arithmetic_operators() {
     let (tokens, _) = tokenize("+ - * / %");
@@ -1159,4 +1211,78 @@ fn test_modulo_and_xor_positions() {
     // ^ at 1:3-1:3
     assert_eq!(tokens[1].value, TokenType::Carat);
     assert_eq!(format!("{}", tokens[1].get_position()), "1:3-1:3");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_sized_literal_suffixes() {
+    let (tokens, diagnostics) = tokenize("10u8 0xFFu32 7_i64 255_u8 1.5f32 2f64 0b1010_u16");
+    
+    let expected = vec![
+        TokenType::SizedIntLiteral(10, NumericType::U8),
+        TokenType::SizedIntLiteral(255, NumericType::U32),
+        TokenType::SizedIntLiteral(7, NumericType::I64),
+        TokenType::SizedIntLiteral(255, NumericType::U8),
+        TokenType::SizedFloatLiteral(1.5, NumericType::F32),
+        TokenType::SizedFloatLiteral(2.0, NumericType::F64),
+        TokenType::SizedIntLiteral(10, NumericType::U16),
+        TokenType::EOF,
+    ];
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(tokens.len(), expected.len());
+    for (token, expected_type) in tokens.iter().zip(expected.iter()) {
+        assert_eq!(token.value, *expected_type);
+    }
+}
+
+#[test]
+fn test_sized_literal_max_values() {
+    let (tokens, diagnostics) = tokenize("127i8 65535u16 18446744073709551615u64");
+    
+    assert!(diagnostics.is_empty());
+    assert_eq!(tokens[0].value, TokenType::SizedIntLiteral(127, NumericType::I8));
+    assert_eq!(tokens[1].value, TokenType::SizedIntLiteral(65535, NumericType::U16));
+    assert_eq!(tokens[2].value, TokenType::SizedIntLiteral(u64::MAX, NumericType::U64));
+}
+
+#[test]
+fn test_sized_literal_out_of_range_error() {
+    let (tokens, diagnostics) = tokenize("256u8 128i8");
+    
+    assert_eq!(tokens.len(), 1); // Only EOF
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].message, "literal 256 out of range for 'u8'");
+    assert_eq!(diagnostics[1].message, "literal 128 out of range for 'i8'");
+}
+
+#[test]
+fn test_sized_literal_errors() {
+    let (tokens, diagnostics) = tokenize("1.5u8 10u7");
+    
+    assert_eq!(tokens.len(), 1); // Only EOF
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].message, "decimal literal cannot be used as int");
+    assert_eq!(diagnostics[1].message, "unknown literal suffix: 'u7'");
+    assert_eq!(format!("{}", diagnostics[1].position), "1:7-1:10");
+}
+
+#[test]
+fn test_negated_literal_minimum_values() {
+    let (tokens, diagnostics) = tokenize("-128i8 x = -32768i16; (-9223372036854775808)");
+    
+    assert!(diagnostics.is_empty());
+    assert_eq!(tokens[1].value, TokenType::SizedIntLiteral(128, NumericType::I8));
+    assert_eq!(tokens[5].value, TokenType::SizedIntLiteral(32768, NumericType::I16));
+    assert_eq!(tokens[9].value, TokenType::IntLiteral(i64::MIN));
+}
+
+#[test]
+fn test_subtracted_literal_out_of_range_error() {
+    let (_, diagnostics) = tokenize("x - 128i8 - -129i8 -256u8 y 9223372036854775808");
+    
+    assert_eq!(diagnostics.len(), 4);
+    assert_eq!(diagnostics[0].message, "literal 128 out of range for 'i8'");
+    assert_eq!(diagnostics[1].message, "literal 129 out of range for 'i8'");
+    assert_eq!(diagnostics[2].message, "literal 256 out of range for 'u8'");
+    assert_eq!(diagnostics[3].message, "literal 9223372036854775808 out of range for 'i64'");
+}
@@ -176,6 +176,10 @@ impl PositionRange {
         }
     }
 
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
     pub fn with_end(mut self, position: Position) -> PositionRange {
         self.end = position;
         self
@@ -241,6 +245,77 @@ impl Token {
     }
 }
 
+// Explicitly sized numeric types, used both as type keywords and literal suffixes
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumericType {
+    I8, I16, I32, I64,
+    U8, U16, U32, U64,
+    F32, F64,
+}
+
+impl NumericType {
+    pub fn from_name(name: &str) -> Option<NumericType> {
+        Some(match name {
+            "i8" => NumericType::I8,
+            "i16" => NumericType::I16,
+            "i32" => NumericType::I32,
+            "i64" => NumericType::I64,
+            "u8" => NumericType::U8,
+            "u16" => NumericType::U16,
+            "u32" => NumericType::U32,
+            "u64" => NumericType::U64,
+            "f32" => NumericType::F32,
+            "f64" => NumericType::F64,
+            _ => return None,
+        })
+    }
+
+    pub fn bit_width(&self) -> u32 {
+        match self {
+            NumericType::I8 | NumericType::U8 => 8,
+            NumericType::I16 | NumericType::U16 => 16,
+            NumericType::I32 | NumericType::U32 | NumericType::F32 => 32,
+            NumericType::I64 | NumericType::U64 | NumericType::F64 => 64,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumericType::F32 | NumericType::F64)
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, NumericType::I8 | NumericType::I16 | NumericType::I32 | NumericType::I64 | NumericType::F32 | NumericType::F64)
+    }
+
+    // Largest value an integer literal of this type may be written with
+    pub fn max_literal(&self) -> u64 {
+        if self.is_float() {
+            u64::MAX
+        } else if self.is_signed() {
+            (1u64 << (self.bit_width() - 1)) - 1
+        } else {
+            u64::MAX >> (64 - self.bit_width())
+        }
+    }
+}
+
+impl fmt::Display for NumericType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NumericType::I8 => "i8",
+            NumericType::I16 => "i16",
+            NumericType::I32 => "i32",
+            NumericType::I64 => "i64",
+            NumericType::U8 => "u8",
+            NumericType::U16 => "u16",
+            NumericType::U32 => "u32",
+            NumericType::U64 => "u64",
+            NumericType::F32 => "f32",
+            NumericType::F64 => "f64",
+        };
+        write!(f, "{}", name)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum TokenType {
@@ -248,16 +323,19 @@ pub enum TokenType {
     LeftCurly, RightCurly, LeftSquare, RightSquare, LeftParen, RightParen, From, Require, Scope,
     Plus, Minus, Slash, Star, Ampersand,
     NotEqual, Equal, Greater, GreaterEqual, Less, LessEqual,
-    And, Or, Not, Tilda, Bar, LeftShift, RightShift, As, Extern,
+    And, Or, Not, Tilda, Bar, LeftShift, RightShift, As, Extern, Hash,
 
-    New, Let, Delete,
+    New, Let, Const, Static, Pub, Delete,
     If, Else, For, Fn,
-    While, Loop, Break, Result, Return, Impl,
+    While, Loop, Break, Continue, Result, Return, Impl,
 
-    Int, Double, Bool, Char,
+    Int, Double, Bool, Char, Str,
+    Numeric(NumericType),
     Struct,
 
     IntLiteral(i64), DoubleLiteral(f64), BoolLiteral(bool), CharLiteral(char), StringLiteral(String),
+    // Literals with an explicit width suffix, e.g. `10u8` or `1.5f32`
+    SizedIntLiteral(u64, NumericType), SizedFloatLiteral(f64, NumericType),
     Identifier(String),
 
     // Wildcard types for comparisons
@@ -276,6 +354,7 @@ pub enum TokenType {
     #[allow(dead_code)]
     AnyCharLiteral, 
 
+    AnyNumeric,
     AnyIdentifier,
     EOF
 }
@@ -290,6 +369,9 @@ impl PartialEq for TokenType {
                 (TokenType::BoolLiteral(a), TokenType::BoolLiteral(b)) => a == b,
                 (TokenType::CharLiteral(a), TokenType::CharLiteral(b)) => a == b,
                 (TokenType::StringLiteral(a), TokenType::StringLiteral(b)) => a == b,
+                (TokenType::Numeric(a), TokenType::Numeric(b)) => a == b,
+                (TokenType::SizedIntLiteral(a, a_type), TokenType::SizedIntLiteral(b, b_type)) => a == b && a_type == b_type,
+                (TokenType::SizedFloatLiteral(a, a_type), TokenType::SizedFloatLiteral(b, b_type)) => a == b && a_type == b_type,
                 (TokenType::Identifier(a), TokenType::Identifier(b)) => a == b,
                 _ => true,
             }
@@ -305,6 +387,8 @@ impl PartialEq for TokenType {
                 (TokenType::AnyCharLiteral, TokenType::CharLiteral(_)) => true,
                 (TokenType::StringLiteral(_), TokenType::AnyStringLiteral) => true,
                 (TokenType::AnyStringLiteral, TokenType::StringLiteral(_)) => true,
+                (TokenType::Numeric(_), TokenType::AnyNumeric) => true,
+                (TokenType::AnyNumeric, TokenType::Numeric(_)) => true,
                 (TokenType::Identifier(_), TokenType::AnyIdentifier) => true,
                 (TokenType::AnyIdentifier, TokenType::Identifier(_)) => true,
                 _ => false,
@@ -326,6 +410,7 @@ impl fmt::Display for TokenType {
             TokenType::Carat => "^",
             TokenType::Percentage => "%",
             TokenType::Tilda => "~",
+            TokenType::Hash => "#",
             TokenType::Bar => "|",
             TokenType::Semicolon => ";",
             TokenType::Colon => ":",
@@ -357,6 +442,9 @@ impl fmt::Display for TokenType {
             TokenType::Not => "!",
             TokenType::New => "new",
             TokenType::Let => "let",
+            TokenType::Const => "const",
+            TokenType::Static => "static",
+            TokenType::Pub => "pub",
             TokenType::If => "if",
             TokenType::Else => "else",
             TokenType::For => "for",
@@ -365,6 +453,7 @@ impl fmt::Display for TokenType {
             TokenType::While => "while",
             TokenType::Loop => "loop",
             TokenType::Break => "break",
+            TokenType::Continue => "continue",
             TokenType::Result => "result",
             TokenType::Return => "return",
             TokenType::Impl => "impl",
@@ -372,10 +461,15 @@ impl fmt::Display for TokenType {
             TokenType::Double => "double",
             TokenType::Bool => "bool",
             TokenType::Char => "char",
+            TokenType::Str => "str",
+            TokenType::Numeric(numeric_type) => &numeric_type.to_string(),
+            TokenType::AnyNumeric => "numeric type",
             TokenType::Struct => "struct",
             TokenType::As => "as",
             TokenType::IntLiteral(_) | TokenType::AnyIntLiteral => "integer literal",
             TokenType::DoubleLiteral(_) | TokenType::AnyDoubleLiteral => "double literal",
+            TokenType::SizedIntLiteral(_, _) => "sized integer literal",
+            TokenType::SizedFloatLiteral(_, _) => "sized float literal",
             TokenType::BoolLiteral(_) | TokenType::AnyBoolLiteral => "boolean literal",
             TokenType::CharLiteral(_) | TokenType::AnyCharLiteral => "char literal",
             TokenType::StringLiteral(_) | TokenType::AnyStringLiteral => "string literal",
@@ -2,32 +2,122 @@
 
 use dotenv::dotenv;
 use std::env;
+use std::path::PathBuf;
 use std::process;
 
+use crate::compiler::{Allocator, CompilerOptions, Emit, OptLevel};
+
 mod ast;
-//mod codegen;
+mod codegen;
 mod compiler;
 mod diagnostic;
 mod lexer;
 mod logger;
+mod modules;
 mod parser;
-//mod resolver;
 mod run;
-//mod type_checker;
 
 #[tokio::main]
 async fn main() {
     dotenv().unwrap();
 
-    let mut args: Vec<String> = env::args().collect();
-    args.remove(0);
-    
-    let len = args.len();
+    let mut args = env::args().skip(1);
+
+    let mut options = CompilerOptions::default();
+    let mut allocator = None;
+    let mut files = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-bounds-check" => options.bounds_checks = false,
+            "--sanitize=memory" => options.sanitize_memory = true,
+            "--freestanding" => options.freestanding = true,
+            flag if flag.starts_with("--source-root=") => {
+                options.source_roots.push(PathBuf::from(&flag["--source-root=".len()..]));
+            },
+            flag if flag.starts_with("--allocator=") => {
+                let name = &flag["--allocator=".len()..];
+
+                allocator = Some(Allocator::from_name(name).unwrap_or_else(|| {
+                    eprintln!("Unknown allocator: {} (expected libc or bump)", name);
+                    process::exit(1);
+                }));
+            },
+            flag if flag.starts_with("--heap-size=") => {
+                let size = &flag["--heap-size=".len()..];
+
+                options.heap_size = size.parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid heap size: {}", size);
+                    process::exit(1);
+                });
+            },
+            flag if flag.starts_with("--emit=") => {
+                let name = &flag["--emit=".len()..];
+
+                options.emit = Emit::from_name(name).unwrap_or_else(|| {
+                    eprintln!("Unknown emit kind: {} (expected exe, obj, asm or llvm-ir)", name);
+                    process::exit(1);
+                });
+            },
+            "-o" => {
+                options.output_file = Some(PathBuf::from(args.next().unwrap_or_else(|| {
+                    eprintln!("Missing output file after -o");
+                    process::exit(1);
+                })));
+            },
+            "--target" => {
+                options.target_triple = Some(args.next().unwrap_or_else(|| {
+                    eprintln!("Missing target triple after --target");
+                    process::exit(1);
+                }));
+            },
+            flag if flag.starts_with("--target=") => {
+                options.target_triple = Some(flag["--target=".len()..].to_string());
+            },
+            flag if flag.starts_with("--cpu=") => {
+                options.target_cpu = Some(flag["--cpu=".len()..].to_string());
+            },
+            flag if flag.starts_with("--features=") => {
+                options.target_features = Some(flag["--features=".len()..].to_string());
+            },
+            flag if flag.starts_with("--linker=") => {
+                options.linker = flag["--linker=".len()..].to_string();
+            },
+            flag if flag.starts_with("--link-arg=") => {
+                options.link_args.push(flag["--link-arg=".len()..].to_string());
+            },
+            flag if flag.starts_with("-O") => {
+                options.opt_level = OptLevel::from_flag(flag).unwrap_or_else(|| {
+                    eprintln!("Unknown optimization level: {} (expected -O0, -O1, -O2, -O3 or -Os)", flag);
+                    process::exit(1);
+                });
+            },
+            flag if flag.starts_with("--") => {
+                eprintln!("Unknown option: {}", flag);
+                process::exit(1);
+            },
+            _ => files.push(arg),
+        }
+    }
+
+    // Without a C library there is no malloc, so freestanding builds default to the bump allocator
+    options.allocator = match allocator {
+        Some(Allocator::Libc) if options.freestanding => {
+            eprintln!("--allocator=libc cannot be used with --freestanding");
+            process::exit(1);
+        },
+        Some(allocator) => allocator,
+        None if options.freestanding => Allocator::Bump,
+        None => Allocator::Libc,
+    };
+
+    if files.is_empty() {
+        eprintln!("Usage: untitled [--no-bounds-check] [--sanitize=memory] [--freestanding] [--source-root=<dir>]... [--allocator=libc|bump] [--heap-size=<bytes>] [-O0|-O1|-O2|-O3|-Os] [--emit=exe|obj|asm|llvm-ir] [--target <triple>] [--cpu=<cpu>] [--features=<features>] [--linker=<program>] [--link-arg=<arg>]... [-o <file>] [script]");
+        process::exit(1);
+    }
 
-    if len < 1 {
-        println!("Usage: untitled [script]");
+    // Build scripts only see the exit status, the reasons were already logged
+    if !run::run_files(files, options).await {
         process::exit(1);
-    } else {
-        run::run_files(args).await;
     }
 }
\ No newline at end of file
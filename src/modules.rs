@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::ast::Scope;
+
+// Keeps track of which file declares which scope and what every file requires,
+// so that the driver can load the files of scopes that nobody passed on the
+// command line and report files that end up requiring each other.
+pub struct ModuleGraph {
+    files: Vec<ModuleFile>,
+    // Full scope path to the index of the file declaring it
+    scopes: HashMap<Vec<String>, usize>,
+}
+
+struct ModuleFile {
+    path: PathBuf,
+    requires: Vec<Vec<String>>,
+}
+
+impl ModuleGraph {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            scopes: HashMap::new(),
+        }
+    }
+
+    pub fn add_file(&mut self, path: PathBuf, scope: &Scope) -> usize {
+        let index = self.files.len();
+        let mut requires = Vec::new();
+
+        self.add_scope(index, &[], scope, &mut requires);
+        self.files.push(ModuleFile { path, requires });
+
+        index
+    }
+
+    fn add_scope(&mut self, index: usize, parent_path: &[String], scope: &Scope, requires: &mut Vec<Vec<String>>) {
+        let scope_path = [parent_path, scope.path.segments.as_slice()].concat();
+
+        for require in &scope.requires {
+            requires.push(require.path.segments.clone());
+        }
+
+        for child_scope in &scope.child_scopes {
+            self.add_scope(index, &scope_path, child_scope, requires);
+        }
+
+        // Duplicate scopes are reported once every file is flattened
+        self.scopes.entry(scope_path).or_insert(index);
+    }
+
+    // A require names either a scope or an item declared in one
+    fn owner(&self, path: &[String]) -> Option<usize> {
+        let (_, scope_path) = path.split_last()?;
+
+        self.scopes.get(path).or_else(|| self.scopes.get(scope_path)).copied()
+    }
+
+    pub fn missing_requires(&self, file: usize) -> Vec<&[String]> {
+        self.files[file].requires.iter()
+            .filter(|path| self.owner(path).is_none())
+            .map(|path| path.as_slice())
+            .collect()
+    }
+
+    // `Foo::Bar` may live in `Foo/Bar.mar` or `Foo.mar` under any root, the longest match wins
+    pub fn candidate_files(source_roots: &[PathBuf], path: &[String]) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+
+        for length in (1..=path.len()).rev() {
+            for source_root in source_roots {
+                let mut candidate = source_root.clone();
+                candidate.extend(&path[..length]);
+                candidate.set_extension("mar");
+
+                candidates.push(candidate);
+            }
+        }
+
+        candidates
+    }
+
+    // Every cycle is returned as the chain of files leading back to its first file
+    pub fn find_cycles(&self) -> Vec<Vec<&Path>> {
+        let mut states = vec![VisitState::Unvisited; self.files.len()];
+        let mut stack = Vec::new();
+        let mut cycles = Vec::new();
+
+        for file in 0..self.files.len() {
+            self.visit_file(file, &mut states, &mut stack, &mut cycles);
+        }
+
+        cycles
+    }
+
+    fn visit_file<'a>(&'a self, file: usize, states: &mut [VisitState], stack: &mut Vec<usize>, cycles: &mut Vec<Vec<&'a Path>>) {
+        match states[file] {
+            VisitState::Done => return,
+            VisitState::InProgress => {
+                let start = stack.iter().position(|index| *index == file).unwrap();
+                let chain = stack[start..].iter()
+                    .chain(std::iter::once(&file))
+                    .map(|index| self.files[*index].path.as_path())
+                    .collect();
+
+                cycles.push(chain);
+                return;
+            },
+            VisitState::Unvisited => {},
+        }
+
+        states[file] = VisitState::InProgress;
+        stack.push(file);
+
+        let mut required_files = Vec::new();
+
+        for path in &self.files[file].requires {
+            if let Some(owner) = self.owner(path) && owner != file && !required_files.contains(&owner) {
+                required_files.push(owner);
+            }
+        }
+
+        for required_file in required_files {
+            self.visit_file(required_file, states, stack, cycles);
+        }
+
+        stack.pop();
+        states[file] = VisitState::Done;
+    }
+}
+
+#[derive(Clone, Copy)]
+enum VisitState {
+    Unvisited,
+    InProgress,
+    Done,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::test_util::parse;
+
+    fn graph(files: &[(&str, &str)]) -> ModuleGraph {
+        let mut graph = ModuleGraph::new();
+
+        for (path, code) in files {
+            graph.add_file(PathBuf::from(path), &parse(code));
+        }
+
+        graph
+    }
+
+    #[test]
+    fn test_candidate_files() {
+        let roots = vec![PathBuf::from("src"), PathBuf::from("lib")];
+        let path = vec!["Foo".to_string(), "Bar".to_string()];
+
+        assert_eq!(ModuleGraph::candidate_files(&roots, &path), vec![
+            PathBuf::from("src/Foo/Bar.mar"),
+            PathBuf::from("lib/Foo/Bar.mar"),
+            PathBuf::from("src/Foo.mar"),
+            PathBuf::from("lib/Foo.mar"),
+        ]);
+    }
+
+    #[test]
+    fn test_missing_requires() {
+        let graph = graph(&[
+            ("Main.mar", "scope Main { require Main::Inner, Util::clamp, Foo::Bar; from Util require *; scope Inner {} }"),
+            ("Util.mar", "scope Util { require Main::Inner::x; }"),
+        ]);
+
+        assert_eq!(graph.missing_requires(0), vec![["Foo".to_string(), "Bar".to_string()].as_slice()]);
+        assert!(graph.missing_requires(1).is_empty());
+    }
+
+    #[test]
+    fn test_no_cycle_within_a_file() {
+        let graph = graph(&[
+            ("Main.mar", "scope Main { require Main::Inner; scope Inner { require Main::Inner::x; } }"),
+            ("Util.mar", "scope Util { require Main; }"),
+        ]);
+
+        assert!(graph.find_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_import_cycle_chain() {
+        let graph = graph(&[
+            ("Main.mar", "scope Main { require A; }"),
+            ("A.mar", "scope A { require B::x; }"),
+            ("B.mar", "scope B { from Main require *; }"),
+        ]);
+
+        let cycles = graph.find_cycles();
+
+        assert_eq!(cycles, vec![vec![Path::new("Main.mar"), Path::new("A.mar"), Path::new("B.mar"), Path::new("Main.mar")]]);
+    }
+}
@@ -218,7 +218,7 @@ impl<'ctx> ExprParser<'ctx> {
     pub fn parse(mut self) -> Option<Scope> {
         self.log_info(self.log_target, "Beginning parser");
 
-        let scope = self.apply_rule(ScopeRule {}, "top-level scope", None);
+        let scope = self.apply_rule(ScopeRule {}, "top-level scope", Some(ErrMsg::ExpectedToken(TokenType::Scope)));
 
         self.log_info(self.log_target, "Parser finished");
 
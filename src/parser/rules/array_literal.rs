@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::ast::ArrayLiteral;
+use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
+use crate::parser::rules::expr::ExprRule;
+use crate::lexer::token::TokenType;
+
+pub struct ArrayLiteralRule {}
+
+impl fmt::Display for ArrayLiteralRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArrayLiteral")
+    }
+}
+
+impl ParseRule<ArrayLiteral> for ArrayLiteralRule {
+    fn check_match(&self, mut cursor: ParserCursor) -> bool {
+        cursor.try_consume(TokenType::LeftSquare).is_some()
+    }
+
+    fn parse(&self, parser: &mut ExprParser) -> Option<ArrayLiteral> {
+        parser.begin_range();
+        parser.try_consume(TokenType::LeftSquare)?;
+
+        let mut elements = Vec::new();
+
+        if parser.try_consume(TokenType::RightSquare).is_none() {
+            let element = parser.apply_rule(ExprRule {}, "first array element", None)?;
+            elements.push(element);
+
+            while parser.try_consume(TokenType::Comma).is_some() {
+                let element = parser.apply_rule(ExprRule {}, "array element", None)?;
+                elements.push(element);
+            }
+
+            parser.consume_or_diagnostic(TokenType::RightSquare);
+        }
+
+        Some(ArrayLiteral::new(elements, parser.end_range()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::{Token, TokenType, PositionRange};
+    use crate::logger::CONSOLE_LOGGER;
+
+    fn create_token(token_type: TokenType) -> Token {
+        Token::new(token_type, PositionRange::zero())
+    }
+
+    #[test]
+    fn test_array_literal_rule_check_match_with_left_square() {
+        let rule = ArrayLiteralRule {};
+        let tokens = vec![
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::EOF),
+        ];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+
+        assert!(rule.check_match(cursor));
+    }
+
+    #[test]
+    fn test_array_literal_rule_check_match_without_left_square() {
+        let rule = ArrayLiteralRule {};
+        let tokens = vec![
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::EOF),
+        ];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+
+        assert!(!rule.check_match(cursor));
+    }
+
+    #[test]
+    fn test_parse_array_literal() {
+        let rule = ArrayLiteralRule {};
+        let tokens = vec![
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::Comma),
+            create_token(TokenType::IntLiteral(2)),
+            create_token(TokenType::Comma),
+            create_token(TokenType::IntLiteral(3)),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let result = rule.parse(&mut parser);
+
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().elements.len(), 3);
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for array literal");
+    }
+
+    #[test]
+    fn test_parse_empty_array_literal() {
+        let rule = ArrayLiteralRule {};
+        let tokens = vec![
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let result = rule.parse(&mut parser);
+
+        assert!(result.is_some());
+        assert!(result.unwrap().elements.is_empty());
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for empty array literal");
+    }
+
+    #[test]
+    fn test_parse_array_literal_missing_right_square() {
+        let rule = ArrayLiteralRule {};
+        let tokens = vec![
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        rule.parse(&mut parser);
+
+        assert!(!diagnostics.is_empty(), "Expected diagnostic for missing ']'");
+    }
+}
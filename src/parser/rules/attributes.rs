@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::ast::Attribute;
+use crate::diagnostic::ErrMsg;
+use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
+use crate::lexer::token::{Located, PositionRange, Positioned, TokenType};
+
+pub struct AttributesRule {}
+
+impl fmt::Display for AttributesRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Attributes")
+    }
+}
+
+// Moves the cursor past any `#[...]` in front of an item, so that item rules can match on what follows
+pub fn skip_attributes(cursor: &mut ParserCursor) {
+    while cursor.try_consume(TokenType::Hash).is_some() {
+        while !cursor.is_at_end() && cursor.next().value != TokenType::RightSquare {}
+    }
+}
+
+impl ParseRule<Vec<Located<Attribute>>> for AttributesRule {
+    fn check_match(&self, _cursor: ParserCursor) -> bool {
+        true
+    }
+
+    // Items without any attributes get an empty list
+    fn parse(&self, parser: &mut ExprParser) -> Option<Vec<Located<Attribute>>> {
+        let mut attributes = Vec::new();
+
+        while let Some(hash) = parser.try_consume(TokenType::Hash) {
+            parser.consume_or_diagnostic(TokenType::LeftSquare)?;
+            let name = parser.consume_or_diagnostic(TokenType::AnyIdentifier)?.unwrap_identifier();
+
+            let attribute = match name.as_str() {
+                "entry" => Some(Attribute::Entry),
+                "section" => {
+                    parser.consume_or_diagnostic(TokenType::LeftParen)?;
+                    let section = parser.consume_or_diagnostic(TokenType::AnyStringLiteral)?.unwrap_string_literal();
+                    parser.consume_or_diagnostic(TokenType::RightParen)?;
+
+                    Some(Attribute::Section(section.to_string()))
+                },
+                _ => {
+                    parser.push_diagnostic(ErrMsg::UnknownAttribute(name.to_string()).make_diagnostic(*name.get_position()));
+
+                    // Skip whatever arguments it has, the item itself can still be parsed
+                    while !parser.is_at_end() && parser.try_match(&[TokenType::RightSquare]).is_none() {
+                        parser.next();
+                    }
+
+                    None
+                },
+            };
+
+            let end = parser.consume_or_diagnostic(TokenType::RightSquare)?;
+
+            if let Some(attribute) = attribute {
+                attributes.push(Located::new(attribute, PositionRange::concat(hash.get_position(), end.get_position())));
+            }
+        }
+
+        Some(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::Token;
+    use crate::logger::CONSOLE_LOGGER;
+
+    fn create_token(token_type: TokenType) -> Token {
+        Token::new(token_type, PositionRange::zero())
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let rule = AttributesRule {};
+        let tokens = vec![
+            create_token(TokenType::Hash),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::Identifier("entry".to_string())),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::Hash),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::Identifier("section".to_string())),
+            create_token(TokenType::LeftParen),
+            create_token(TokenType::StringLiteral(".isr_vector".to_string())),
+            create_token(TokenType::RightParen),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::Fn),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let attributes = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(*attributes[0], Attribute::Entry);
+        assert_eq!(*attributes[1], Attribute::Section(".isr_vector".to_string()));
+        assert_eq!(parser.cur().value, TokenType::Fn);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_unknown_attribute() {
+        let rule = AttributesRule {};
+        let tokens = vec![
+            create_token(TokenType::Hash),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::Identifier("inline".to_string())),
+            create_token(TokenType::LeftParen),
+            create_token(TokenType::Identifier("always".to_string())),
+            create_token(TokenType::RightParen),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::Fn),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let attributes = rule.parse(&mut parser).unwrap();
+
+        assert!(attributes.is_empty());
+        assert_eq!(parser.cur().value, TokenType::Fn);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "unknown attribute: 'inline'");
+    }
+}
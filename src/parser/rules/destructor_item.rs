@@ -0,0 +1,30 @@
+use std::fmt;
+
+use crate::ast::ConstructorItem;
+use crate::diagnostic::ErrMsg;
+use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
+use crate::parser::rules::block::BlockRule;
+use crate::lexer::token::TokenType;
+
+pub struct DestructorRule {}
+
+impl fmt::Display for DestructorRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Destructor")
+    }
+}
+
+impl ParseRule<ConstructorItem> for DestructorRule {
+    fn check_match(&self, mut cursor: ParserCursor) -> bool {
+        cursor.try_consume(TokenType::Tilda).is_some()
+    }
+
+    fn parse(&self, parser: &mut ExprParser) -> Option<ConstructorItem> {
+        parser.begin_range();
+        parser.try_consume(TokenType::Tilda)?;
+
+        let body = parser.apply_rule(BlockRule {}, "destructor body", Some(ErrMsg::ExpectedBlock))?;
+
+        Some(ConstructorItem::new(Vec::new(), body, parser.end_range()))
+    }
+}
@@ -16,20 +16,21 @@ impl fmt::Display for ExitRule {
 
 impl ParseRule<ExitExpr> for ExitRule {
     fn check_match(&self, cursor: ParserCursor) -> bool {
-        cursor.try_match(&[TokenType::Break, TokenType::Return, TokenType::Result]).is_some()
+        cursor.try_match(&[TokenType::Break, TokenType::Continue, TokenType::Return, TokenType::Result]).is_some()
     }
 
     fn parse(&self, parser: &mut ExprParser) -> Option<ExitExpr> {
         parser.begin_range();
 
-        let exit_type = match parser.try_consume_match(&[TokenType::Break, TokenType::Return, TokenType::Result])?.value {
+        let exit_type = match parser.try_consume_match(&[TokenType::Break, TokenType::Continue, TokenType::Return, TokenType::Result])?.value {
             TokenType::Break => ExitType::Break,
+            TokenType::Continue => ExitType::Continue,
             TokenType::Return => ExitType::Return,
             TokenType::Result => ExitType::Result,
             _ => return None,
         };
 
-        let label = if exit_type == ExitType::Break || exit_type == ExitType::Result {
+        let label = if exit_type != ExitType::Return {
             match parser.try_consume(TokenType::Colon) {
                 Some(_) => Some(parser.consume_or_diagnostic(TokenType::AnyIdentifier)?.unwrap_identifier()),
                 _ => None
@@ -38,6 +39,13 @@ impl ParseRule<ExitExpr> for ExitRule {
             None
         };
 
+        // continue never carries a value
+        if exit_type == ExitType::Continue {
+            parser.consume_or_diagnostic(TokenType::Semicolon)?;
+
+            return Some(ExitExpr::new(exit_type, None, label, parser.end_range()));
+        }
+
         let expr = if parser.try_consume(TokenType::Semicolon).is_none() {
             let result = parser.apply_rule(ExprRule {}, "exit expression", Some(ErrMsg::ExpectedExpression))?;
             parser.consume_or_diagnostic(TokenType::Semicolon);
@@ -614,4 +622,77 @@ mod tests {
         assert!(result.is_some());
         assert!(diagnostics.is_empty(), "Expected no diagnostics for return with logical OR");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_exit_rule_check_match_with_continue() {
+        let rule = ExitRule {};
+        let tokens = vec![
+            create_token(TokenType::Continue),
+            create_token(TokenType::EOF),
+        ];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+        
+        assert!(rule.check_match(cursor));
+    }
+
+    #[test]
+    fn test_parse_continue() {
+        let rule = ExitRule {};
+        let tokens = vec![
+            create_token(TokenType::Continue),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let exit_expr = result.unwrap();
+        assert!(exit_expr.exit_type == ExitType::Continue);
+        assert!(exit_expr.label.is_none());
+        assert!(exit_expr.expr.is_none());
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for continue");
+    }
+
+    #[test]
+    fn test_parse_continue_with_label() {
+        let rule = ExitRule {};
+        let tokens = vec![
+            create_token(TokenType::Continue),
+            create_token(TokenType::Colon),
+            create_token(TokenType::Identifier("outer".to_string())),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let exit_expr = result.unwrap();
+        assert!(exit_expr.exit_type == ExitType::Continue);
+        assert_eq!(exit_expr.label.as_ref().map(|label| label.to_string()), Some("outer".to_string()));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for labeled continue");
+    }
+
+    #[test]
+    fn test_parse_continue_with_value() {
+        let rule = ExitRule {};
+        let tokens = vec![
+            create_token(TokenType::Continue),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_none());
+        assert!(!diagnostics.is_empty(), "Expected diagnostic for continue with a value");
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt;
 use crate::ast::{FunctionItem, ParsedType, ParsedTypeEnum};
 use crate::diagnostic::ErrMsg;
 use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
-use crate::parser::rules::{block::BlockRule, parameters::ParametersRule, parsed_type::ParsedTypeRule};
+use crate::parser::rules::{attributes::{AttributesRule, skip_attributes}, block::BlockRule, parameters::ParametersRule, parsed_type::ParsedTypeRule, visibility::VisibilityRule};
 use crate::lexer::token::{PositionRange, TokenType};
 
 pub struct FunctionRule;
@@ -16,6 +16,9 @@ impl fmt::Display for FunctionRule {
 
 impl ParseRule<FunctionItem> for FunctionRule {
     fn check_match(&self, mut cursor: ParserCursor) -> bool {
+        skip_attributes(&mut cursor);
+        cursor.try_consume(TokenType::Pub);
+
         cursor.try_consume(TokenType::Fn).is_some()
          || cursor.try_consume(TokenType::Extern).is_some()
     }
@@ -23,6 +26,9 @@ impl ParseRule<FunctionItem> for FunctionRule {
     fn parse(&self, parser: &mut ExprParser) -> Option<FunctionItem> {
         parser.begin_range();
 
+        let attributes = parser.apply_rule(AttributesRule {}, "function attributes", None)?;
+
+        let visibility = parser.apply_rule(VisibilityRule {}, "function visibility", None)?;
         let is_extern = parser.try_consume(TokenType::Extern).is_some();
 
         parser.try_consume(TokenType::Fn)?;
@@ -45,13 +51,14 @@ impl ParseRule<FunctionItem> for FunctionRule {
             None
         };
 
-        Some(FunctionItem::new(name, parameters, ret_type, block, parser.end_range()))
+        Some(FunctionItem::new(attributes, visibility, name, parameters, ret_type, block, parser.end_range()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::Visibility;
     use crate::lexer::token::{Token, TokenType, PositionRange};
     use crate::logger::CONSOLE_LOGGER;
     use crate::parser::ExprParser;
@@ -120,6 +127,7 @@ mod tests {
         assert!(result.is_some());
         let function = result.unwrap();
         assert_eq!(*function.name, "test");
+        assert_eq!(function.visibility, Visibility::Private);
     }
 
     #[test]
@@ -221,4 +229,25 @@ mod tests {
         
         assert!(result.is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_pub_function() {
+        let mut parser = create_parser_with_tokens(vec![
+            TokenType::Pub,
+            TokenType::Fn,
+            TokenType::Identifier("helper".to_string()),
+            TokenType::LeftParen,
+            TokenType::RightParen,
+            TokenType::LeftCurly,
+            TokenType::RightCurly,
+            TokenType::EOF,
+        ]);
+        let rule = FunctionRule;
+        assert!(rule.check_match(parser.get_cursor()));
+
+        let function = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(*function.name, "helper");
+        assert_eq!(function.visibility, Visibility::Public);
+    }
+}
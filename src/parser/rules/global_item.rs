@@ -0,0 +1,196 @@
+use std::fmt;
+
+use crate::ast::{GlobalItem, GlobalKind};
+use crate::diagnostic::ErrMsg;
+use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
+use crate::parser::rules::{attributes::{AttributesRule, skip_attributes}, expr::ExprRule, parsed_type::ParsedTypeRule, visibility::VisibilityRule};
+use crate::lexer::token::TokenType;
+
+pub struct GlobalRule {}
+
+impl fmt::Display for GlobalRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Global")
+    }
+}
+
+impl ParseRule<GlobalItem> for GlobalRule {
+    fn check_match(&self, mut cursor: ParserCursor) -> bool {
+        skip_attributes(&mut cursor);
+        cursor.try_consume(TokenType::Pub);
+
+        cursor.try_consume_match(&[TokenType::Const, TokenType::Static]).is_some()
+    }
+
+    fn parse(&self, parser: &mut ExprParser) -> Option<GlobalItem> {
+        parser.begin_range();
+
+        let attributes = parser.apply_rule(AttributesRule {}, "global attributes", None)?;
+
+        let visibility = parser.apply_rule(VisibilityRule {}, "global visibility", None)?;
+        let kind = match parser.try_consume_match(&[TokenType::Const, TokenType::Static])?.value {
+            TokenType::Const => GlobalKind::Const,
+            _ => GlobalKind::Static,
+        };
+
+        let name = parser.consume_or_diagnostic(TokenType::AnyIdentifier)?.unwrap_identifier();
+
+        parser.consume_or_diagnostic(TokenType::Colon)?;
+        let declaration_type = parser.apply_rule(ParsedTypeRule {}, "global type", Some(ErrMsg::ExpectedType))?;
+
+        // Globals are always initialized, there is no point at which to assign them
+        parser.consume_or_diagnostic(TokenType::Assignment)?;
+        let expr = parser.apply_rule(ExprRule {}, "global initializer", Some(ErrMsg::ExpectedExpression))?;
+
+        parser.consume_or_diagnostic(TokenType::Semicolon);
+
+        Some(GlobalItem::new(attributes, visibility, kind, name, declaration_type, expr, parser.end_range()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{ASTEnum, ParsedTypeEnum, Visibility};
+    use crate::lexer::token::{Token, PositionRange};
+    use crate::logger::CONSOLE_LOGGER;
+
+    fn create_token(token_type: TokenType) -> Token {
+        Token::new(token_type, PositionRange::zero())
+    }
+
+    #[test]
+    fn test_global_rule_check_match() {
+        let rule = GlobalRule {};
+
+        for token_type in [TokenType::Const, TokenType::Static] {
+            let tokens = vec![create_token(token_type), create_token(TokenType::EOF)];
+            let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+
+            assert!(rule.check_match(cursor));
+        }
+
+        let tokens = vec![create_token(TokenType::Let), create_token(TokenType::EOF)];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+
+        assert!(!rule.check_match(cursor));
+    }
+
+    #[test]
+    fn test_parse_const() {
+        let rule = GlobalRule {};
+        let tokens = vec![
+            create_token(TokenType::Const),
+            create_token(TokenType::Identifier("PI".to_string())),
+            create_token(TokenType::Colon),
+            create_token(TokenType::Double),
+            create_token(TokenType::Assignment),
+            create_token(TokenType::DoubleLiteral(2.5)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let global = rule.parse(&mut parser).unwrap();
+
+        assert!(global.kind == GlobalKind::Const);
+        assert_eq!(*global.name, "PI");
+        assert_eq!(global.declaration_type.parsed_type, ParsedTypeEnum::Double);
+        assert!(matches!(global.expr, ASTEnum::Literal(_)));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for valid const item");
+    }
+
+    #[test]
+    fn test_parse_static() {
+        let rule = GlobalRule {};
+        let tokens = vec![
+            create_token(TokenType::Static),
+            create_token(TokenType::Identifier("counter".to_string())),
+            create_token(TokenType::Colon),
+            create_token(TokenType::Int),
+            create_token(TokenType::Assignment),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::Plus),
+            create_token(TokenType::IntLiteral(2)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let global = rule.parse(&mut parser).unwrap();
+
+        assert!(global.kind == GlobalKind::Static);
+        assert_eq!(*global.name, "counter");
+        assert!(matches!(global.expr, ASTEnum::Binary(_)));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for valid static item");
+    }
+
+    #[test]
+    fn test_parse_global_missing_initializer() {
+        let rule = GlobalRule {};
+        let tokens = vec![
+            create_token(TokenType::Const),
+            create_token(TokenType::Identifier("BASE".to_string())),
+            create_token(TokenType::Colon),
+            create_token(TokenType::Numeric(crate::lexer::token::NumericType::U32)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let result = rule.parse(&mut parser);
+
+        assert!(result.is_none());
+        assert!(!diagnostics.is_empty(), "Expected a diagnostic for missing initializer");
+    }
+
+    #[test]
+    fn test_parse_global_missing_type() {
+        let rule = GlobalRule {};
+        let tokens = vec![
+            create_token(TokenType::Static),
+            create_token(TokenType::Identifier("counter".to_string())),
+            create_token(TokenType::Assignment),
+            create_token(TokenType::IntLiteral(0)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let result = rule.parse(&mut parser);
+
+        assert!(result.is_none());
+        assert!(!diagnostics.is_empty(), "Expected a diagnostic for missing type");
+    }
+
+    #[test]
+    fn test_parse_pub_const() {
+        let rule = GlobalRule {};
+        let tokens = vec![
+            create_token(TokenType::Pub),
+            create_token(TokenType::Const),
+            create_token(TokenType::Identifier("SIZE".to_string())),
+            create_token(TokenType::Colon),
+            create_token(TokenType::Int),
+            create_token(TokenType::Assignment),
+            create_token(TokenType::IntLiteral(4)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+        assert!(rule.check_match(cursor));
+
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens.clone(), &mut diagnostics);
+
+        let global = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(global.visibility, Visibility::Public);
+        assert!(global.kind == GlobalKind::Const);
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for pub const");
+    }
+}
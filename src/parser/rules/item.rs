@@ -4,7 +4,7 @@ use crate::ast::ASTEnum;
 use crate::parser::{ExprParser, ParseRule, ParserCursor};
 use crate::parser::rules::{impl_block::ImplBlockRule};
 
-use super::{function_item::FunctionRule, struct_item::StructRule};
+use super::{function_item::FunctionRule, global_item::GlobalRule, struct_item::StructRule};
 
 pub struct ItemRule {}
 
@@ -19,6 +19,7 @@ impl ParseRule<ASTEnum> for ItemRule {
         (FunctionRule {}).check_match(cursor)
             || (StructRule {}).check_match(cursor)
             || (ImplBlockRule {}).check_match(cursor)
+            || (GlobalRule {}).check_match(cursor)
     }
 
     fn parse(&self, parser: &mut ExprParser) -> Option<ASTEnum> {
@@ -34,6 +35,10 @@ impl ParseRule<ASTEnum> for ItemRule {
             return parser.apply_rule_boxed(ImplBlockRule {}, "impl item", None);
         }
 
+        if (GlobalRule {}).check_match(parser.get_cursor()) {
+            return parser.apply_rule_boxed(GlobalRule {}, "global item", None);
+        }
+
         None
     }
 }
@@ -84,6 +89,18 @@ mod tests {
         assert!(rule.check_match(cursor));
     }
 
+    #[test]
+    fn test_item_rule_check_match_global() {
+        let rule = ItemRule {};
+        let tokens = vec![
+            create_token(TokenType::Const),
+            create_token(TokenType::EOF),
+        ];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+        
+        assert!(rule.check_match(cursor));
+    }
+
     #[test]
     fn test_item_rule_check_match_no_match() {
         let rule = ItemRule {};
@@ -1,6 +1,8 @@
 pub mod new_array;
 pub mod arguments;
+pub mod array_literal;
 pub mod assignment;
+pub mod attributes;
 pub mod binary_expr;
 pub mod block;
 pub mod cast;
@@ -10,16 +12,21 @@ pub mod delete_expr;
 pub mod expr;
 pub mod for_loop;
 pub mod function_item;
+pub mod global_item;
 pub mod if_block;
 pub mod item;
+pub mod lambda;
 pub mod loop_expr;
 pub mod primary;
 pub mod path;
 pub mod statement;
 pub mod constructor_item;
+pub mod destructor_item;
 pub mod struct_item;
+pub mod struct_literal;
 pub mod unary;
 pub mod var;
+pub mod visibility;
 pub mod while_loop;
 pub mod parsed_type;
 pub mod parameters;
@@ -4,6 +4,7 @@ use crate::ast::{ParsedType, ParsedTypeEnum};
 use crate::diagnostic::ErrMsg;
 use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
 use crate::parser::rules::parsed_unit_type::ParsedUnitTypeRule;
+use crate::parser::rules::path::PathRule;
 use crate::lexer::token::TokenType;
 
 pub struct ParsedTypeRule {}
@@ -25,12 +26,25 @@ impl ParseRule<ParsedType> for ParsedTypeRule {
 
 
         while parser.try_consume(TokenType::LeftSquare).is_some() {
+            let length = parser.try_consume(TokenType::AnyIntLiteral)
+                .map(|token| token.unwrap_int_literal());
+
+            // The length may also name a constant, which is folded once constants are evaluated
+            let constant = match length {
+                None if parser.try_match(&[TokenType::AnyIdentifier]).is_some() => Some(parser.apply_rule(PathRule {}, "array length", None)?.into_inner()),
+                _ => None,
+            };
+
             parser.consume_or_diagnostic(TokenType::RightSquare);
 
-            unit_type = ParsedType::new(
-                ParsedTypeEnum::Array(Box::new(unit_type)),
-                parser.current_range(),
-            );
+            // `T[N]` is a fixed-size array, `T[]` a slice that carries its length
+            let array_type = match (length, constant) {
+                (Some(length), _) => ParsedTypeEnum::FixedArray(Box::new(unit_type), *length as usize),
+                (None, Some(constant)) => ParsedTypeEnum::ConstArray(Box::new(unit_type), constant),
+                (None, None) => ParsedTypeEnum::Array(Box::new(unit_type)),
+            };
+
+            unit_type = ParsedType::new(array_type, parser.current_range());
 
             if parser.try_consume(TokenType::Ampersand).is_some() {
                 unit_type = ParsedType::new(
@@ -48,7 +62,7 @@ impl ParseRule<ParsedType> for ParsedTypeRule {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::lexer::token::{Token, TokenType, PositionRange};
+    use crate::lexer::token::{NumericType, Token, TokenType, PositionRange};
     use crate::logger::CONSOLE_LOGGER;
 
     fn create_token(token_type: TokenType) -> Token {
@@ -617,4 +631,106 @@ mod tests {
         assert!(matches!(current, ParsedTypeEnum::Integer));
         assert!(diagnostics.is_empty(), "Expected no diagnostics for array sequence");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_fixed_array_type() {
+        let rule = ParsedTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Int),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::IntLiteral(4)),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        if let ParsedTypeEnum::FixedArray(inner, length) = &parsed_type.parsed_type {
+            assert!(matches!(inner.parsed_type, ParsedTypeEnum::Integer));
+            assert_eq!(*length, 4);
+        } else {
+            panic!("Expected FixedArray variant");
+        }
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for fixed array type");
+    }
+
+    #[test]
+    fn test_parse_slice_of_fixed_arrays() {
+        let rule = ParsedTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Char),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::IntLiteral(8)),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        // Should be Array(FixedArray(Char, 8))
+        if let ParsedTypeEnum::Array(inner) = &parsed_type.parsed_type {
+            assert!(matches!(&inner.parsed_type, ParsedTypeEnum::FixedArray(element, 8) if element.parsed_type == ParsedTypeEnum::Char));
+        } else {
+            panic!("Expected Array variant");
+        }
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for slice of fixed arrays");
+    }
+
+    #[test]
+    fn test_parse_fixed_array_with_non_literal_length() {
+        let rule = ParsedTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Int),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::Plus),
+            create_token(TokenType::IntLiteral(2)),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        rule.parse(&mut parser);
+        
+        assert!(!diagnostics.is_empty(), "Expected diagnostic for non-literal array length");
+    }
+
+    #[test]
+    fn test_parse_fixed_array_with_constant_length() {
+        let rule = ParsedTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Numeric(NumericType::U8)),
+            create_token(TokenType::LeftSquare),
+            create_token(TokenType::Identifier("Config".to_string())),
+            create_token(TokenType::DoubleColon),
+            create_token(TokenType::Identifier("BUFFER_SIZE".to_string())),
+            create_token(TokenType::RightSquare),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        if let ParsedTypeEnum::ConstArray(inner, length) = &parsed_type.parsed_type {
+            assert!(matches!(inner.parsed_type, ParsedTypeEnum::Numeric(NumericType::U8)));
+            assert_eq!(length.to_string(), "Config::BUFFER_SIZE");
+        } else {
+            panic!("Expected ConstArray variant");
+        }
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for constant array length");
+    }
+}
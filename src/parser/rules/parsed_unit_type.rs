@@ -1,8 +1,10 @@
 use std::fmt;
 
 use crate::ast::{ParsedType, ParsedTypeEnum};
+use crate::diagnostic::ErrMsg;
 use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
-use crate::lexer::token::{Positioned, TokenType};
+use crate::parser::rules::parsed_type::ParsedTypeRule;
+use crate::lexer::token::{PositionRange, Positioned, TokenType};
 
 pub struct ParsedUnitTypeRule {}
 
@@ -19,7 +21,10 @@ impl ParseRule<ParsedType> for ParsedUnitTypeRule {
             TokenType::Double,
             TokenType::Bool,
             TokenType::Char,
+            TokenType::Str,
+            TokenType::AnyNumeric,
             TokenType::Void,
+            TokenType::Fn,
             TokenType::AnyIdentifier,
         ]).is_some()
     }
@@ -33,10 +38,35 @@ impl ParseRule<ParsedType> for ParsedUnitTypeRule {
             TokenType::Double => ParsedType::new(ParsedTypeEnum::Double, *cur.get_position()),
             TokenType::Bool => ParsedType::new(ParsedTypeEnum::Boolean, *cur.get_position()),
             TokenType::Char => ParsedType::new(ParsedTypeEnum::Char, *cur.get_position()),
+            TokenType::Str => ParsedType::new(ParsedTypeEnum::Str, *cur.get_position()),
+            TokenType::Numeric(numeric_type) => ParsedType::new(ParsedTypeEnum::Numeric(numeric_type), *cur.get_position()),
             TokenType::Void => ParsedType::new(ParsedTypeEnum::Void, *cur.get_position()),
             TokenType::Identifier(ref type_name) => {
                 ParsedType::new(ParsedTypeEnum::TypeName(type_name.to_string()), *cur.get_position())
             }
+            TokenType::Fn => {
+                parser.consume_or_diagnostic(TokenType::LeftParen)?;
+
+                let mut param_types = Vec::new();
+
+                if parser.cur().value != TokenType::RightParen {
+                    param_types.push(parser.apply_rule(ParsedTypeRule {}, "first function type parameter", Some(ErrMsg::ExpectedType))?);
+                }
+
+                while parser.try_consume(TokenType::Comma).is_some() {
+                    param_types.push(parser.apply_rule(ParsedTypeRule {}, "function type parameter", Some(ErrMsg::ExpectedType))?);
+                }
+
+                parser.consume_or_diagnostic(TokenType::RightParen)?;
+
+                let return_type = if parser.try_consume(TokenType::Arrow).is_some() {
+                    parser.apply_rule(ParsedTypeRule {}, "function type return type", Some(ErrMsg::ExpectedType))?
+                } else {
+                    ParsedType::new(ParsedTypeEnum::Void, PositionRange::zero())
+                };
+
+                ParsedType::new(ParsedTypeEnum::Function(param_types, Box::new(return_type)), parser.current_range())
+            }
             _ => {
                 return None;
             }
@@ -59,7 +89,7 @@ impl ParseRule<ParsedType> for ParsedUnitTypeRule {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::lexer::token::{Token, TokenType, PositionRange};
+    use crate::lexer::token::{NumericType, Token, TokenType, PositionRange};
     use crate::logger::CONSOLE_LOGGER;
 
     fn create_token(token_type: TokenType) -> Token {
@@ -210,6 +240,47 @@ mod tests {
         assert!(diagnostics.is_empty(), "Expected no diagnostics for char type");
     }
 
+    #[test]
+    fn test_parse_str_type() {
+        let rule = ParsedUnitTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Str),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        assert!(matches!(parsed_type.parsed_type, ParsedTypeEnum::Str));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for str type");
+    }
+
+    #[test]
+    fn test_parse_numeric_type() {
+        let rule = ParsedUnitTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Numeric(NumericType::U32)),
+            create_token(TokenType::Star),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        if let ParsedTypeEnum::Pointer(inner) = &parsed_type.parsed_type {
+            assert!(matches!(inner.parsed_type, ParsedTypeEnum::Numeric(NumericType::U32)));
+        } else {
+            panic!("Expected Pointer variant");
+        }
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for numeric type");
+    }
+
     #[test]
     fn test_parse_custom_type() {
         let rule = ParsedUnitTypeRule {};
@@ -454,6 +525,64 @@ mod tests {
         assert!(diagnostics.is_empty(), "Expected no diagnostics for custom type with pointers and reference");
     }
 
+    #[test]
+    fn test_parse_function_type() {
+        let rule = ParsedUnitTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Fn),
+            create_token(TokenType::LeftParen),
+            create_token(TokenType::Int),
+            create_token(TokenType::Comma),
+            create_token(TokenType::Char),
+            create_token(TokenType::Star),
+            create_token(TokenType::RightParen),
+            create_token(TokenType::Arrow),
+            create_token(TokenType::Bool),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        if let ParsedTypeEnum::Function(param_types, return_type) = &parsed_type.parsed_type {
+            assert_eq!(param_types.len(), 2);
+            assert!(matches!(param_types[0].parsed_type, ParsedTypeEnum::Integer));
+            assert!(matches!(param_types[1].parsed_type, ParsedTypeEnum::Pointer(_)));
+            assert!(matches!(return_type.parsed_type, ParsedTypeEnum::Boolean));
+        } else {
+            panic!("Expected Function variant");
+        }
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for function type");
+    }
+
+    #[test]
+    fn test_parse_function_type_without_return_type() {
+        let rule = ParsedUnitTypeRule {};
+        let tokens = vec![
+            create_token(TokenType::Fn),
+            create_token(TokenType::LeftParen),
+            create_token(TokenType::RightParen),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+        
+        let result = rule.parse(&mut parser);
+        
+        assert!(result.is_some());
+        let parsed_type = result.unwrap();
+        if let ParsedTypeEnum::Function(param_types, return_type) = &parsed_type.parsed_type {
+            assert!(param_types.is_empty());
+            assert!(matches!(return_type.parsed_type, ParsedTypeEnum::Void));
+        } else {
+            panic!("Expected Function variant");
+        }
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for void function type");
+    }
+
     #[test]
     fn test_parse_invalid_token() {
         let rule = ParsedUnitTypeRule {};
@@ -3,7 +3,7 @@ use std::fmt;
 use crate::ast::{Path, Require};
 use crate::parser::rules::path::PathRule;
 use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
-use crate::lexer::token::{Located, TokenType};
+use crate::lexer::token::{Located, Positioned, TokenType};
 
 pub struct RequireRule {}
 
@@ -14,12 +14,15 @@ impl fmt::Display for RequireRule {
 }
 
 impl RequireRule {
-    fn parse_path_alias(&self, prefix: Option<Located<Path>>, parser: &mut ExprParser) -> Option<(Located<Path>, Option<Located<String>>)> {
+    fn parse_path_alias(&self, prefix: Option<&Located<Path>>, parser: &mut ExprParser) -> Option<Require> {
         let path = parser.apply_rule(PathRule {}, "require path", None)?;
         
-        let path = if let Some(mut prefix) = prefix {
-            prefix.extend(&path);
-            prefix
+        // Keep the position of the path as written, the prefix was only written once
+        let path = if let Some(prefix) = prefix {
+            let position = *path.get_position();
+            let mut full_path = prefix.as_ref().clone();
+            full_path.extend(&path);
+            Located::new(full_path, position)
         } else {
             path
         };
@@ -31,7 +34,27 @@ impl RequireRule {
             None
         };
 
-        Some((path, alias))
+        Some(Require::new(path, alias))
+    }
+
+    // Either a single path or a `{ ... }` group of them, which may be nested
+    fn parse_item(&self, prefix: Option<&Located<Path>>, parser: &mut ExprParser) -> Option<Vec<Require>> {
+        if parser.try_consume(TokenType::LeftCurly).is_none() {
+            return Some(vec![self.parse_path_alias(prefix, parser)?]);
+        }
+
+        let mut requires = Vec::new();
+
+        while parser.try_consume(TokenType::RightCurly).is_none() {
+            requires.extend(self.parse_item(prefix, parser)?);
+
+            if parser.try_consume(TokenType::Comma).is_none() {
+                parser.consume_or_diagnostic(TokenType::RightCurly)?;
+                break;
+            }
+        }
+
+        Some(requires)
     }
 }
 
@@ -47,18 +70,158 @@ impl ParseRule<Vec<Require>> for RequireRule {
             None
         };
 
+        parser.consume_or_diagnostic(TokenType::Require)?;
+
         let mut requires = Vec::new();
 
-        requires.push(self.parse_path_alias(from_path.clone(), parser)?);
+        if let Some(from_path) = &from_path && parser.try_consume(TokenType::Star).is_some() {
+            requires.push(Require::new_glob(from_path.clone()));
+        } else {
+            requires.extend(self.parse_item(from_path.as_ref(), parser)?);
 
-        while let Some(_) = parser.try_consume(TokenType::Comma) {
-            requires.push(self.parse_path_alias(from_path.clone(), parser)?);
+            while let Some(_) = parser.try_consume(TokenType::Comma) {
+                requires.extend(self.parse_item(from_path.as_ref(), parser)?);
+            }
         }
         
         parser.consume_or_diagnostic(TokenType::Semicolon);
 
-        Some(requires.into_iter().map(|(path, alias)| {
-            Require::new(path, alias)
-        }).collect::<Vec<Require>>())
+        Some(requires)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::{Token, PositionRange};
+    use crate::logger::CONSOLE_LOGGER;
+
+    fn create_token(token_type: TokenType) -> Token {
+        Token::new(token_type, PositionRange::zero())
+    }
+
+    fn identifier(name: &str) -> Token {
+        create_token(TokenType::Identifier(name.to_string()))
+    }
+
+    fn paths(requires: &[Require]) -> Vec<String> {
+        requires.iter().map(|require| require.path.to_string()).collect()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_require_with_alias() {
+        let rule = RequireRule {};
+        let tokens = vec![
+            create_token(TokenType::Require),
+            identifier("Math"),
+            create_token(TokenType::DoubleColon),
+            identifier("PI"),
+            create_token(TokenType::As),
+            identifier("pi"),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let requires = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(paths(&requires), vec!["Math::PI"]);
+        assert_eq!(requires[0].alias.as_ref().map(|alias| alias.to_string()), Some("pi".to_string()));
+        assert!(!requires[0].glob);
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for valid require");
+    }
+
+    #[test]
+    fn test_parse_from_require_glob() {
+        let rule = RequireRule {};
+        let tokens = vec![
+            create_token(TokenType::From),
+            identifier("CharUtil"),
+            create_token(TokenType::Require),
+            create_token(TokenType::Star),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let requires = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(paths(&requires), vec!["CharUtil"]);
+        assert!(requires[0].glob);
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for glob require");
+    }
+
+    #[test]
+    fn test_parse_from_require_group() {
+        // from a require { b::c, d as e, { f }, }, g;
+        let rule = RequireRule {};
+        let tokens = vec![
+            create_token(TokenType::From),
+            identifier("a"),
+            create_token(TokenType::Require),
+            create_token(TokenType::LeftCurly),
+            identifier("b"),
+            create_token(TokenType::DoubleColon),
+            identifier("c"),
+            create_token(TokenType::Comma),
+            identifier("d"),
+            create_token(TokenType::As),
+            identifier("e"),
+            create_token(TokenType::Comma),
+            create_token(TokenType::LeftCurly),
+            identifier("f"),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::Comma),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::Comma),
+            identifier("g"),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let requires = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(paths(&requires), vec!["a::b::c", "a::d", "a::f", "a::g"]);
+        assert_eq!(requires[1].alias.as_ref().map(|alias| alias.to_string()), Some("e".to_string()));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for grouped require");
+    }
+
+    #[test]
+    fn test_parse_glob_without_from() {
+        let rule = RequireRule {};
+        let tokens = vec![
+            create_token(TokenType::Require),
+            create_token(TokenType::Star),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        assert!(rule.parse(&mut parser).is_none());
+        assert!(!diagnostics.is_empty(), "Expected a diagnostic for a glob without a scope");
+    }
+
+    #[test]
+    fn test_parse_unclosed_group() {
+        let rule = RequireRule {};
+        let tokens = vec![
+            create_token(TokenType::From),
+            identifier("a"),
+            create_token(TokenType::Require),
+            create_token(TokenType::LeftCurly),
+            identifier("b"),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        assert!(rule.parse(&mut parser).is_none());
+        assert!(!diagnostics.is_empty(), "Expected a diagnostic for an unclosed group");
+    }
+}
@@ -1,9 +1,10 @@
 use std::fmt;
 
-use crate::ast::StructItem;
+use crate::ast::{StructItem, Visibility};
+use crate::diagnostic::ErrMsg;
 use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
-use crate::parser::rules::{constructor_item::ConstructorRule, parsed_type::ParsedTypeRule};
-use crate::lexer::token::TokenType;
+use crate::parser::rules::{constructor_item::ConstructorRule, destructor_item::DestructorRule, expr::ExprRule, parsed_type::ParsedTypeRule, visibility::VisibilityRule};
+use crate::lexer::token::{Positioned, TokenType};
 
 pub struct StructRule {}
 
@@ -15,11 +16,15 @@ impl fmt::Display for StructRule {
 
 impl ParseRule<StructItem> for StructRule {
     fn check_match(&self, mut cursor: ParserCursor) -> bool {
+        cursor.try_consume(TokenType::Pub);
+
         cursor.try_consume(TokenType::Struct).is_some()
     }
 
     fn parse(&self, parser: &mut ExprParser) -> Option<StructItem> {
         parser.begin_range();
+
+        let visibility = parser.apply_rule(VisibilityRule {}, "struct visibility", None)?;
         parser.try_consume(TokenType::Struct)?;
 
         let struct_identifier = parser.consume_or_diagnostic(TokenType::AnyIdentifier)?.unwrap_identifier();
@@ -27,25 +32,52 @@ impl ParseRule<StructItem> for StructRule {
         parser.consume_or_diagnostic(TokenType::LeftCurly);
 
         let mut members = Vec::new();
-        while let Some(member_type) = parser.apply_rule(ParsedTypeRule {}, "struct member type", None) {
+        loop {
+            let member_visibility = parser.apply_rule(VisibilityRule {}, "struct member visibility", None)?;
+
+            // Constructors cannot be marked `pub`, so a `pub` must be followed by a member
+            let err_msg = (member_visibility == Visibility::Public).then_some(ErrMsg::ExpectedType);
+
+            let Some(member_type) = parser.apply_rule(ParsedTypeRule {}, "struct member type", err_msg) else {
+                break;
+            };
 
             if let Some(token) = parser.consume_or_diagnostic(TokenType::AnyIdentifier) {
                 let member_identifier = token.unwrap_identifier();
+
+                let default_value = if parser.try_consume(TokenType::Assignment).is_some() {
+                    Some(parser.apply_rule(ExprRule {}, "struct member default", Some(ErrMsg::ExpectedExpression))?)
+                } else {
+                    None
+                };
+
                 parser.consume_or_diagnostic(TokenType::Semicolon);
 
-                members.push((member_type, member_identifier));
+                members.push((member_visibility, member_type, member_identifier, default_value));
             }
         }
         
         let mut constructors = Vec::new();
+        let mut destructor = None;
 
-        while let Some(constructor) = parser.apply_rule(ConstructorRule {}, "struct constructor", None) {
-            constructors.push(constructor);
+        loop {
+            if let Some(constructor) = parser.apply_rule(ConstructorRule {}, "struct constructor", None) {
+                constructors.push(constructor);
+            } else if let Some(parsed_destructor) = parser.apply_rule(DestructorRule {}, "struct destructor", None) {
+                if destructor.is_some() {
+                    let position = *parsed_destructor.get_position();
+                    parser.push_diagnostic(ErrMsg::DuplicateDestructor(struct_identifier.to_string()).make_diagnostic(position));
+                } else {
+                    destructor = Some(parsed_destructor);
+                }
+            } else {
+                break;
+            }
         }
 
         parser.consume_or_diagnostic(TokenType::RightCurly);
 
-        Some(StructItem::new(struct_identifier, members, constructors, parser.end_range()))
+        Some(StructItem::new(visibility, struct_identifier, members, constructors, destructor, parser.end_range()))
     }
 }
 
@@ -128,7 +160,7 @@ mod tests {
         let struct_item = result.unwrap();
         assert_eq!(*struct_item.name, "Point");
         assert_eq!(struct_item.members.len(), 1);
-        assert_eq!(*struct_item.members[0].1, "x");
+        assert_eq!(*struct_item.members[0].2, "x");
         assert_eq!(struct_item.constructors.len(), 0);
         assert!(diagnostics.is_empty(), "Expected no diagnostics for struct with single member");
     }
@@ -161,9 +193,9 @@ mod tests {
         let struct_item = result.unwrap();
         assert_eq!(*struct_item.name, "Rectangle");
         assert_eq!(struct_item.members.len(), 3);
-        assert_eq!(*struct_item.members[0].1, "width");
-        assert_eq!(*struct_item.members[1].1, "height");
-        assert_eq!(*struct_item.members[2].1, "visible");
+        assert_eq!(*struct_item.members[0].2, "width");
+        assert_eq!(*struct_item.members[1].2, "height");
+        assert_eq!(*struct_item.members[2].2, "visible");
         assert_eq!(struct_item.constructors.len(), 0);
         assert!(diagnostics.is_empty(), "Expected no diagnostics for struct with multiple members");
     }
@@ -272,8 +304,8 @@ mod tests {
         let struct_item = result.unwrap();
         assert_eq!(*struct_item.name, "Vehicle");
         assert_eq!(struct_item.members.len(), 2);
-        assert_eq!(*struct_item.members[0].1, "engine");
-        assert_eq!(*struct_item.members[1].1, "tires");
+        assert_eq!(*struct_item.members[0].2, "engine");
+        assert_eq!(*struct_item.members[1].2, "tires");
         assert!(diagnostics.is_empty(), "Expected no diagnostics for struct with custom types");
     }
 
@@ -441,4 +473,162 @@ mod tests {
         assert_eq!(struct_item.constructors.len(), 1);
         assert!(diagnostics.is_empty(), "Expected no diagnostics for complex struct");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_pub_struct_with_pub_member() {
+        let rule = StructRule {};
+        let tokens = vec![
+            create_token(TokenType::Pub),
+            create_token(TokenType::Struct),
+            create_token(TokenType::Identifier("Buffer".to_string())),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::Pub),
+            create_token(TokenType::Int),
+            create_token(TokenType::Identifier("len".to_string())),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::Int),
+            create_token(TokenType::Identifier("capacity".to_string())),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let cursor = ParserCursor { ptr: 0, tokens: &tokens };
+        assert!(rule.check_match(cursor));
+
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens.clone(), &mut diagnostics);
+
+        let struct_item = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(struct_item.visibility, Visibility::Public);
+        assert_eq!(struct_item.members[0].0, Visibility::Public);
+        assert_eq!(struct_item.members[1].0, Visibility::Private);
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for pub struct");
+    }
+
+    #[test]
+    fn test_parse_pub_without_member() {
+        let rule = StructRule {};
+        let tokens = vec![
+            create_token(TokenType::Struct),
+            create_token(TokenType::Identifier("Buffer".to_string())),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::Pub),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        rule.parse(&mut parser);
+
+        assert!(!diagnostics.is_empty(), "Expected diagnostic for 'pub' without a member");
+    }
+
+    #[test]
+    fn test_parse_member_default_values() {
+        let rule = StructRule {};
+        let tokens = vec![
+            create_token(TokenType::Struct),
+            create_token(TokenType::Identifier("Config".to_string())),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::Int),
+            create_token(TokenType::Identifier("retries".to_string())),
+            create_token(TokenType::Assignment),
+            create_token(TokenType::IntLiteral(3)),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::Char),
+            create_token(TokenType::Identifier("mode".to_string())),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let struct_item = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(struct_item.members.len(), 2);
+        assert!(matches!(struct_item.members[0].3, Some(crate::ast::ASTEnum::Literal(_))));
+        assert!(struct_item.members[1].3.is_none());
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for member default values");
+    }
+
+    #[test]
+    fn test_parse_member_missing_default_value() {
+        let rule = StructRule {};
+        let tokens = vec![
+            create_token(TokenType::Struct),
+            create_token(TokenType::Identifier("Config".to_string())),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::Int),
+            create_token(TokenType::Identifier("retries".to_string())),
+            create_token(TokenType::Assignment),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        assert!(rule.parse(&mut parser).is_none());
+        assert!(!diagnostics.is_empty(), "Expected a diagnostic for a missing default value");
+    }
+
+    #[test]
+    fn test_parse_struct_with_destructor() {
+        let rule = StructRule {};
+        let tokens = vec![
+            create_token(TokenType::Struct),
+            create_token(TokenType::Identifier("Buffer".to_string())),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::Int),
+            create_token(TokenType::Star),
+            create_token(TokenType::Identifier("data".to_string())),
+            create_token(TokenType::Semicolon),
+            create_token(TokenType::Tilda),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::DollarSign),
+            create_token(TokenType::LeftParen),
+            create_token(TokenType::RightParen),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let struct_item = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(struct_item.constructors.len(), 1);
+        assert!(struct_item.destructor.as_ref().is_some_and(|destructor| destructor.parameters.is_empty()));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for struct with destructor");
+    }
+
+    #[test]
+    fn test_parse_struct_with_duplicate_destructor() {
+        let rule = StructRule {};
+        let tokens = vec![
+            create_token(TokenType::Struct),
+            create_token(TokenType::Identifier("Buffer".to_string())),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::Tilda),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::Tilda),
+            create_token(TokenType::LeftCurly),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let struct_item = rule.parse(&mut parser).unwrap();
+
+        assert!(struct_item.destructor.is_some());
+        assert!(diagnostics.iter().any(|d| d.message.contains("duplicate destructor")));
+    }
+}
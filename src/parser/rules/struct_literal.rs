@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::ast::StructLiteral;
+use crate::diagnostic::ErrMsg;
+use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
+use crate::parser::rules::expr::ExprRule;
+use crate::lexer::token::TokenType;
+
+pub struct StructLiteralRule {}
+
+impl fmt::Display for StructLiteralRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StructLiteral")
+    }
+}
+
+impl ParseRule<StructLiteral> for StructLiteralRule {
+    // At least one field has to be written, otherwise `if done {}` would read as a literal
+    fn check_match(&self, mut cursor: ParserCursor) -> bool {
+        cursor.try_consume(TokenType::AnyIdentifier).is_some()
+            && cursor.try_consume(TokenType::LeftCurly).is_some()
+            && cursor.try_consume(TokenType::AnyIdentifier).is_some()
+            && cursor.try_consume(TokenType::Colon).is_some()
+    }
+
+    fn parse(&self, parser: &mut ExprParser) -> Option<StructLiteral> {
+        parser.begin_range();
+
+        let type_name = parser.consume_or_diagnostic(TokenType::AnyIdentifier)?.unwrap_identifier();
+        parser.consume_or_diagnostic(TokenType::LeftCurly)?;
+
+        let mut fields = Vec::new();
+
+        while parser.try_consume(TokenType::RightCurly).is_none() {
+            let field_name = parser.consume_or_diagnostic(TokenType::AnyIdentifier)?.unwrap_identifier();
+            parser.consume_or_diagnostic(TokenType::Colon)?;
+
+            let value = parser.apply_rule(ExprRule {}, "struct literal field", Some(ErrMsg::ExpectedExpression))?;
+            fields.push((field_name, value));
+
+            if parser.try_consume(TokenType::Comma).is_none() {
+                parser.consume_or_diagnostic(TokenType::RightCurly)?;
+                break;
+            }
+        }
+
+        Some(StructLiteral::new(type_name, fields, parser.end_range()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::ASTEnum;
+    use crate::lexer::token::{Token, PositionRange};
+    use crate::logger::CONSOLE_LOGGER;
+
+    fn create_token(token_type: TokenType) -> Token {
+        Token::new(token_type, PositionRange::zero())
+    }
+
+    fn identifier(name: &str) -> Token {
+        create_token(TokenType::Identifier(name.to_string()))
+    }
+
+    #[test]
+    fn test_struct_literal_rule_check_match() {
+        let rule = StructLiteralRule {};
+
+        let tokens = vec![identifier("Foo"), create_token(TokenType::LeftCurly), identifier("a"), create_token(TokenType::Colon), create_token(TokenType::EOF)];
+        assert!(rule.check_match(ParserCursor { ptr: 0, tokens: &tokens }));
+
+        // The condition and body of `if done { x = 1; }`
+        let tokens = vec![identifier("done"), create_token(TokenType::LeftCurly), identifier("x"), create_token(TokenType::Assignment), create_token(TokenType::EOF)];
+        assert!(!rule.check_match(ParserCursor { ptr: 0, tokens: &tokens }));
+
+        let tokens = vec![identifier("done"), create_token(TokenType::LeftCurly), create_token(TokenType::RightCurly), create_token(TokenType::EOF)];
+        assert!(!rule.check_match(ParserCursor { ptr: 0, tokens: &tokens }));
+    }
+
+    #[test]
+    fn test_parse_struct_literal() {
+        let rule = StructLiteralRule {};
+        let tokens = vec![
+            identifier("Foo"),
+            create_token(TokenType::LeftCurly),
+            identifier("a"),
+            create_token(TokenType::Colon),
+            create_token(TokenType::CharLiteral('a')),
+            create_token(TokenType::Comma),
+            identifier("b"),
+            create_token(TokenType::Colon),
+            identifier("x"),
+            create_token(TokenType::Plus),
+            create_token(TokenType::IntLiteral(1)),
+            create_token(TokenType::Comma),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        let literal = rule.parse(&mut parser).unwrap();
+
+        assert_eq!(*literal.type_name, "Foo");
+        assert_eq!(literal.fields.len(), 2);
+        assert_eq!(*literal.fields[0].0, "a");
+        assert!(matches!(literal.fields[0].1, ASTEnum::Literal(_)));
+        assert_eq!(*literal.fields[1].0, "b");
+        assert!(matches!(literal.fields[1].1, ASTEnum::Binary(_)));
+        assert!(diagnostics.is_empty(), "Expected no diagnostics for valid struct literal");
+    }
+
+    #[test]
+    fn test_parse_struct_literal_missing_comma() {
+        let rule = StructLiteralRule {};
+        let tokens = vec![
+            identifier("Foo"),
+            create_token(TokenType::LeftCurly),
+            identifier("a"),
+            create_token(TokenType::Colon),
+            create_token(TokenType::IntLiteral(1)),
+            identifier("b"),
+            create_token(TokenType::Colon),
+            create_token(TokenType::IntLiteral(2)),
+            create_token(TokenType::RightCurly),
+            create_token(TokenType::EOF),
+        ];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        assert!(rule.parse(&mut parser).is_none());
+        assert!(!diagnostics.is_empty(), "Expected a diagnostic for a missing comma");
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+
+use crate::ast::Visibility;
+use crate::parser::{ExprParser, ParseRule, ParserCursor, TokenCursor};
+use crate::lexer::token::TokenType;
+
+pub struct VisibilityRule {}
+
+impl fmt::Display for VisibilityRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Visibility")
+    }
+}
+
+impl ParseRule<Visibility> for VisibilityRule {
+    fn check_match(&self, _cursor: ParserCursor) -> bool {
+        true
+    }
+
+    // Items are private unless marked `pub`
+    fn parse(&self, parser: &mut ExprParser) -> Option<Visibility> {
+        match parser.try_consume(TokenType::Pub) {
+            Some(_) => Some(Visibility::Public),
+            None => Some(Visibility::Private),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::{Token, PositionRange};
+    use crate::logger::CONSOLE_LOGGER;
+
+    fn create_token(token_type: TokenType) -> Token {
+        Token::new(token_type, PositionRange::zero())
+    }
+
+    #[test]
+    fn test_parse_pub() {
+        let tokens = vec![create_token(TokenType::Pub), create_token(TokenType::Fn), create_token(TokenType::EOF)];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        assert_eq!((VisibilityRule {}).parse(&mut parser), Some(Visibility::Public));
+        assert!(parser.try_consume(TokenType::Fn).is_some());
+    }
+
+    #[test]
+    fn test_parse_private_by_default() {
+        let tokens = vec![create_token(TokenType::Fn), create_token(TokenType::EOF)];
+        let mut diagnostics = Vec::new();
+        let mut parser = ExprParser::new(&CONSOLE_LOGGER, tokens, &mut diagnostics);
+
+        assert_eq!((VisibilityRule {}).parse(&mut parser), Some(Visibility::Private));
+        assert!(parser.try_consume(TokenType::Fn).is_some());
+    }
+}
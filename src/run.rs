@@ -2,16 +2,28 @@ use std::collections::HashSet;
 use std::sync::Arc;
 use std::env;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use inkwell::context::Context;
 
 use crate::ast::Scope;
-use crate::compiler::Compiler;
+use crate::codegen::CodeGen;
+use crate::compiler::{Compiler, CompilerOptions};
+use crate::compiler::const_eval::ConstEvaluator;
+use crate::compiler::control_flow::ControlFlowChecker;
+use crate::compiler::definite_assignment::DefiniteAssignmentChecker;
 use crate::compiler::local_resolver::LocalResolver;
-use crate::compiler::visit::ASTVisitor;
-use crate::diagnostic::Diagnostic;
+use crate::compiler::lvalue::LvalueChecker;
+use crate::compiler::structs::StructChecker;
+use crate::compiler::type_checker::TypeChecker;
+use crate::compiler::visibility::VisibilityChecker;
+use crate::compiler::visit::{ASTVisitor, LocalResolved};
+use crate::diagnostic::{Diagnostic, DiagnosticSeverity, ErrMsg};
 use crate::logger::{CONSOLE_LOGGER, FileLogger, Log};
+use crate::modules::ModuleGraph;
 use crate::parser::ExprParser;
 use crate::lexer::Lexer;
+use crate::lexer::token::{PositionRange, TokenType};
 
 static LOG_SOURCE: &str = "Runner";
 
@@ -20,24 +32,52 @@ enum ParseError {
     Diagnostics(Vec<Diagnostic>),
 }
 
+// Returns whether any of the diagnostics is an error, warnings don't stop compilation
 fn log_diagnostics(scope: &Scope, diagnostics: &[Diagnostic]) -> bool {
     for diagnostic in diagnostics {
-        LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("In scope {}: {}", scope.path.to_string(), diagnostic));
+        LOG_SOURCE.log(diagnostic.severity.into(), &CONSOLE_LOGGER, format!("In scope {}: {}", scope.path.to_string(), diagnostic));
     }
 
-    !diagnostics.is_empty()
+    diagnostics.iter().any(|diagnostic| matches!(diagnostic.severity, DiagnosticSeverity::Error))
+}
+
+// Checks that only need the parsed tree, returns whether the scope is free of errors
+async fn check_scope<'ast>(scope: &'ast Scope, compiler: &Compiler<'ast>) -> bool {
+    let symbol_table = compiler.symbol_tables.get(scope.path.segments.as_slice()).unwrap();
+
+    let mut diagnostics = Vec::new();
+    ControlFlowChecker::new(&mut diagnostics).visit_scope(scope);
+    VisibilityChecker::new(compiler, symbol_table, &mut diagnostics).visit_scope(scope);
+    ConstEvaluator::new(compiler, symbol_table, &mut diagnostics).visit_scope(scope);
+
+    !log_diagnostics(scope, &diagnostics)
 }
 
-async fn compile_scope<'ast>(scope: &'ast Scope, compiler: &Compiler<'ast>) {
+// Resolves and checks the bodies of a scope, returns the resolved scope if it is free of errors
+async fn compile_scope<'ast>(scope: &'ast Scope, compiler: &Compiler<'ast>) -> Option<&'ast Scope<LocalResolved>> {
     let path = scope.path.segments.iter().map(|s| s.clone()).collect::<Vec<_>>();
     let log_target = FileLogger::new(&Path::new("modules").join(Path::new(&path.join("_"))).with_extension("log"));
     let symbol_table = compiler.symbol_tables.get(path.as_slice()).unwrap();
-    
+
     let mut local_resolver: LocalResolver<'_, 'ast> = LocalResolver::new(&log_target, &compiler, symbol_table);
 
     let resolve_confirmation = local_resolver.visit_scope(&scope);
 
     let local_resolved = resolve_confirmation.transmute_scope(scope);
+
+    let mut diagnostics = Vec::new();
+    TypeChecker::new(compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+    StructChecker::new(compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+    LvalueChecker::new(compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+    DefiniteAssignmentChecker::new(compiler, symbol_table, &mut diagnostics).visit_scope(local_resolved);
+    diagnostics.extend(compiler.unused_requires(scope, symbol_table));
+
+    (!log_diagnostics(scope, &diagnostics)).then_some(local_resolved)
+}
+
+// The same file can be reached both from the command line and through a source root
+fn canonical_path(file: &Path) -> PathBuf {
+    file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
 }
 
 async fn read_file(file: impl AsRef<Path>) -> io::Result<String> {
@@ -67,47 +107,86 @@ async fn parse_file(file_path: impl AsRef<Path>) -> Result<Scope, ParseError> {
     }
 
     let parser = ExprParser::new(&file_logger, tokens, &mut diagnostics);
-    let scopes = parser.parse();
+    let scope = parser.parse();
 
-    if !diagnostics.is_empty() {
-        return Err(ParseError::Diagnostics(diagnostics));
-    }
+    match scope {
+        Some(scope) if diagnostics.is_empty() => Ok(scope),
+        _ => {
+            // A failed rule normally leaves its own diagnostic, but the file must never be accepted without one
+            if diagnostics.is_empty() {
+                diagnostics.push(ErrMsg::ExpectedToken(TokenType::Scope).make_diagnostic(PositionRange::zero()));
+            }
 
-    Ok(scopes.unwrap())
+            Err(ParseError::Diagnostics(diagnostics))
+        }
+    }
 }  
 
-pub async fn get_scopes(files: &[String]) -> Option<Vec<Scope>> {
-    let mut thread_handles = Vec::new();
+// Every flattened scope comes with the file it was parsed from
+pub async fn get_scopes(files: &[String], source_roots: &[PathBuf]) -> Option<Vec<(PathBuf, Scope)>> {
+    let working_dir = [PathBuf::from(".")];
+    let source_roots = if source_roots.is_empty() { &working_dir } else { source_roots };
 
-    for file in files {
-        let future = parse_file(file.to_string());
-        let thread_handle = tokio::spawn(future);
-        thread_handles.push((file, thread_handle));
-    }
+    let mut graph = ModuleGraph::new();
+    let mut pending = files.iter().map(PathBuf::from).collect::<Vec<_>>();
+    let mut seen = pending.iter().map(|file| canonical_path(file)).collect::<HashSet<_>>();
 
     let mut scopes = Vec::new();
     let mut success = true;
 
-    for (file, thread_handle) in thread_handles {
-        match thread_handle.await.unwrap() {
-            Ok(scope) => {
-                scopes.push(scope);
-            },
-            Err(parse_error) => {
-                success = false;
-
-                match parse_error {
-                    ParseError::Diagnostics(diagnostics) => {
-                        for diagnostic in diagnostics {
-                            LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("In file {}: {}", file, diagnostic));
+    // Files are parsed in rounds, each one loading the files of the scopes the previous round required
+    while !pending.is_empty() {
+        let mut thread_handles = Vec::new();
+
+        for file in pending.drain(..) {
+            let future = parse_file(file.clone());
+            let thread_handle = tokio::spawn(future);
+            thread_handles.push((file, thread_handle));
+        }
+
+        let mut parsed_files = Vec::new();
+
+        for (file, thread_handle) in thread_handles {
+            match thread_handle.await.unwrap() {
+                Ok(scope) => {
+                    parsed_files.push(graph.add_file(file.clone(), &scope));
+                    scopes.push((file, scope));
+                },
+                Err(parse_error) => {
+                    success = false;
+
+                    match parse_error {
+                        ParseError::Diagnostics(diagnostics) => {
+                            for diagnostic in diagnostics {
+                                LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("In file {}: {}", file.display(), diagnostic));
+                            }
+                        },
+                        ParseError::IoError(err) => {
+                            LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("I/O error in file {}: {}", file.display(), err));
                         }
-                    },
-                    ParseError::IoError(err) => {
-                        LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("I/O error in file {}: {}", file, err));
                     }
                 }
             }
         }
+
+        // Requires that are still missing afterwards are reported by the compiler
+        for file in parsed_files {
+            for path in graph.missing_requires(file) {
+                let candidate = ModuleGraph::candidate_files(source_roots, path).into_iter()
+                    .find(|candidate| candidate.is_file());
+
+                if let Some(candidate) = candidate && seen.insert(canonical_path(&candidate)) {
+                    pending.push(candidate);
+                }
+            }
+        }
+    }
+
+    for cycle in graph.find_cycles() {
+        success = false;
+
+        let chain = cycle.iter().map(|file| file.display().to_string()).collect::<Vec<_>>();
+        LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("Import cycle detected: {}", chain.join(" -> ")));
     }
 
     if !success {
@@ -118,11 +197,13 @@ pub async fn get_scopes(files: &[String]) -> Option<Vec<Scope>> {
     let mut flattened_scopes = Vec::new();
     let mut path_set = HashSet::new();
 
-    for scope in scopes.into_iter().map(|scope| scope.flatten()).flatten() {
-        flattened_scopes.push(scope);
+    for (file, scope) in scopes {
+        for scope in scope.flatten() {
+            flattened_scopes.push((file.clone(), scope));
+        }
     }
 
-    for scope in &flattened_scopes {
+    for (_, scope) in &flattened_scopes {
         if !path_set.insert(&*scope.path) {
             LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("Duplicate scope path detected: {}", scope.path.to_string()));
         }
@@ -131,27 +212,88 @@ pub async fn get_scopes(files: &[String]) -> Option<Vec<Scope>> {
     Some(flattened_scopes)
 }
 
-pub async fn run_files(files: Vec<String>) {
+// Returns whether the output file was written
+pub async fn run_files(files: Vec<String>, options: CompilerOptions) -> bool {
     // Leak the AST because we need it for the whole program lifetime
-    let flattened_scopes = match get_scopes(&files).await {
+    let flattened_scopes = match get_scopes(&files, &options.source_roots).await {
         Some(scopes) => scopes,
-        None => return,
+        None => return false,
     }.leak();
     
     // Convert to & from &mut to allow Copy into async closure
     // Leak the compiler because we need it for the whole program lifetime
-    let compiler = Box::leak(Box::new(Compiler::new(flattened_scopes.iter()))) as &Compiler;
+    let compiler = Box::leak(Box::new(Compiler::new(flattened_scopes.iter().map(|(_, scope)| scope), options))) as &Compiler;
+
+    for diagnostic in &compiler.diagnostics {
+        LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("{}", diagnostic));
+    }
+
+    if !compiler.diagnostics.is_empty() {
+        return false;
+    }
+
+    // Every stage runs on all scopes in parallel, and waits for the previous one to succeed everywhere
+    let thread_handles = flattened_scopes.iter()
+        .map(|(_, scope)| tokio::spawn(check_scope(scope, compiler)))
+        .collect::<Vec<_>>();
+
+    if !join_all(thread_handles).await {
+        return false;
+    }
+
+    // Items can refer to the types of any scope, so they all get theirs before bodies are checked
+    let diagnostics = compiler.register_types(flattened_scopes.iter().map(|(_, scope)| scope));
+
+    for diagnostic in &diagnostics {
+        LOG_SOURCE.log_error(&CONSOLE_LOGGER, format!("{}", diagnostic));
+    }
+
+    if !diagnostics.is_empty() {
+        return false;
+    }
+
+    let thread_handles = flattened_scopes.iter()
+        .map(|(file, scope)| (file, tokio::spawn(compile_scope(scope, compiler))))
+        .collect::<Vec<_>>();
 
-    let mut thread_handles = Vec::new();
+    let mut resolved_scopes = Vec::new();
 
-    for scope in flattened_scopes.iter() {
-        let future = compile_scope(scope, compiler);
-        let thread_handle = tokio::spawn(future);
-        
-        thread_handles.push(thread_handle);
+    for (file, handle) in thread_handles {
+        resolved_scopes.push((file.as_path(), handle.await.ok().flatten()));
     }
 
+    let Some(resolved_scopes) = resolved_scopes.into_iter()
+        .map(|(file, scope)| scope.map(|scope| (file, scope)))
+        .collect::<Option<Vec<_>>>() else {
+        return false;
+    };
+
+    // The whole program goes into one module, so code generation starts once every scope passed
+    let log_target = FileLogger::new(&Path::new("codegen").with_extension("log"));
+    let context = Context::create();
+
+    let mut codegen = CodeGen::new(&log_target, &context, compiler);
+    codegen.compile(&resolved_scopes);
+
+    let output_file = compiler.options.output_file.clone()
+        .unwrap_or_else(|| PathBuf::from(compiler.options.emit.default_output()));
+
+    match codegen.emit(&output_file) {
+        Ok(()) => true,
+        Err(e) => {
+            LOG_SOURCE.log_error(&CONSOLE_LOGGER, e);
+            false
+        },
+    }
+}
+
+// Waits for every handle, returns whether all of them succeeded
+async fn join_all(thread_handles: Vec<tokio::task::JoinHandle<bool>>) -> bool {
+    let mut success = true;
+
     for handle in thread_handles {
-        let _ = handle.await;
+        success &= handle.await.unwrap_or(false);
     }
-}
\ No newline at end of file
+
+    success
+}